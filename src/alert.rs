@@ -15,6 +15,7 @@ impl Plugin for AlertPlugin {
 pub struct SpawnAlertEvent{
     pub position: Vec2,
     pub attack_alert: bool,
+    pub spotted_by: Option<Entity>, //моб, который заметил цель - будит соседей
 }
 
 #[derive(Component)]
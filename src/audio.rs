@@ -12,15 +12,29 @@ impl Plugin for AudioPlugin {
 
 #[derive(Event)]
 pub struct PlayAudioEvent {
-    filename: String
+    filename: String,
+    pub source: Option<SoundSource>,
+}
+
+//откуда звук и насколько далеко его слышат мобы
+#[derive(Clone, Copy)]
+pub struct SoundSource {
+    pub position: Vec2,
+    pub radius: f32,
 }
 
 impl PlayAudioEvent {
     pub fn from_file(filename: &str) -> Self {
         Self {
-            filename: filename.to_string()
+            filename: filename.to_string(),
+            source: None,
         }
     }
+
+    pub fn at(mut self, position: Vec2, radius: f32) -> Self {
+        self.source = Some(SoundSource { position, radius });
+        self
+    }
 }
 
 fn play_sfx(
@@ -1,7 +1,18 @@
+use std::collections::HashMap;
+
 use avian2d::prelude::ExternalForce;
 use bevy::prelude::*;
 
-use crate::{mobs::Mob, utils::pulsate};
+use crate::{
+    exp_orb::{ExpOrb, ExpOrbDrop},
+    friend::Friend,
+    health::{Health, Hit},
+    health_tank::HealthTank,
+    mobs::Mob,
+    player::Player,
+    projectile::{release_projectile, Hostile, Projectile},
+    utils::pulsate,
+};
 
 pub struct BlackHolePlugin;
 
@@ -16,18 +27,29 @@ impl Plugin for BlackHolePlugin {
                 move_black_hole,
                 pulsate::<BlackHole>,
                 pull_mobs,
+                pull_projectiles,
+                pull_pickups,
+                damage_core,
                 despawn_black_hole_on_timer,
-            ),
+            )
+                .chain(),
         );
     }
 }
 
+//в ядре снаряды пропадают, а мобы получают урон
+const CORE_RADIUS: f32 = 12.;
+const CORE_TICK: f32 = 0.25;
+
 #[derive(Event)]
 pub struct SpawnBlackHoleEvent {
     pub spawn_pos: Vec3,
     pub target_pos: Vec3,
     pub lifetime: f32,
     pub strength: f32,
+    pub radius: f32,
+    pub falloff: f32, //степень затухания силы к краю, 0 - одинаково по всему радиусу
+    pub core_damage: i32,
 }
 
 #[derive(Component)]
@@ -35,6 +57,23 @@ pub struct BlackHole {
     target_pos: Vec3,
     timer: Timer,
     strength: f32,
+    radius: f32,
+    falloff: f32,
+    core_damage: i32,
+    core_tick: Timer,
+    //какую силу дыра приложила в этом кадре, чтобы при исчезновении снять только ее
+    applied: HashMap<Entity, Vec2>,
+}
+
+impl BlackHole {
+    //0 за радиусом, 1 в центре
+    fn pull(&self, distance: f32) -> f32 {
+        if distance > self.radius {
+            return 0.;
+        }
+
+        (1. - distance / self.radius).powf(self.falloff)
+    }
 }
 
 fn spawn_black_hole(
@@ -53,6 +92,11 @@ fn spawn_black_hole(
                 target_pos: ev.target_pos,
                 timer: Timer::from_seconds(ev.lifetime, TimerMode::Once),
                 strength: ev.strength,
+                radius: ev.radius,
+                falloff: ev.falloff,
+                core_damage: ev.core_damage,
+                core_tick: Timer::from_seconds(CORE_TICK, TimerMode::Repeating),
+                applied: HashMap::new(),
             });
     }
 }
@@ -66,17 +110,127 @@ fn move_black_hole(mut black_hole_query: Query<(&mut Transform, &BlackHole)>, ti
     }
 }
 
+//дыра - заклинание игрока, призванных не трогает
 fn pull_mobs(
-    mut black_hole_query: Query<(&Transform, &BlackHole)>,
-    mut mob_query: Query<(&mut ExternalForce, &Transform), With<Mob>>,
+    mut black_hole_query: Query<(&Transform, &mut BlackHole)>,
+    mut mob_query: Query<(Entity, &mut ExternalForce, &Transform), (With<Mob>, Without<Friend>)>,
+) {
+    for (hole_tf, mut hole) in black_hole_query.iter_mut() {
+        hole.applied.clear();
+
+        for (mob_e, mut force, mob_tf) in mob_query.iter_mut() {
+            let pull = hole.pull(hole_tf.translation.distance(mob_tf.translation));
+            if pull <= 0. {
+                continue;
+            }
+
+            let direction = (hole_tf.translation - mob_tf.translation)
+                .truncate()
+                .normalize_or_zero();
+            let applied = direction * hole.strength * pull;
+
+            force.apply_force(applied).with_persistence(false);
+            hole.applied.insert(mob_e, applied);
+        }
+    }
+}
+
+//вражеские снаряды заворачивают в дыру и пропадают в ядре
+fn pull_projectiles(
+    mut commands: Commands,
+    black_hole_query: Query<(&Transform, &BlackHole)>,
+    mut projectile_query: Query<
+        (Entity, &Transform, &mut Projectile),
+        (With<Hostile>, Without<BlackHole>),
+    >,
+    time: Res<Time>,
+) {
+    for (hole_tf, hole) in black_hole_query.iter() {
+        for (proj_e, proj_tf, mut projectile) in projectile_query.iter_mut() {
+            let distance = hole_tf.translation.truncate().distance(proj_tf.translation.truncate());
+
+            if distance <= CORE_RADIUS {
+                projectile.spent = true;
+                commands.entity(proj_e).add(release_projectile);
+                continue;
+            }
+
+            let pull = hole.pull(distance);
+            if pull <= 0. {
+                continue;
+            }
+
+            let to_hole = (hole_tf.translation - proj_tf.translation)
+                .truncate()
+                .normalize_or_zero();
+            //сила дыры рассчитана на мобов, снаряды доворачиваем долей от нее
+            projectile.direction = (projectile.direction
+                + to_hole * pull * hole.strength / 1_000. * time.delta_seconds() * 4.)
+                .normalize_or_zero();
+            projectile.angle = projectile.direction.to_angle();
+        }
+    }
+}
+
+//опыт и банки здоровья, попавшие в радиус, летят к игроку
+fn pull_pickups(
+    black_hole_query: Query<(&Transform, &BlackHole)>,
+    mut pickup_query: Query<
+        &mut Transform,
+        (
+            Or<(With<ExpOrb>, With<HealthTank>)>,
+            Without<ExpOrbDrop>,
+            Without<BlackHole>,
+            Without<Player>,
+        ),
+    >,
+    player_query: Query<&Transform, (With<Player>, Without<BlackHole>)>,
+    time: Res<Time>,
+) {
+    let Ok(player_tf) = player_query.get_single() else {
+        return;
+    };
+
+    for mut pickup_tf in pickup_query.iter_mut() {
+        let pull = black_hole_query
+            .iter()
+            .map(|(hole_tf, hole)| {
+                hole.pull(hole_tf.translation.truncate().distance(pickup_tf.translation.truncate()))
+            })
+            .fold(0., f32::max);
+
+        if pull <= 0. {
+            continue;
+        }
+
+        let goal = player_tf.translation.truncate().extend(pickup_tf.translation.z);
+        pickup_tf.translation = pickup_tf
+            .translation
+            .move_towards(goal, (100. + 300. * pull) * time.delta_seconds());
+    }
+}
+
+fn damage_core(
+    mut black_hole_query: Query<(&Transform, &mut BlackHole)>,
+    mut mob_query: Query<(&Transform, &mut Health), (With<Mob>, Without<Friend>)>,
+    time: Res<Time>,
 ) {
-    for (hole_tf, hole) in black_hole_query.iter_mut() {
-        for (mut force, mob_tf) in mob_query.iter_mut() {
-            if hole_tf.translation.distance(mob_tf.translation) <= 64. {
-                let direction = (hole_tf.translation - mob_tf.translation)
-                    .truncate()
-                    .normalize_or_zero();
-                force.apply_force(direction * hole.strength).with_persistence(false);
+    for (hole_tf, mut hole) in black_hole_query.iter_mut() {
+        hole.core_tick.tick(time.delta());
+
+        if !hole.core_tick.just_finished() || hole.core_damage <= 0 {
+            continue;
+        }
+
+        for (mob_tf, mut health) in mob_query.iter_mut() {
+            let to_core = (hole_tf.translation - mob_tf.translation).truncate();
+
+            if to_core.length() <= CORE_RADIUS * 2. {
+                health.hit_queue.push(Hit {
+                    damage: hole.core_damage,
+                    element: None,
+                    direction: to_core.normalize_or_zero().extend(0.),
+                });
             }
         }
     }
@@ -92,9 +246,11 @@ fn despawn_black_hole_on_timer(
         black_hole.timer.tick(time.delta());
 
         if black_hole.timer.just_finished() {
-
-            for mut external_force in mob_query.iter_mut() {
-                external_force.clear();
+            //снимаем только свою силу, чужие оставляем
+            for (mob_e, applied) in black_hole.applied.iter() {
+                if let Ok(mut external_force) = mob_query.get_mut(*mob_e) {
+                    external_force.apply_force(-*applied);
+                }
             }
 
             commands.entity(entity).despawn();
@@ -1,5 +1,5 @@
 use crate::{
-    camera::YSort, gamemap::{Floor, Map, Tile, TileType, Wall, ROOM_SIZE, TILE_SIZE}, GameLayer, GameState
+    camera::YSort, chapter::ChapterManager, gamemap::{Floor, Map, Tile, TileType, Wall, ROOM_SIZE, TILE_SIZE}, hazards::{spawn_hazard_tile, HazardType}, mobs::MobType, obstacles::CorpseSpawnEvent, pathfinding::Graph, utils::get_random_index_with_weight, GameLayer, GameState
 };
 use avian2d::prelude::*;
 use bevy::prelude::*;
@@ -10,30 +10,112 @@ impl Plugin for BossRoomPlugin {
         app.add_systems(OnEnter(GameState::LoadingBoss), spawn_boss_room);
     }
 }
-pub fn spawn_boss_room(mut game_map: ResMut<Map>,asset_server: Res<AssetServer>, mut commands: Commands) {
+
+//арена своего босса для каждой главы, координаты - смещения от центра комнаты
+struct ArenaKit {
+    chapter: Option<u8>, //None - текстуры хаба, арена колдуна
+    pillars: Vec<(i32, i32)>,
+    hazard: Option<HazardType>,
+    hazard_tiles: Vec<(i32, i32)>,
+    graves: Vec<((i32, i32), MobType)>,
+}
+
+impl ArenaKit {
+    fn for_chapter(chapter: u8) -> Self {
+        match chapter {
+            //пустыня: зыбучие пески по углам, голем прячется под ними
+            1 => Self {
+                chapter: Some(1),
+                pillars: vec![],
+                hazard: Some(HazardType::Quicksand),
+                hazard_tiles: square_patches(&[(-5, -5), (4, -5), (-5, 4), (4, 4)]),
+                graves: vec![],
+            },
+            //джунгли: колонны-укрытия от лоз и лужи
+            2 => Self {
+                chapter: Some(2),
+                pillars: vec![(-4, -4), (4, -4), (-4, 4), (4, 4)],
+                hazard: Some(HazardType::Puddle),
+                hazard_tiles: square_patches(&[(-1, -6), (-1, 5)]),
+                graves: vec![],
+            },
+            //ад: лава по краям и могилы, которые лорд некромантов поднимает
+            3 => Self {
+                chapter: Some(3),
+                pillars: vec![(-2, 0), (2, 0)],
+                hazard: Some(HazardType::Lava),
+                hazard_tiles: vec![(-7, -7), (-7, 7), (7, -7), (7, 7), (-6, -7), (6, 7), (-7, 6), (7, -6)],
+                graves: vec![
+                    ((-5, -3), MobType::Knight),
+                    ((5, -3), MobType::Knight),
+                    ((-5, 3), MobType::SkeletMage),
+                    ((5, 3), MobType::SkeletRanger),
+                    ((0, -5), MobType::Knight),
+                    ((0, 5), MobType::FireElemental),
+                ],
+            },
+            _ => Self {
+                chapter: None,
+                pillars: vec![],
+                hazard: None,
+                hazard_tiles: vec![],
+                graves: vec![],
+            },
+        }
+    }
+
+    fn wall_texture(&self, top: bool) -> String {
+        match (self.chapter, top) {
+            (Some(chapter), true) => format!("textures/t_wall_top_{}.png", chapter),
+            (Some(chapter), false) => format!("textures/t_wall_{}.png", chapter),
+            (None, true) => "textures/t_wall_top_hub.png".to_string(),
+            (None, false) => "textures/t_wall_hub.png".to_string(),
+        }
+    }
+}
+
+//пятна 2x2 с левым нижним углом в каждой точке
+fn square_patches(corners: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    corners
+        .iter()
+        .flat_map(|(x, y)| [(*x, *y), (x + 1, *y), (*x, y + 1), (x + 1, y + 1)])
+        .collect()
+}
+
+pub fn spawn_boss_room(
+    mut game_map: ResMut<Map>,
+    mut graph: ResMut<Graph>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    chapter_manager: Res<ChapterManager>,
+    mut ev_corpse_spawn: EventWriter<CorpseSpawnEvent>,
+    mut commands: Commands,
+) {
     let lower = ROOM_SIZE / 2 - 8;
     let upper = ROOM_SIZE / 2 + 8;
-                    
-    commands.insert_resource(ClearColor(Color::srgb(69. / 255., 35. / 255., 13. / 255.)));
+    let center = ROOM_SIZE / 2;
+
+    let arena = ArenaKit::for_chapter(chapter_manager.get_current_chapter());
+
+    //карта прошлого уровня арене не нужна
+    game_map.map.clear();
+
+    commands.insert_resource(ClearColor(chapter_manager.get_current_color()));
+
+    let floor_layout = texture_atlas_layouts.add(TextureAtlasLayout::from_grid(UVec2::splat(32), 4, 1, None, None));
 
     for x in lower..=upper {
         for y in lower..=upper {
-            if x == lower || x == upper || y == lower || y == upper {
-                let texture_path = {
-                    if y > lower {
-                        if y == upper && lower < x && x < upper {
-                            "textures/t_wall_top_hub.png"
-                        } else {
-                            "textures/t_wall_hub.png"
-                        }
-                    } else {
-                        "textures/t_wall_hub.png"
-                    }
-                };
+            let is_pillar = arena.pillars.contains(&(x - center, y - center));
+
+            if x == lower || x == upper || y == lower || y == upper || is_pillar {
+                let top = (y > lower && y == upper && lower < x && x < upper) || is_pillar;
+
+                game_map.map.insert((x as u16, y as u16), Tile::new(TileType::Wall, 0));
 
                 commands
                     .spawn(SpriteBundle {
-                        texture: asset_server.load(texture_path),
+                        texture: asset_server.load(arena.wall_texture(top)),
                         transform: Transform::from_xyz(
                             TILE_SIZE * x as f32,
                             TILE_SIZE * y as f32,
@@ -46,28 +128,43 @@ pub fn spawn_boss_room(mut game_map: ResMut<Map>,asset_server: Res<AssetServer>,
                     .insert(Wall)
                     .insert(YSort(16.0))
                     .insert(CollisionLayers::new(GameLayer::Wall, [GameLayer::Enemy, GameLayer::Player, GameLayer::Projectile]));
-                    
+
             } else {
                 game_map.map.insert((x as u16, y as u16), Tile::new(TileType::Floor, 0));
-                
-                let floor = commands.spawn(SpriteBundle {
+
+                let transform = Transform::from_xyz(
+                    TILE_SIZE * x as f32,
+                    TILE_SIZE * y as f32,
+                    -100.0,
+                );
+
+                let floor = match arena.chapter {
+                    Some(chapter) => commands.spawn((
+                        SpriteBundle {
+                            texture: asset_server.load(format!("textures/t_floor_{}.png", chapter)),
+                            transform,
+                            ..default()
+                        },
+                        TextureAtlas {
+                            layout: floor_layout.clone(),
+                            index: get_random_index_with_weight(vec![10, 3, 2, 1]),
+                        },
+                    )),
+                    None => commands.spawn(SpriteBundle {
                         texture: asset_server.load("textures/t_floor_hub.png"),
-                        transform: Transform::from_xyz(
-                            TILE_SIZE * x as f32,
-                            TILE_SIZE * y as f32,
-                            -100.0,
-                        ),
+                        transform,
                         ..default()
-                    })
-                    .insert(Floor)
-                    .id();
+                    }),
+                }
+                .insert(Floor)
+                .id();
 
                     if y == upper-1 {
                         commands.entity(floor).with_children(|parent| {
                             parent.spawn(SpriteBundle {
                                 texture: asset_server.load("textures/t_shadow.png"),
                                 transform: Transform::from_xyz(
-                                    0.0, 
+                                    0.0,
                                     0.0,
                                     0.1,
                                 ),
@@ -78,4 +175,26 @@ pub fn spawn_boss_room(mut game_map: ResMut<Map>,asset_server: Res<AssetServer>,
             }
         }
     }
+
+    if let Some(hazard) = arena.hazard {
+        for (dx, dy) in arena.hazard_tiles.iter() {
+            let tile = ((center + dx) as u16, (center + dy) as u16);
+            if game_map.map.get(&tile).is_some_and(|tile| tile.tiletype == TileType::Floor) {
+                spawn_hazard_tile(&mut commands, &mut game_map, tile, hazard);
+            }
+        }
+    }
+
+    for ((dx, dy), mob_type) in arena.graves.iter() {
+        ev_corpse_spawn.send(CorpseSpawnEvent {
+            pos: Vec3::new(
+                TILE_SIZE * (center + dx) as f32,
+                TILE_SIZE * (center + dy) as f32,
+                0.05,
+            ),
+            mob_type: mob_type.clone(),
+        });
+    }
+
+    graph.rebuild_from_map(&game_map);
 }
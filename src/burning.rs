@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+
+use crate::{
+    elements::{ElementResistance, ElementType},
+    health::{Health, Hit},
+    mobs::Mob,
+};
+
+pub struct BurningPlugin;
+
+impl Plugin for BurningPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, handle_burning);
+    }
+}
+
+//горит и получает огненный урон раз в полсекунды, повторный поджог только продлевает горение
+#[derive(Component)]
+pub struct Burning {
+    damage: u32,
+    tick_timer: Timer,
+    effect_timer: Timer,
+}
+
+impl Burning {
+    pub fn new(damage: u32, duration: f32) -> Self {
+        Self {
+            damage,
+            tick_timer: Timer::from_seconds(0.5, TimerMode::Repeating),
+            effect_timer: Timer::from_seconds(duration, TimerMode::Once),
+        }
+    }
+
+    pub fn refresh(&mut self, damage: u32, duration: f32) {
+        self.damage = self.damage.max(damage);
+        self.effect_timer = Timer::from_seconds(duration, TimerMode::Once);
+    }
+}
+
+fn handle_burning(
+    mut commands: Commands,
+    mut burning_query: Query<(
+        &mut Burning,
+        Entity,
+        &mut Health,
+        Option<&ElementResistance>,
+        Has<Mob>,
+    )>,
+    time: Res<Time>,
+) {
+    for (mut burning, entity, mut health, resistance, is_mob) in burning_query.iter_mut() {
+        burning.effect_timer.tick(time.delta());
+        burning.tick_timer.tick(time.delta());
+
+        if burning.tick_timer.just_finished() {
+            //сопротивление игрока считается в hit_player, мобов - здесь
+            let mut damage = burning.damage as i32;
+            if let Some(resistance) = resistance.filter(|_| is_mob) {
+                resistance.calculate_for(&mut damage, Some(ElementType::Fire));
+            }
+
+            health.hit_queue.push(Hit {
+                damage,
+                element: Some(ElementType::Fire),
+                direction: Vec3::ZERO,
+            });
+        }
+
+        if burning.effect_timer.just_finished() {
+            commands.entity(entity).remove::<Burning>();
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//потоковые заклинания: огнемет и водяной луч работают, пока зажата кнопка,
+//тратят ману каждую секунду и обрываются при отпускании, оглушении или нехватке маны
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    audio::PlayAudioEvent,
+    elements::{ElementBar, ElementType, Spell},
+    item::ItemPickupAnimation,
+    mana::{CastDenied, CastDeniedEvent, Mana},
+    mouse_position::MouseCoords,
+    player::{Player, PlayerStats},
+    projectile::{ProjectileModifier, SpawnProjectileEvent, Trajectory},
+    stun::Stun,
+    wand::{EquippedWand, Wand},
+    GameState,
+};
+
+pub struct ChannelPlugin;
+
+impl Plugin for ChannelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<StartChannelEvent>()
+            .add_systems(OnExit(GameState::InGame), stop_all_channels)
+            .add_systems(
+                Update,
+                (start_channel, channel_spell)
+                    .chain()
+                    .run_if(in_state(GameState::InGame).or_else(in_state(GameState::Hub))),
+            );
+    }
+}
+
+//раз в сколько секунд поток выпускает снаряды
+const CHANNEL_TICK: f32 = 0.08;
+
+#[derive(Event)]
+pub struct StartChannelEvent {
+    pub spell: Spell,
+    pub element: ElementType,
+    pub damage: u32, //урон одного снаряда потока
+    pub bar: ElementBar,
+}
+
+#[derive(Component)]
+pub struct Channeling {
+    pub spell: Spell,
+    pub element: ElementType,
+    damage: u32,
+    bar: ElementBar,
+    tick: Timer,
+}
+
+fn start_channel(
+    mut commands: Commands,
+    mut ev_start_channel: EventReader<StartChannelEvent>,
+    mut ev_play_audio: EventWriter<PlayAudioEvent>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+) {
+    let Ok((player_e, player_transform)) = player_query.get_single() else {
+        return;
+    };
+
+    for ev in ev_start_channel.read() {
+        ev_play_audio.send(
+            PlayAudioEvent::from_file(ev.element.audio())
+                .at(player_transform.translation.truncate(), 192.),
+        );
+
+        commands.entity(player_e).insert(Channeling {
+            spell: ev.spell,
+            element: ev.element,
+            damage: ev.damage.max(1),
+            bar: ev.bar,
+            tick: Timer::from_seconds(CHANNEL_TICK, TimerMode::Repeating),
+        });
+    }
+}
+
+fn channel_spell(
+    mut commands: Commands,
+    mut player_query: Query<
+        (Entity, &mut Channeling, Has<Stun>, Has<ItemPickupAnimation>),
+        With<Player>,
+    >,
+    wand_query: Query<&Transform, With<Wand>>,
+    mut ev_spawn_projectile: EventWriter<SpawnProjectileEvent>,
+    mut ev_cast_denied: EventWriter<CastDeniedEvent>,
+    mut mana: ResMut<Mana>,
+    player_stats: Res<PlayerStats>,
+    equipped_wand: Res<EquippedWand>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mouse_coords: Res<MouseCoords>,
+    time: Res<Time<Virtual>>,
+) {
+    let Ok((player_e, mut channel, stunned, picking_up)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    if time.is_paused() {
+        return;
+    }
+
+    if !mouse.pressed(MouseButton::Left) || stunned || picking_up {
+        commands.entity(player_e).remove::<Channeling>();
+        return;
+    }
+
+    let drain = channel.spell.channel_drain() * player_stats.mana_cost_mult * time.delta_seconds();
+    if !mana.drain(drain) {
+        ev_cast_denied.send(CastDeniedEvent {
+            spell: channel.spell,
+            reason: CastDenied::NoMana,
+        });
+        commands.entity(player_e).remove::<Channeling>();
+        return;
+    }
+
+    channel.tick.tick(time.delta());
+    if !channel.tick.just_finished() {
+        return;
+    }
+
+    let Ok(wand_transform) = wand_query.get_single() else {
+        return;
+    };
+
+    let origin = wand_transform.translation;
+    let dir = (mouse_coords.0 - origin.truncate()).normalize_or_zero();
+    let angle = dir.y.atan2(dir.x);
+    let color = channel.element.color();
+
+    let mut rng = rand::thread_rng();
+
+    match channel.spell {
+        //широкий конус огня, каждый огонек прожигает одного врага насквозь
+        Spell::Flamethrower => {
+            let offset = PI / 8.0;
+            for _i in 0..channel.bar.fire {
+                let mut projectile = SpawnProjectileEvent {
+                    texture_path: "textures/small_fire.png".to_string(),
+                    color,
+                    translation: origin,
+                    angle: angle + rng.gen_range(-offset..offset),
+                    collider_radius: 6.,
+                    speed: 220.0 + rng.gen_range(-40.0..40.0),
+                    damage: channel.damage,
+                    element: channel.element,
+                    is_friendly: true,
+                    trajectory: Trajectory::Straight,
+                    can_go_through_walls: false,
+                    modifiers: vec![ProjectileModifier::Piercing { targets: 1 }],
+                };
+                equipped_wand.apply(&mut projectile, Some(angle));
+                ev_spawn_projectile.send(projectile);
+            }
+        }
+        //узкая быстрая струя, пробивает столько врагов, сколько в ней воды
+        Spell::WaterBeam => {
+            let mut projectile = SpawnProjectileEvent {
+                texture_path: "textures/small_fire.png".to_string(),
+                color,
+                translation: origin,
+                angle: angle + rng.gen_range(-0.02..0.02),
+                collider_radius: 5.,
+                speed: 380.,
+                damage: channel.damage,
+                element: channel.element,
+                is_friendly: true,
+                trajectory: Trajectory::Straight,
+                can_go_through_walls: false,
+                modifiers: vec![ProjectileModifier::Piercing {
+                    targets: channel.bar.water as u32,
+                }],
+            };
+            equipped_wand.apply(&mut projectile, Some(angle));
+            ev_spawn_projectile.send(projectile);
+        }
+        _ => {}
+    }
+}
+
+fn stop_all_channels(mut commands: Commands, channel_query: Query<Entity, With<Channeling>>) {
+    for entity in channel_query.iter() {
+        commands.entity(entity).remove::<Channeling>();
+    }
+}
@@ -12,6 +12,9 @@ impl Plugin for ChapterPlugin {
     }
 }
 
+//последний уровень каждой главы - арена с боссом
+pub const BOSS_LEVEL: u8 = 3;
+
 #[derive(Resource)]
 pub struct ChapterManager {
     pub current_level: u8,
@@ -38,6 +41,12 @@ impl ChapterManager {
         self.current_level
     }
 
+    //после хаба идем к боссу главы, а после босса третьей главы - к колдуну
+    pub fn is_next_level_boss(&self) -> bool {
+        self.current_level + 1 == BOSS_LEVEL
+            || (self.current_chapter + 1 == self.max_chapter && self.current_level == BOSS_LEVEL)
+    }
+
     pub fn get_current_color(&self) -> Color {
         match self.current_chapter {
             //1 => Color::srgb(69. / 255., 35. / 255., 13. / 255.),
@@ -62,7 +71,7 @@ fn update_chapter(mut commands: Commands, mut chapter_manager: ResMut<ChapterMan
         chapter_manager.current_level = 1;
     }
 
-    if chapter_manager.current_level > 2 {
+    if chapter_manager.current_level > BOSS_LEVEL {
         chapter_manager.current_level = 1;
         chapter_manager.current_chapter += 1;
     }
@@ -0,0 +1,374 @@
+//зоны урона: огненный ковер, облако пара, зыбучий песок, ядовитая лужа.
+//Спавнятся событием, бьют всех внутри раз в tick_rate секунд, могут расти и ходить за владельцем
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::{
+    elements::{ElementResistance, ElementType},
+    friend::Friend,
+    gamemap::{tile_of, Map},
+    hazards::{HazardType, Soaked, QUICKSAND_SLOW},
+    health::{Health, Hit},
+    invincibility::Invincibility,
+    mobs::{Enemy, Mob},
+    player::Player,
+    utils::despawn_all_with,
+    GameState,
+};
+
+pub struct DamageZonePlugin;
+
+impl Plugin for DamageZonePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpawnDamageZoneEvent>()
+            .add_systems(
+                OnExit(GameState::InGame),
+                (despawn_all_with::<DamageZone>, clear_zone_slow, clear_blind),
+            )
+            .add_systems(
+                Update,
+                (
+                    spawn_damage_zone,
+                    update_damage_zones,
+                    tick_damage_zones,
+                    slow_in_zones,
+                    blind_in_zones,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    scale_slowed_velocity.before(PhysicsSet::StepSimulation),
+                    restore_slowed_velocity.after(PhysicsSet::StepSimulation),
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ZoneShape {
+    Circle,
+}
+
+impl ZoneShape {
+    fn contains(&self, center: Vec2, radius: f32, point: Vec2) -> bool {
+        let diff = point - center;
+        match self {
+            ZoneShape::Circle => diff.length_squared() <= radius * radius,
+        }
+    }
+}
+
+//как меняется радиус за время жизни зоны, from/to - доля от radius
+#[derive(Clone, Copy)]
+pub enum ZoneGrowth {
+    Constant,
+    Linear { from: f32 },
+    EaseOut { from: f32 }, //быстро раздувается и замирает
+    Shrink { to: f32 },
+}
+
+impl ZoneGrowth {
+    //t от 0 до 1
+    fn factor(&self, t: f32) -> f32 {
+        let t = t.clamp(0., 1.);
+        match *self {
+            ZoneGrowth::Constant => 1.,
+            ZoneGrowth::Linear { from } => from + (1. - from) * t,
+            ZoneGrowth::EaseOut { from } => from + (1. - from) * (1. - (1. - t).powi(3)),
+            ZoneGrowth::Shrink { to } => 1. + (to - 1.) * t,
+        }
+    }
+}
+
+//что зона делает помимо урона, пока в ней стоишь
+#[derive(Clone, Copy, PartialEq)]
+pub enum ZoneEffect {
+    Slow { factor: f32 }, //множитель скорости, 0.5 - вдвое медленнее
+    Soak,
+    Blind, //мобы внутри бьют мимо
+}
+
+#[derive(Event)]
+pub struct SpawnDamageZoneEvent {
+    pub position: Vec3,
+    pub shape: ZoneShape,
+    pub radius: f32,
+    pub duration: f32,
+    pub tick_rate: f32, //секунд между тиками урона
+    pub damage: i32,
+    pub element: Option<ElementType>,
+    pub color: Color,
+    pub is_friendly: bool,
+    pub follow_owner: Option<Entity>,
+    pub growth: ZoneGrowth,
+    pub effect: Option<ZoneEffect>,
+}
+
+#[derive(Component)]
+pub struct DamageZone {
+    pub shape: ZoneShape,
+    pub radius: f32,
+    pub current_radius: f32,
+    pub damage: i32,
+    pub element: Option<ElementType>,
+    pub is_friendly: bool,
+    pub follow_owner: Option<Entity>,
+    pub growth: ZoneGrowth,
+    pub effect: Option<ZoneEffect>,
+    lifetime: Timer,
+    tick: Timer,
+}
+
+impl DamageZone {
+    pub fn contains(&self, center: Vec2, point: Vec2) -> bool {
+        self.shape.contains(center, self.current_radius, point)
+    }
+}
+
+//замедлен зоной или зыбучим песком, на шаг физики скорость умножается на factor
+#[derive(Component)]
+pub struct ZoneSlowed {
+    pub factor: f32,
+}
+
+//моб ослеплен и атакует наугад, пока стоит в ослепляющей зоне
+#[derive(Component)]
+pub struct Blinded;
+
+fn spawn_damage_zone(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut ev_spawn_zone: EventReader<SpawnDamageZoneEvent>,
+) {
+    for ev in ev_spawn_zone.read() {
+        let current_radius = ev.radius * ev.growth.factor(0.);
+
+        let texture = match ev.shape {
+            ZoneShape::Circle => asset_server.load("textures/damage_zone.png"),
+        };
+
+        commands
+            .spawn(SpriteBundle {
+                texture,
+                sprite: Sprite {
+                    color: ev.color,
+                    custom_size: Some(Vec2::splat(current_radius * 2.)),
+                    ..default()
+                },
+                //поверх пола и тайлов-ловушек, под всеми остальными
+                transform: Transform::from_translation(ev.position.truncate().extend(-99.)),
+                ..default()
+            })
+            .insert(DamageZone {
+                shape: ev.shape,
+                radius: ev.radius,
+                current_radius,
+                damage: ev.damage,
+                element: ev.element,
+                is_friendly: ev.is_friendly,
+                follow_owner: ev.follow_owner,
+                growth: ev.growth,
+                effect: ev.effect,
+                lifetime: Timer::from_seconds(ev.duration, TimerMode::Once),
+                tick: Timer::from_seconds(ev.tick_rate.max(0.05), TimerMode::Repeating),
+            });
+    }
+}
+
+fn update_damage_zones(
+    mut commands: Commands,
+    mut zone_query: Query<(Entity, &mut DamageZone, &mut Transform, &mut Sprite)>,
+    owner_query: Query<&Transform, Without<DamageZone>>,
+    time: Res<Time>,
+) {
+    for (zone_e, mut zone, mut transform, mut sprite) in zone_query.iter_mut() {
+        zone.lifetime.tick(time.delta());
+
+        if zone.lifetime.finished() {
+            commands.entity(zone_e).despawn();
+            continue;
+        }
+
+        //владелец умер - зона остается, где была
+        if let Some(owner_transform) = zone.follow_owner.and_then(|e| owner_query.get(e).ok()) {
+            transform.translation.x = owner_transform.translation.x;
+            transform.translation.y = owner_transform.translation.y;
+        }
+
+        let t = zone.lifetime.fraction();
+        zone.current_radius = zone.radius * zone.growth.factor(t);
+        sprite.custom_size = Some(Vec2::splat(zone.current_radius * 2.));
+
+        //под конец зона тает
+        let alpha = sprite.color.alpha();
+        let fade = (zone.lifetime.remaining_secs() / 0.5).min(1.);
+        sprite.color.set_alpha(alpha.min(fade.max(0.)));
+    }
+}
+
+fn tick_damage_zones(
+    mut commands: Commands,
+    mut zone_query: Query<(&mut DamageZone, &Transform)>,
+    mut enemy_query: Query<
+        (Entity, &Transform, &mut Health, &ElementResistance),
+        (With<Mob>, With<Enemy>, Without<DamageZone>),
+    >,
+    mut ally_query: Query<
+        (Entity, &Transform, &mut Health, Has<Invincibility>),
+        (
+            Or<(With<Player>, With<Friend>)>,
+            Without<Enemy>,
+            Without<DamageZone>,
+        ),
+    >,
+    time: Res<Time>,
+) {
+    for (mut zone, zone_transform) in zone_query.iter_mut() {
+        zone.tick.tick(time.delta());
+        let damage_tick = zone.tick.just_finished();
+        let center = zone_transform.translation.truncate();
+
+        if zone.is_friendly {
+            for (entity, transform, mut health, resistance) in enemy_query.iter_mut() {
+                if !zone.contains(center, transform.translation.truncate()) {
+                    continue;
+                }
+
+                if zone.effect == Some(ZoneEffect::Soak) {
+                    commands.entity(entity).insert(Soaked::default());
+                }
+
+                if damage_tick && zone.damage > 0 {
+                    let mut damage = zone.damage;
+                    resistance.calculate_for(&mut damage, zone.element);
+
+                    health.hit_queue.push(Hit {
+                        damage,
+                        element: zone.element,
+                        direction: Vec3::ZERO,
+                    });
+                }
+            }
+        } else {
+            for (entity, transform, mut health, invincible) in ally_query.iter_mut() {
+                if !zone.contains(center, transform.translation.truncate()) {
+                    continue;
+                }
+
+                if zone.effect == Some(ZoneEffect::Soak) {
+                    commands.entity(entity).insert(Soaked::default());
+                }
+
+                //сопротивление игрока считается в damage_player
+                if damage_tick && zone.damage > 0 && !invincible {
+                    health.hit_queue.push(Hit {
+                        damage: zone.damage,
+                        element: zone.element,
+                        direction: Vec3::ZERO,
+                    });
+                }
+            }
+        }
+    }
+}
+
+//зыбучий песок замедляет всех, кто в нем стоит, наравне с зонами
+fn slow_in_zones(
+    mut commands: Commands,
+    map: Res<Map>,
+    zone_query: Query<(&DamageZone, &Transform)>,
+    actor_query: Query<
+        (Entity, &Transform, Has<Enemy>, Has<ZoneSlowed>),
+        (Or<(With<Player>, With<Mob>)>, Without<DamageZone>),
+    >,
+) {
+    for (entity, transform, is_enemy, slowed) in actor_query.iter() {
+        let pos = transform.translation.truncate();
+
+        let in_quicksand = map
+            .map
+            .get(&tile_of(transform.translation))
+            .and_then(|tile| tile.hazard)
+            == Some(HazardType::Quicksand);
+
+        //дружественные зоны замедляют врагов, вражеские - игрока и призванных
+        let factor = zone_query
+            .iter()
+            .filter(|(zone, _)| zone.is_friendly == is_enemy)
+            .filter(|(zone, zone_transform)| {
+                zone.contains(zone_transform.translation.truncate(), pos)
+            })
+            .filter_map(|(zone, _)| match zone.effect {
+                Some(ZoneEffect::Slow { factor }) => Some(factor),
+                _ => None,
+            })
+            .chain(in_quicksand.then_some(QUICKSAND_SLOW))
+            .reduce(f32::min);
+
+        match factor {
+            Some(factor) => {
+                commands.entity(entity).insert(ZoneSlowed { factor });
+            }
+            None if slowed => {
+                commands.entity(entity).remove::<ZoneSlowed>();
+            }
+            None => {}
+        }
+    }
+}
+
+//режем скорость только на время шага физики, после шага возвращаем как было
+//иначе скорость, которую никто не задает каждый кадр (отталкивание, рывок), затухает с каждым кадром
+fn scale_slowed_velocity(mut slowed_query: Query<(&mut LinearVelocity, &ZoneSlowed)>) {
+    for (mut linvel, slowed) in slowed_query.iter_mut() {
+        linvel.0 *= slowed.factor;
+    }
+}
+
+fn restore_slowed_velocity(mut slowed_query: Query<(&mut LinearVelocity, &ZoneSlowed)>) {
+    for (mut linvel, slowed) in slowed_query.iter_mut() {
+        if slowed.factor > 0. {
+            linvel.0 /= slowed.factor;
+        }
+    }
+}
+
+//ослепляют только дружественные зоны и только врагов
+fn blind_in_zones(
+    mut commands: Commands,
+    zone_query: Query<(&DamageZone, &Transform)>,
+    mob_query: Query<(Entity, &Transform, Has<Blinded>), (With<Enemy>, Without<DamageZone>)>,
+) {
+    for (entity, transform, blinded) in mob_query.iter() {
+        let pos = transform.translation.truncate();
+
+        let in_cloud = zone_query.iter().any(|(zone, zone_transform)| {
+            zone.is_friendly
+                && zone.effect == Some(ZoneEffect::Blind)
+                && zone.contains(zone_transform.translation.truncate(), pos)
+        });
+
+        if in_cloud && !blinded {
+            commands.entity(entity).insert(Blinded);
+        } else if !in_cloud && blinded {
+            commands.entity(entity).remove::<Blinded>();
+        }
+    }
+}
+
+fn clear_blind(mut commands: Commands, blinded_query: Query<Entity, With<Blinded>>) {
+    for entity in blinded_query.iter() {
+        commands.entity(entity).remove::<Blinded>();
+    }
+}
+
+//игрок переживает уровень, зоны - нет
+fn clear_zone_slow(mut commands: Commands, slowed_query: Query<Entity, With<ZoneSlowed>>) {
+    for entity in slowed_query.iter() {
+        commands.entity(entity).remove::<ZoneSlowed>();
+    }
+}
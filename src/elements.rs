@@ -6,16 +6,21 @@ use rand::{distributions::Standard, prelude::Distribution, Rng};
 use crate::{
     audio::PlayAudioEvent,
     black_hole::SpawnBlackHoleEvent,
+    channel::StartChannelEvent,
+    damage_zone::{SpawnDamageZoneEvent, ZoneEffect, ZoneGrowth, ZoneShape},
     blank_spell::SpawnBlankEvent,
     health::Health,
     item::{ItemPickupAnimation, ItemType},
-    mobs::{MobSpawnEvent, MobType},
+    mana::{CastDeniedEvent, Mana},
+    meteor::SpawnMeteorEvent,
+    mobs::{EliteRoll, MobSpawnEvent, MobType},
     mouse_position::MouseCoords,
     player::{Player, PlayerDeathEvent, PlayerStats},
-    projectile::SpawnProjectileEvent,
+    projectile::{ProjectileModifier, SpawnProjectileEvent},
     shield_spell::SpawnShieldEvent,
+    stun::Stun,
     ui::ItemInventory,
-    wand::Wand,
+    wand::{EquippedWand, Wand},
     GameState,
 };
 
@@ -28,10 +33,12 @@ impl Plugin for ElementsPlugin {
             .add_event::<CastSpellEvent>()
             .insert_resource(ElementBar::default())
             .insert_resource(SpellPool::default())
+            .insert_resource(SpellCharge::default())
             .add_systems(OnExit(GameState::MainMenu), init_spells)
             .add_systems(
                 Update,
                 (fill_bar, handle_recipe, cast_spell)
+                    .chain()
                     .run_if(in_state(GameState::InGame).or_else(in_state(GameState::Hub))),
             );
     }
@@ -81,7 +88,7 @@ impl ElementType {
     }
 }
 
-#[derive(PartialEq, Clone, Copy, PartialOrd, Ord, Eq, Debug)]
+#[derive(PartialEq, Clone, Copy, PartialOrd, Ord, Eq, Hash, Debug)]
 pub enum Spell {
     Fire,
     Water,
@@ -95,6 +102,178 @@ pub enum Spell {
     WaterElemental,
     EarthElemental,
     AirElemental,
+    Flamethrower,
+    WaterBeam,
+    Lightning,
+    Ice,
+    Magma,
+    Sandstorm,
+    Meteor,
+}
+
+impl Spell {
+    pub fn name(&self) -> &str {
+        match self {
+            Spell::Fire => "Огонь",
+            Spell::Water => "Вода",
+            Spell::Earth => "Земля",
+            Spell::Air => "Воздух",
+            Spell::Steam => "Пар",
+            Spell::Shield => "Щит",
+            Spell::BlackHole => "Чёрная дыра",
+            Spell::Blank => "Пустышка",
+            Spell::FireElemental => "Огненный дух",
+            Spell::WaterElemental => "Водный дух",
+            Spell::EarthElemental => "Земляной дух",
+            Spell::AirElemental => "Воздушный дух",
+            Spell::Flamethrower => "Огнемёт",
+            Spell::WaterBeam => "Водяной луч",
+            Spell::Lightning => "Молния",
+            Spell::Ice => "Ледяной осколок",
+            Spell::Magma => "Магма",
+            Spell::Sandstorm => "Песчаная буря",
+            Spell::Meteor => "Метеор",
+        }
+    }
+
+    //рецепт значками, как в альманахе: fire/water/earth/air, etc - сколько угодно еще,
+    //greater - больше, чем, equals - столько же
+    pub fn recipe(&self) -> &'static [&'static str] {
+        match self {
+            Spell::Fire => &["fire", "etc", "fire"],
+            Spell::Water => &["water", "etc", "water"],
+            Spell::Earth => &["earth", "etc", "earth"],
+            Spell::Air => &["air", "etc", "air"],
+            Spell::Steam => &["fire", "etc", "water"],
+            Spell::Shield => &["water", "earth", "earth", "etc", "earth"],
+            Spell::Blank => &["water", "air", "air", "etc", "air"],
+            Spell::BlackHole => &[
+                "fire", "equals", "water", "equals", "earth", "equals", "air",
+            ],
+            Spell::FireElemental => &["fire", "fire", "etc", "fire", "water", "earth"],
+            Spell::WaterElemental => &["fire", "water", "water", "etc", "water", "earth"],
+            Spell::EarthElemental => &["fire", "water", "earth", "earth", "etc", "earth"],
+            Spell::AirElemental => &["fire", "water", "air", "air", "etc", "air"],
+            Spell::Flamethrower => &["fire", "greater", "air"],
+            Spell::WaterBeam => &["water", "greater", "air"],
+            Spell::Lightning => &["water", "water", "lower", "air"],
+            Spell::Ice => &["water", "equals", "air"],
+            Spell::Magma => &["fire", "etc", "earth"],
+            Spell::Sandstorm => &["earth", "etc", "air"],
+            Spell::Meteor => &["fire", "earth", "air"],
+        }
+    }
+
+    //как заклинание растет от длины полоски
+    pub fn scaling(&self) -> &str {
+        match self {
+            Spell::Fire => "3 огонька за каждый огонь",
+            Spell::Water => "3 капли за каждую воду",
+            Spell::Earth => "кольцо из 3 камней за каждый элемент",
+            Spell::Air => "один снаряд, урон растет с каждым элементом",
+            Spell::Steam => "3 снаряда за огонь и воду, облако растет с полоской",
+            Spell::Shield => "2 секунды за каждую землю",
+            Spell::Blank => "радиус растет с воздухом",
+            Spell::BlackHole => "сила, радиус и время растут с каждым элементом",
+            Spell::FireElemental
+            | Spell::WaterElemental
+            | Spell::EarthElemental
+            | Spell::AirElemental => "призывает союзника",
+            Spell::Flamethrower => "огоньков за раз по числу огня",
+            Spell::WaterBeam => "пробивает врагов по числу воды",
+            Spell::Lightning => "перескакивает на врагов, прыжков по числу элементов",
+            Spell::Ice => "осколков по числу воды, заморозка дольше с воздухом",
+            Spell::Magma => "лужа за каждый огонь, земля делает их шире",
+            Spell::Sandstorm => "буря растет с полоской, враги в ней бьют мимо",
+            Spell::Meteor => "урон и радиус растут с каждым элементом",
+        }
+    }
+
+    //поток: работает, пока держат кнопку, и тратит ману каждую секунду
+    pub fn is_channelled(&self) -> bool {
+        matches!(self, Spell::Flamethrower | Spell::WaterBeam)
+    }
+
+    //сколько маны в секунду съедает поток
+    pub fn channel_drain(&self) -> f32 {
+        match self {
+            Spell::Flamethrower => 20.,
+            Spell::WaterBeam => 15.,
+            _ => 0.,
+        }
+    }
+
+    //сколько маны стоит заклинание без учета предметов
+    pub fn mana_cost(&self) -> f32 {
+        match self {
+            Spell::Fire | Spell::Water | Spell::Earth | Spell::Air => 10.,
+            Spell::Steam => 20.,
+            Spell::Shield => 25.,
+            Spell::Blank => 30.,
+            Spell::FireElemental
+            | Spell::WaterElemental
+            | Spell::EarthElemental
+            | Spell::AirElemental => 40.,
+            Spell::BlackHole => 60.,
+            Spell::Flamethrower | Spell::WaterBeam => 5., //только за начало потока
+            Spell::Ice => 20.,
+            Spell::Lightning => 25.,
+            Spell::Magma => 30.,
+            Spell::Sandstorm => 35.,
+            Spell::Meteor => 50.,
+        }
+    }
+
+    //перезарядка в секундах без учета предметов
+    pub fn cooldown(&self) -> f32 {
+        match self {
+            Spell::Fire | Spell::Water | Spell::Earth | Spell::Air => 0.,
+            Spell::Steam => 1.,
+            Spell::Shield => 6.,
+            Spell::Blank => 8.,
+            Spell::FireElemental
+            | Spell::WaterElemental
+            | Spell::EarthElemental
+            | Spell::AirElemental => 10.,
+            Spell::BlackHole => 15.,
+            Spell::Flamethrower | Spell::WaterBeam => 0.,
+            Spell::Ice | Spell::Lightning => 2.,
+            Spell::Magma => 4.,
+            Spell::Sandstorm => 8.,
+            Spell::Meteor => 10.,
+        }
+    }
+}
+
+//сколько секунд держать кнопку для полного заряда
+const MAX_CHARGE: f32 = 1.5;
+
+//зарядка заклинания, пока зажата кнопка мыши
+#[derive(Resource, Default)]
+pub struct SpellCharge {
+    pub charging: Option<ElementType>,
+    pub held: f32,
+}
+
+impl SpellCharge {
+    fn start(&mut self, element: ElementType) {
+        self.charging = Some(element);
+        self.held = 0.;
+    }
+
+    fn cancel(&mut self) {
+        self.charging = None;
+        self.held = 0.;
+    }
+
+    pub fn is_charging(&self) -> bool {
+        self.charging.is_some()
+    }
+
+    //от 0 до 1
+    pub fn fraction(&self) -> f32 {
+        (self.held / MAX_CHARGE).min(1.)
+    }
 }
 
 #[derive(Resource)]
@@ -103,7 +282,7 @@ pub struct SpellPool {
 }
 
 impl SpellPool {
-    fn is_unlocked(&self, spell: Spell) -> bool {
+    pub fn is_unlocked(&self, spell: Spell) -> bool {
         self.unlocked.contains(&spell)
     }
 
@@ -117,7 +296,12 @@ impl SpellPool {
 impl Default for SpellPool {
     fn default() -> Self {
         Self {
-            unlocked: vec![Spell::Fire, Spell::Water, Spell::Earth, Spell::Air],
+            unlocked: vec![
+                Spell::Fire,
+                Spell::Water,
+                Spell::Earth,
+                Spell::Air,
+            ],
         }
     }
 }
@@ -129,10 +313,11 @@ pub struct ElementBar {
     pub earth: u8,
     pub air: u8,
     pub max: u8,
+    pub bonus: i8, //от посоха, может быть и отрицательным
 }
 
 impl ElementBar {
-    fn clear(&mut self) {
+    pub fn clear(&mut self) {
         self.fire = 0;
         self.water = 0;
         self.earth = 0;
@@ -143,8 +328,13 @@ impl ElementBar {
         self.fire + self.water + self.earth + self.air
     }
 
+    //сколько элементов помещается в полоску с учетом посоха, но не меньше одного
+    pub fn capacity(&self) -> u8 {
+        (self.max as i16 + self.bonus as i16).max(1) as u8
+    }
+
     fn add(&mut self, element: ElementType) {
-        if self.len() < self.max {
+        if self.len() < self.capacity() {
             match element {
                 ElementType::Fire => self.fire += 1,
                 ElementType::Water => self.water += 1,
@@ -162,6 +352,7 @@ impl ElementBar {
             earth: 0,
             air: 0,
             max: 1,
+            bonus: 0,
         }
     }
 }
@@ -203,6 +394,7 @@ pub struct ElementBarClear;
 fn init_spells(mut commands: Commands) {
     commands.insert_resource(ElementBar::default());
     commands.insert_resource(SpellPool::default());
+    commands.insert_resource(SpellCharge::default());
 }
 
 fn fill_bar(
@@ -222,22 +414,25 @@ fn fill_bar(
             _ => new_element = None,
         }
 
-        if new_element.is_some() && bar.len() < bar.max && !time.is_paused() {
+        if new_element.is_some() && bar.len() < bar.capacity() && !time.is_paused() {
             ev_bar_filled.send(ElementBarFilled(new_element.unwrap()));
             bar.add(new_element.unwrap());
         }
     });
 }
 
+//обычные заклинания заряжаются, пока держат кнопку, и срабатывают при отпускании,
+//потоки начинаются сразу при нажатии
 fn handle_recipe(
     player_stats: Res<PlayerStats>,
 
     wand_query: Query<&Transform, With<Wand>>,
+    equipped_wand: Res<EquippedWand>,
 
     spell_pool: Res<SpellPool>,
 
     mut player_query: Query<
-        (&mut Health, Entity, &Transform),
+        (&mut Health, Entity, &Transform, Has<Stun>),
         (With<Player>, Without<ItemPickupAnimation>),
     >,
     mut ev_death: EventWriter<PlayerDeathEvent>,
@@ -246,252 +441,362 @@ fn handle_recipe(
     mut ev_bar_clear: EventWriter<ElementBarClear>,
 
     mut ev_cast_spell: EventWriter<CastSpellEvent>,
+    mut ev_start_channel: EventWriter<StartChannelEvent>,
+
+    mut mana: ResMut<Mana>,
+    mut ev_cast_denied: EventWriter<CastDeniedEvent>,
+    mut charge: ResMut<SpellCharge>,
 
     mouse: Res<ButtonInput<MouseButton>>,
 
     time: Res<Time<Virtual>>,
 ) {
-    if mouse.just_pressed(MouseButton::Left) && element_bar.len() > 0 && !time.is_paused() {
-        let Ok((mut player_health, player_e, transform)) = player_query.get_single_mut() else {
-            return;
-        };
+    if time.is_paused() {
+        return;
+    }
 
-        // отнимаем хп, если предмет
-        if player_stats.spell_cast_hp_fee > 0 {
-            player_health.damage(player_stats.spell_cast_hp_fee);
-            if player_health.current <= 0 {
-                ev_death.send(PlayerDeathEvent(player_e));
+    let Ok((mut player_health, player_e, transform, stunned)) = player_query.get_single_mut()
+    else {
+        charge.cancel();
+        return;
+    };
+
+    //оглушение сбивает зарядку, элементы остаются в полоске
+    if stunned {
+        charge.cancel();
+        return;
+    }
+
+    let bar = element_bar.clone();
+
+    let pick = || {
+        wand_query.get_single().ok().and_then(|wand_transform| {
+            pick_spell(
+                &bar,
+                &spell_pool,
+                &player_stats,
+                &equipped_wand,
+                transform,
+                wand_transform,
+            )
+        })
+    };
+
+    let cast = if mouse.just_pressed(MouseButton::Left) && bar.len() > 0 {
+        let cast = pick();
+
+        match &cast {
+            Some(cast) if !cast.spell.is_channelled() => {
+                charge.start(cast.element);
+                return;
             }
+            _ => cast,
         }
+    } else if charge.is_charging() {
+        charge.held += time.delta_seconds();
 
-        ev_bar_clear.send(ElementBarClear);
+        if mouse.pressed(MouseButton::Left) {
+            return;
+        }
 
-        let bar = element_bar.clone();
-        element_bar.clear();
+        let power = charge.fraction();
+        charge.cancel();
 
-        let mut element: ElementType;
-        let elements_to_comapre = vec![bar.fire, bar.water, bar.earth, bar.air];
+        if bar.len() == 0 {
+            return;
+        }
 
-        // need to rewrite to look better
-        if *elements_to_comapre.iter().max().unwrap() == bar.fire {
-            element = ElementType::Fire;
-        } else if *elements_to_comapre.iter().max().unwrap() == bar.water {
-            element = ElementType::Water;
-        } else if *elements_to_comapre.iter().max().unwrap() == bar.earth {
-            element = ElementType::Earth;
-        } else {
-            element = ElementType::Air;
+        pick().map(|cast| CastSpellEvent {
+            charge: power,
+            ..cast
+        })
+    } else {
+        return;
+    };
+
+    //не хватает маны или заклинание еще не готово - элементы остаются в полоске
+    if let Some(cast) = &cast {
+        if let Err(reason) = mana.try_cast(cast.spell, &player_stats) {
+            ev_cast_denied.send(CastDeniedEvent {
+                spell: cast.spell,
+                reason,
+            });
+            return;
         }
+    }
 
-        let mut dmg = player_stats.get_bonused_damage(element);
-        dmg *= bar.len() as u32;
-
-        println!("{:?}", spell_pool.unlocked);
-
-        if let Ok(wand_transform) = wand_query.get_single() {
-            if spell_pool.is_unlocked(Spell::Shield)
-                && bar.water == 1
-                && bar.earth > 1
-                && bar.fire <= 0
-                && bar.air <= 0
-            {
-                ev_cast_spell.send(CastSpellEvent {
-                    spell: Spell::Shield,
-                    element: element,
-                    origin: Vec3::ZERO,
-                    bar: bar,
-                    damage: 0,
-                });
+    // отнимаем хп, если предмет
+    if player_stats.spell_cast_hp_fee > 0 {
+        player_health.damage(player_stats.spell_cast_hp_fee);
+        if player_health.current <= 0 {
+            ev_death.send(PlayerDeathEvent(player_e));
+        }
+    }
 
-                return;
-            }
+    ev_bar_clear.send(ElementBarClear);
+    element_bar.clear();
+
+    match cast {
+        Some(cast) if cast.spell.is_channelled() => {
+            ev_start_channel.send(StartChannelEvent {
+                spell: cast.spell,
+                element: cast.element,
+                damage: cast.damage,
+                bar: cast.bar,
+            });
+        }
+        Some(cast) => {
+            ev_cast_spell.send(cast);
+        }
+        None => {}
+    }
+}
 
-            if spell_pool.is_unlocked(Spell::Blank)
-                && bar.water == 1
-                && bar.air > 1
-                && bar.fire <= 0
-                && bar.earth <= 0
-            {
-                ev_cast_spell.send(CastSpellEvent {
-                    spell: Spell::Blank,
-                    element,
-                    origin: transform.translation,
-                    damage: dmg,
-                    bar,
-                });
+//какое заклинание получится из набранных элементов, без учета маны и перезарядки
+pub fn match_recipe(bar: &ElementBar, spell_pool: &SpellPool) -> Option<Spell> {
+    let bar = *bar;
 
-                return;
-            }
+    if bar.len() == 0 {
+        return None;
+    }
 
-            if spell_pool.is_unlocked(Spell::BlackHole)
-                && bar.fire == bar.water
-                && bar.water == bar.earth
-                && bar.earth == bar.air
-                && bar.air == bar.fire
-            {
-                ev_cast_spell.send(CastSpellEvent {
-                    spell: Spell::BlackHole,
-                    element,
-                    origin: transform.translation,
-                    damage: dmg,
-                    bar,
-                });
+    if spell_pool.is_unlocked(Spell::Shield)
+        && bar.water == 1
+        && bar.earth > 1
+        && bar.fire <= 0
+        && bar.air <= 0
+    {
+        return Some(Spell::Shield);
+    }
 
-                return;
-            }
+    if spell_pool.is_unlocked(Spell::Blank)
+        && bar.water == 1
+        && bar.air > 1
+        && bar.fire <= 0
+        && bar.earth <= 0
+    {
+        return Some(Spell::Blank);
+    }
 
-            //spawn ClayGolem -- TODO: prolly delete? as we agreed golem to be a regualr enemy
-            // as we already have earth elemental???
-            // ---
-            // if bar.earth == 2
-            // && bar.air <= 0
-            // && bar.water >=2
-            // && bar.fire >=2 {
-            //     ev_spawn_friend.send(MobSpawnEvent{mob_type: MobType::ClayGolem, pos: mouse_coords.0, is_friendly: true });
-            //     return;
-            // }
-
-            //spawn FireElemental
-            if spell_pool.is_unlocked(Spell::FireElemental)
-                && bar.earth >= 1
-                && bar.air <= 0
-                && bar.water >= 1
-                && bar.fire == 2
-            {
-                ev_cast_spell.send(CastSpellEvent {
-                    spell: Spell::FireElemental,
-                    element,
-                    origin: Vec3::ZERO,
-                    damage: dmg,
-                    bar,
-                });
+    if spell_pool.is_unlocked(Spell::BlackHole)
+        && bar.fire == bar.water
+        && bar.water == bar.earth
+        && bar.earth == bar.air
+        && bar.air == bar.fire
+    {
+        return Some(Spell::BlackHole);
+    }
 
-                return;
-            }
+    //spawn ClayGolem -- TODO: prolly delete? as we agreed golem to be a regualr enemy
+    // as we already have earth elemental???
+    // ---
+    // if bar.earth == 2
+    // && bar.air <= 0
+    // && bar.water >=2
+    // && bar.fire >=2 {
+    //     ev_spawn_friend.send(MobSpawnEvent{mob_type: MobType::ClayGolem, pos: mouse_coords.0, is_friendly: true });
+    //     return;
+    // }
+
+    //spawn FireElemental
+    if spell_pool.is_unlocked(Spell::FireElemental)
+        && bar.earth >= 1
+        && bar.air <= 0
+        && bar.water >= 1
+        && bar.fire == 2
+    {
+        return Some(Spell::FireElemental);
+    }
 
-            if spell_pool.is_unlocked(Spell::WaterElemental)
-                && bar.earth >= 1
-                && bar.air <= 0
-                && bar.water == 2
-                && bar.fire >= 1
-            {
-                ev_cast_spell.send(CastSpellEvent {
-                    spell: Spell::WaterElemental,
-                    element,
-                    origin: Vec3::ZERO,
-                    damage: dmg,
-                    bar,
-                });
+    if spell_pool.is_unlocked(Spell::WaterElemental)
+        && bar.earth >= 1
+        && bar.air <= 0
+        && bar.water == 2
+        && bar.fire >= 1
+    {
+        return Some(Spell::WaterElemental);
+    }
 
-                return;
-            }
+    //spawn EarthElemental
+    if spell_pool.is_unlocked(Spell::EarthElemental)
+        && bar.earth == 2
+        && bar.air <= 0
+        && bar.water >= 1
+        && bar.fire >= 1
+    {
+        return Some(Spell::EarthElemental);
+    }
 
-            //spawn EarthElemental
-            if spell_pool.is_unlocked(Spell::EarthElemental)
-                && bar.earth == 2
-                && bar.air <= 0
-                && bar.water >= 1
-                && bar.fire >= 1
-            {
-                ev_cast_spell.send(CastSpellEvent {
-                    spell: Spell::EarthElemental,
-                    element,
-                    origin: Vec3::ZERO,
-                    damage: dmg,
-                    bar,
-                });
+    //spawn AirElemental
+    if spell_pool.is_unlocked(Spell::AirElemental)
+        && bar.earth <= 0
+        && bar.air == 2
+        && bar.water >= 1
+        && bar.fire >= 1
+    {
+        return Some(Spell::AirElemental);
+    }
 
-                return;
-            }
+    // sub-element, cannot directly cast
+    if bar.fire > 0
+        && bar.water > 0
+        && (bar.earth + bar.air) < (bar.fire + bar.water)
+        && spell_pool.is_unlocked(Spell::Steam)
+    {
+        return Some(Spell::Steam);
+    }
 
-            //spawn AirElemental
-            if spell_pool.is_unlocked(Spell::AirElemental)
-                && bar.earth <= 0
-                && bar.air == 2
-                && bar.water >= 1
-                && bar.fire >= 1
-            {
-                ev_cast_spell.send(CastSpellEvent {
-                    spell: Spell::AirElemental,
-                    element,
-                    origin: Vec3::ZERO,
-                    damage: dmg,
-                    bar,
-                });
+    //огонь с воздухом - огнемет, пока держат кнопку
+    if spell_pool.is_unlocked(Spell::Flamethrower)
+        && bar.fire > bar.air
+        && bar.air > 0
+        && bar.water <= 0
+        && bar.earth <= 0
+    {
+        return Some(Spell::Flamethrower);
+    }
 
-                return;
-            }
+    //вода с воздухом - водяной луч
+    if spell_pool.is_unlocked(Spell::WaterBeam)
+        && bar.water > bar.air
+        && bar.air > 0
+        && bar.fire <= 0
+        && bar.earth <= 0
+    {
+        return Some(Spell::WaterBeam);
+    }
 
-            // sub-element, cannot directly cast
-            if bar.fire > 0
-                && bar.water > 0
-                && (bar.earth + bar.air) < (bar.fire + bar.water)
-                && spell_pool.is_unlocked(Spell::Steam)
-            {
-                element = ElementType::Steam;
+    //воздуха больше, чем воды - молния, с одной водой это пустышка
+    if spell_pool.is_unlocked(Spell::Lightning)
+        && bar.air > bar.water
+        && bar.water > 1
+        && bar.fire <= 0
+        && bar.earth <= 0
+    {
+        return Some(Spell::Lightning);
+    }
 
-                ev_cast_spell.send(CastSpellEvent {
-                    spell: Spell::Steam,
-                    element,
-                    origin: wand_transform.translation,
-                    damage: dmg,
-                    bar,
-                });
+    //воды и воздуха поровну - лед
+    if spell_pool.is_unlocked(Spell::Ice)
+        && bar.water == bar.air
+        && bar.water > 0
+        && bar.fire <= 0
+        && bar.earth <= 0
+    {
+        return Some(Spell::Ice);
+    }
 
-                return;
-            }
+    if spell_pool.is_unlocked(Spell::Meteor)
+        && bar.fire > 0
+        && bar.earth > 0
+        && bar.air > 0
+        && bar.water <= 0
+    {
+        return Some(Spell::Meteor);
+    }
 
-            if bar.fire > bar.water && bar.earth <= 0 && bar.air <= 0 {
-                ev_cast_spell.send(CastSpellEvent {
-                    spell: Spell::Fire,
-                    element,
-                    origin: wand_transform.translation,
-                    damage: dmg,
-                    bar,
-                });
+    if spell_pool.is_unlocked(Spell::Magma)
+        && bar.fire > 0
+        && bar.earth > 0
+        && bar.water <= 0
+        && bar.air <= 0
+    {
+        return Some(Spell::Magma);
+    }
 
-                return;
-            }
+    if spell_pool.is_unlocked(Spell::Sandstorm)
+        && bar.earth > 0
+        && bar.air > 0
+        && bar.fire <= 0
+        && bar.water <= 0
+    {
+        return Some(Spell::Sandstorm);
+    }
 
-            if bar.water > bar.fire && bar.earth <= 0 && bar.air <= 0 {
-                ev_cast_spell.send(CastSpellEvent {
-                    spell: Spell::Water,
-                    element,
-                    origin: wand_transform.translation,
-                    damage: dmg,
-                    bar,
-                });
+    if bar.fire > bar.water && bar.earth <= 0 && bar.air <= 0 {
+        return Some(Spell::Fire);
+    }
 
-                return;
-            }
+    if bar.water > bar.fire && bar.earth <= 0 && bar.air <= 0 {
+        return Some(Spell::Water);
+    }
 
-            if bar.earth > 0 && bar.air <= 0 {
-                ev_cast_spell.send(CastSpellEvent {
-                    spell: Spell::Earth,
-                    element,
-                    origin: wand_transform.translation,
-                    damage: dmg,
-                    bar,
-                });
+    if bar.earth > 0 && bar.air <= 0 {
+        return Some(Spell::Earth);
+    }
 
-                return;
-            }
+    if bar.air > 0 {
+        return Some(Spell::Air);
+    }
 
-            if bar.air > 0 {
-                ev_cast_spell.send(CastSpellEvent {
-                    spell: Spell::Air,
-                    element,
-                    origin: wand_transform.translation,
-                    damage: dmg,
-                    bar,
-                });
+    None
+}
 
-                return;
-            }
-        }
+//стихия, которой больше всего в полоске
+pub fn dominant_element(bar: &ElementBar) -> ElementType {
+    let elements_to_comapre = vec![bar.fire, bar.water, bar.earth, bar.air];
+
+    // need to rewrite to look better
+    if *elements_to_comapre.iter().max().unwrap() == bar.fire {
+        ElementType::Fire
+    } else if *elements_to_comapre.iter().max().unwrap() == bar.water {
+        ElementType::Water
+    } else if *elements_to_comapre.iter().max().unwrap() == bar.earth {
+        ElementType::Earth
+    } else {
+        ElementType::Air
     }
 }
 
+//по набранным элементам выбираем заклинание
+fn pick_spell(
+    bar: &ElementBar,
+    spell_pool: &SpellPool,
+    player_stats: &PlayerStats,
+    equipped_wand: &EquippedWand,
+    transform: &Transform,
+    wand_transform: &Transform,
+) -> Option<CastSpellEvent> {
+    let bar = *bar;
+    let spell = match_recipe(&bar, spell_pool)?;
+
+    let element = match spell {
+        Spell::Steam => ElementType::Steam,
+        Spell::Flamethrower | Spell::Magma | Spell::Meteor => ElementType::Fire,
+        Spell::WaterBeam | Spell::Ice => ElementType::Water,
+        Spell::Lightning => ElementType::Air,
+        Spell::Sandstorm => ElementType::Earth,
+        _ => dominant_element(&bar),
+    };
+
+    let mut dmg = player_stats.get_bonused_damage(dominant_element(&bar));
+    dmg *= bar.len() as u32;
+    dmg = (dmg as f32 * equipped_wand.damage_mult(dominant_element(&bar))) as u32;
+
+    let (origin, damage) = match spell {
+        Spell::Shield => (Vec3::ZERO, 0),
+        Spell::FireElemental
+        | Spell::WaterElemental
+        | Spell::EarthElemental
+        | Spell::AirElemental => (Vec3::ZERO, dmg),
+        Spell::Blank | Spell::BlackHole | Spell::Meteor => (transform.translation, dmg),
+        //поток бьет часто, поэтому каждый снаряд несет урон одного элемента
+        Spell::Flamethrower | Spell::WaterBeam => {
+            (wand_transform.translation, dmg / bar.len() as u32)
+        }
+        _ => (wand_transform.translation, dmg),
+    };
+
+    Some(CastSpellEvent {
+        spell,
+        element,
+        origin,
+        damage,
+        bar,
+        charge: 0.,
+    })
+}
+
 #[derive(Event)]
 struct CastSpellEvent {
     spell: Spell,
@@ -499,6 +804,7 @@ struct CastSpellEvent {
     origin: Vec3,
     damage: u32,
     bar: ElementBar,
+    charge: f32, //от 0 до 1, сколько держали кнопку
 }
 
 fn cast_spell(
@@ -508,12 +814,15 @@ fn cast_spell(
     mut ev_spawn_blank: EventWriter<SpawnBlankEvent>,
     mut ev_spawn_black_hole: EventWriter<SpawnBlackHoleEvent>,
     mut ev_spawn_projectile: EventWriter<SpawnProjectileEvent>,
+    mut ev_spawn_zone: EventWriter<SpawnDamageZoneEvent>,
+    mut ev_spawn_meteor: EventWriter<SpawnMeteorEvent>,
     mut ev_spawn_friend: EventWriter<MobSpawnEvent>,
 
     mut ev_play_audio: EventWriter<PlayAudioEvent>,
 
     mouse_coords: Res<MouseCoords>,
     inventory: Res<ItemInventory>,
+    equipped_wand: Res<EquippedWand>,
 
     player_query: Query<Entity, With<Player>>,
 ) {
@@ -528,16 +837,20 @@ fn cast_spell(
         let color = element.color();
         let origin = ev.origin;
         let dmg = ev.damage;
+        //полный заряд удваивает число снарядов, урон одиночного снаряда или площадь
+        let power = 1. + ev.charge;
 
         let audio_file = element.audio();
-        ev_play_audio.send(PlayAudioEvent::from_file(audio_file));
+        ev_play_audio.send(PlayAudioEvent::from_file(audio_file).at(origin.truncate(), 192.));
 
         let mut rng = rand::thread_rng();
+        //снаряды сначала собираем, чтобы посох поправил их скорость и разброс
+        let mut projectiles: Vec<SpawnProjectileEvent> = vec![];
 
         match ev.spell {
             Spell::Fire => {
                 let offset = PI / 10.0;
-                for _i in 0..bar.fire * 3 {
+                for _i in 0..charged(bar.fire * 3, ev.charge) {
                     let dir = (mouse_coords.0 - origin.truncate()).normalize_or_zero();
                     let angle = dir.y.atan2(dir.x) + rng.gen_range(-offset..offset);
 
@@ -550,7 +863,7 @@ fn cast_spell(
                         origin.truncate() - Vec2::from_angle(angle + PI / 2.) * radius
                     };
 
-                    ev_spawn_projectile.send(SpawnProjectileEvent {
+                    projectiles.push(SpawnProjectileEvent {
                         texture_path: "textures/small_fire.png".to_string(),
                         color,
                         translation: origin,
@@ -567,16 +880,17 @@ fn cast_spell(
                             counter_clockwise,
                         },
                         can_go_through_walls: false,
+                        modifiers: vec![],
                     });
                 }
             }
             Spell::Water => {
                 let offset = PI / 12.0;
-                for _i in 0..bar.water * 3 {
+                for _i in 0..charged(bar.water * 3, ev.charge) {
                     let dir = (mouse_coords.0 - origin.truncate()).normalize_or_zero();
                     let angle = dir.y.atan2(dir.x) + rng.gen_range(-offset..offset);
 
-                    ev_spawn_projectile.send(SpawnProjectileEvent {
+                    projectiles.push(SpawnProjectileEvent {
                         texture_path: "textures/small_fire.png".to_string(),
                         color,
                         translation: origin,
@@ -588,15 +902,17 @@ fn cast_spell(
                         is_friendly: true,
                         trajectory: crate::projectile::Trajectory::Straight,
                         can_go_through_walls: false,
+                        modifiers: vec![],
                     });
                 }
             }
             Spell::Earth => {
-                let offset = (2.0 * PI) / (bar.len() * 3) as f32;
-                for i in 0..bar.len() * 3 {
+                let count = charged(bar.len() * 3, ev.charge);
+                let offset = (2.0 * PI) / count as f32;
+                for i in 0..count {
                     let angle = offset * i as f32;
 
-                    ev_spawn_projectile.send(SpawnProjectileEvent {
+                    projectiles.push(SpawnProjectileEvent {
                         texture_path: "textures/earthquake.png".to_string(),
                         color,
                         translation: origin,
@@ -608,6 +924,7 @@ fn cast_spell(
                         is_friendly: true,
                         trajectory: crate::projectile::Trajectory::Straight,
                         can_go_through_walls: false,
+                        modifiers: vec![],
                     });
                 }
             }
@@ -615,27 +932,28 @@ fn cast_spell(
                 let dir = (mouse_coords.0 - origin.truncate()).normalize_or_zero();
                 let angle = dir.y.atan2(dir.x);
 
-                ev_spawn_projectile.send(SpawnProjectileEvent {
+                projectiles.push(SpawnProjectileEvent {
                     texture_path: "textures/fireball.png".to_string(),
                     color,
                     translation: origin,
                     angle,
                     collider_radius: 8.0,
                     speed: 100.,
-                    damage: dmg,
+                    damage: (dmg as f32 * power) as u32,
                     element,
                     is_friendly: true,
                     trajectory: crate::projectile::Trajectory::Straight,
                     can_go_through_walls: false,
+                    modifiers: vec![],
                 });
             }
             Spell::Steam => {
                 let offset = PI / 10.0;
-                for _i in 0..(bar.fire + bar.water) * 3 {
+                for _i in 0..charged((bar.fire + bar.water) * 3, ev.charge) {
                     let dir = (mouse_coords.0 - origin.truncate()).normalize_or_zero();
                     let angle = dir.y.atan2(dir.x) + rng.gen_range(-offset..offset);
 
-                    ev_spawn_projectile.send(SpawnProjectileEvent {
+                    projectiles.push(SpawnProjectileEvent {
                         texture_path: "textures/small_fire.png".to_string(),
                         color,
                         translation: origin,
@@ -647,12 +965,31 @@ fn cast_spell(
                         is_friendly: true,
                         trajectory: crate::projectile::Trajectory::Straight,
                         can_go_through_walls: false,
+                        modifiers: vec![],
                     });
                 }
+
+                //после залпа остается облако пара, не дальше 96 пикселей от игрока
+                let to_cursor = (mouse_coords.0 - origin.truncate()).clamp_length_max(96.);
+
+                ev_spawn_zone.send(SpawnDamageZoneEvent {
+                    position: origin + to_cursor.extend(0.),
+                    shape: ZoneShape::Circle,
+                    radius: (24. + 8. * (bar.fire + bar.water) as f32) * power,
+                    duration: 2. + bar.len() as f32 * 0.5,
+                    tick_rate: 0.5,
+                    damage: (dmg / 10).max(1) as i32,
+                    element: Some(element),
+                    color: element.color().with_alpha(0.5),
+                    is_friendly: true,
+                    follow_owner: None,
+                    growth: ZoneGrowth::EaseOut { from: 0.3 },
+                    effect: None,
+                });
             }
             Spell::Shield => {
                 ev_spawn_shield.send(SpawnShieldEvent {
-                    duration: bar.earth as f32 * 2.
+                    duration: bar.earth as f32 * 2. * power
                         + *inventory.amount_of_item(ItemType::Shield) as f32,
                     owner: player_e,
                     is_friendly: true,
@@ -666,11 +1003,15 @@ fn cast_spell(
                     lifetime: 1.5 * bar.len() as f32
                         + *inventory.amount_of_item(ItemType::ElementWheel) as f32, // seconds
                     strength: 1_000. * bar.len() as f32,
+                    radius: (48. + 8. * bar.len() as f32) * power,
+                    falloff: 1.,
+                    core_damage: 5 * bar.len() as i32,
                 });
             }
             Spell::Blank => {
                 ev_spawn_blank.send(SpawnBlankEvent {
-                    range: bar.air as f32 * 2. + *inventory.amount_of_item(ItemType::Blank) as f32,
+                    range: bar.air as f32 * 2. * power
+                        + *inventory.amount_of_item(ItemType::Blank) as f32,
                     position: origin,
                     speed: 10.0,
                     is_friendly: true,
@@ -684,6 +1025,7 @@ fn cast_spell(
                     owner: Some(player_e),
                     loot: None,
                     exp_amount: -1,
+                    elite: EliteRoll::Never,
                 });
             }
             Spell::WaterElemental => {
@@ -694,6 +1036,7 @@ fn cast_spell(
                     owner: Some(player_e),
                     loot: None,
                     exp_amount: -1,
+                    elite: EliteRoll::Never,
                 });
             }
             Spell::EarthElemental => {
@@ -704,6 +1047,7 @@ fn cast_spell(
                     owner: Some(player_e),
                     loot: None,
                     exp_amount: -1,
+                    elite: EliteRoll::Never,
                 });
             }
             Spell::AirElemental => {
@@ -714,8 +1058,122 @@ fn cast_spell(
                     owner: Some(player_e),
                     loot: None,
                     exp_amount: -1,
+                    elite: EliteRoll::Never,
+                });
+            }
+            //одна быстрая молния, после попадания перескакивает на соседей
+            Spell::Lightning => {
+                let dir = (mouse_coords.0 - origin.truncate()).normalize_or_zero();
+
+                projectiles.push(SpawnProjectileEvent {
+                    texture_path: "textures/lightning.png".to_string(),
+                    color: Color::srgb(1.5, 1.5, 2.0),
+                    translation: origin,
+                    angle: dir.to_angle(),
+                    collider_radius: 6.,
+                    speed: 420.,
+                    damage: (dmg as f32 * power) as u32,
+                    element,
+                    is_friendly: true,
+                    trajectory: crate::projectile::Trajectory::Straight,
+                    can_go_through_walls: false,
+                    modifiers: vec![ProjectileModifier::Chaining {
+                        jumps: bar.len() as u32,
+                        radius: 96.,
+                        falloff: 0.8,
+                    }],
+                });
+            }
+            Spell::Ice => {
+                let count = charged(bar.water, ev.charge);
+                let offset = PI / 16.0;
+                let dir = (mouse_coords.0 - origin.truncate()).normalize_or_zero();
+
+                for i in 0..count {
+                    //веером, средний осколок - точно в курсор
+                    let spread = offset * (i as f32 - (count - 1) as f32 / 2.);
+
+                    projectiles.push(SpawnProjectileEvent {
+                        texture_path: "textures/ice_shard.png".to_string(),
+                        color: Color::WHITE,
+                        translation: origin,
+                        angle: dir.to_angle() + spread,
+                        collider_radius: 6.,
+                        speed: 180.,
+                        damage: dmg / bar.water as u32,
+                        element,
+                        is_friendly: true,
+                        trajectory: crate::projectile::Trajectory::Straight,
+                        can_go_through_walls: false,
+                        modifiers: vec![ProjectileModifier::Freezing {
+                            duration: 0.5 + 0.5 * bar.air as f32,
+                        }],
+                    });
+                }
+            }
+            //лужи магмы вокруг курсора, стоять в них больно и вязко
+            Spell::Magma => {
+                let count = charged(bar.fire, ev.charge);
+
+                for _i in 0..count {
+                    let scatter = Vec2::new(rng.gen_range(-40.0..40.0), rng.gen_range(-40.0..40.0));
+
+                    ev_spawn_zone.send(SpawnDamageZoneEvent {
+                        position: (mouse_coords.0 + scatter).extend(0.),
+                        shape: ZoneShape::Circle,
+                        radius: 20. + 6. * bar.earth as f32,
+                        duration: 3. + bar.earth as f32,
+                        tick_rate: 0.5,
+                        damage: (dmg / (4 * count)).max(1) as i32,
+                        element: Some(element),
+                        color: Color::srgba(1.2, 0.4, 0.1, 0.7),
+                        is_friendly: true,
+                        follow_owner: None,
+                        growth: ZoneGrowth::Linear { from: 0.4 },
+                        effect: Some(ZoneEffect::Slow { factor: 0.5 }),
+                    });
+                }
+            }
+            Spell::Sandstorm => {
+                ev_spawn_zone.send(SpawnDamageZoneEvent {
+                    position: mouse_coords.0.extend(0.),
+                    shape: ZoneShape::Circle,
+                    radius: (32. + 8. * bar.len() as f32) * power,
+                    duration: 3. + bar.air as f32,
+                    tick_rate: 0.5,
+                    damage: (dmg / 10).max(1) as i32,
+                    element: Some(element),
+                    color: Color::srgba(0.9, 0.75, 0.45, 0.6),
+                    is_friendly: true,
+                    follow_owner: None,
+                    growth: ZoneGrowth::Linear { from: 0.5 },
+                    effect: Some(ZoneEffect::Blind),
+                });
+            }
+            Spell::Meteor => {
+                ev_spawn_meteor.send(SpawnMeteorEvent {
+                    target: mouse_coords.0,
+                    damage: (dmg as f32 * 2. * power) as u32,
+                    radius: (32. + 8. * bar.len() as f32) * power,
+                    fall_time: 0.8,
                 });
             }
+            //потоки запускаются через StartChannelEvent
+            Spell::Flamethrower | Spell::WaterBeam => {}
+        }
+
+        //разброс посоха меняет только конусы, кольца и кружащий огонь остаются как есть
+        let aim = matches!(ev.spell, Spell::Water | Spell::Steam | Spell::Ice)
+            .then(|| (mouse_coords.0 - origin.truncate()).to_angle());
+
+        for mut projectile in projectiles {
+            equipped_wand.apply(&mut projectile, aim);
+            ev_spawn_projectile.send(projectile);
         }
     }
 }
+
+//сколько снарядов выпустить с учетом заряда
+fn charged(amount: u8, charge: f32) -> u32 {
+    (amount as f32 * (1. + charge)).round() as u32
+}
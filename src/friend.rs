@@ -1,4 +1,5 @@
 use crate::{blank_spell::SpawnBlankEvent, mobs::{OnDeathEffect, OnDeathEffectEvent, OnHitEffect, OnHitEffectEvent, PickupItemQueue, ProjectileType}};
+use crate::{alert::SpawnAlertEvent, mobs::{Enemy, Orbital, SummonQueue}, mouse_position::MouseCoords};
 //all things about mobs and their spawn/behaviour
 ///add mobs with kinematic body type
 #[allow(unused)]
@@ -28,15 +29,56 @@ pub struct FriendPlugin;
 
 impl Plugin for FriendPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.init_resource::<SummonCommand>().add_systems(
             Update,
-            (friend_damage_mob, damage_friends).run_if(in_state(GameState::InGame)),
+            (
+                friend_damage_mob,
+                damage_friends,
+                issue_summon_command,
+                obey_summon_command,
+            )
+                .run_if(in_state(GameState::InGame)),
         );
     }
 }
 
 #[derive(Component, Default)]
 pub struct Friend;
+
+//приказ всем призванным игрока, без приказа они сами ищут врагов и ходят за игроком
+#[derive(Resource, Default, Clone, Copy, PartialEq)]
+pub enum SummonCommand {
+    #[default]
+    Follow,
+    Attack(Entity),
+    Hold,
+    Return,
+}
+
+impl SummonCommand {
+    pub fn label(&self) -> &str {
+        match self {
+            SummonCommand::Follow => "За мной",
+            SummonCommand::Attack(_) => "В атаку",
+            SummonCommand::Hold => "Стоять",
+            SummonCommand::Return => "Ко мне",
+        }
+    }
+}
+
+const FOLLOW_KEY: KeyCode = KeyCode::KeyF;
+const ATTACK_KEY: KeyCode = KeyCode::KeyG;
+const HOLD_KEY: KeyCode = KeyCode::KeyH;
+const RETURN_KEY: KeyCode = KeyCode::KeyR;
+
+//как близко к курсору должен быть враг, чтобы его можно было указать целью
+const ATTACK_PICK_RADIUS: f32 = 24.;
+//на таком расстоянии от игрока вернувшиеся снова ходят сами
+const RETURN_RADIUS: f32 = 40.;
+
+//точка, где призванный стоит по приказу Hold
+#[derive(Component)]
+pub struct HoldPosition(pub Vec2);
 ///maybe add contact damage or add some melee attacks?
 
 ///спавн именно особых энтити, не поднятие дохлых, дохлых поднимать можно через mob_spawn
@@ -196,3 +238,127 @@ pub fn damage_friends(
         }
     }
 }
+
+fn issue_summon_command(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse_coords: Res<MouseCoords>,
+    mut command: ResMut<SummonCommand>,
+    enemy_query: Query<(Entity, &Transform), (With<Enemy>, With<Mob>)>,
+    hold_query: Query<Entity, With<HoldPosition>>,
+    mut ev_spawn_alert: EventWriter<SpawnAlertEvent>,
+) {
+    let new_command = if keyboard.just_pressed(FOLLOW_KEY) {
+        SummonCommand::Follow
+    } else if keyboard.just_pressed(HOLD_KEY) {
+        SummonCommand::Hold
+    } else if keyboard.just_pressed(RETURN_KEY) {
+        SummonCommand::Return
+    } else if keyboard.just_pressed(ATTACK_KEY) {
+        let Some((target_e, target_transform)) = enemy_query
+            .iter()
+            .filter(|(_, t)| t.translation.truncate().distance(mouse_coords.0) <= ATTACK_PICK_RADIUS)
+            .min_by(|(_, a), (_, b)| {
+                a.translation
+                    .truncate()
+                    .distance(mouse_coords.0)
+                    .total_cmp(&b.translation.truncate().distance(mouse_coords.0))
+            })
+        else {
+            return;
+        };
+
+        ev_spawn_alert.send(SpawnAlertEvent {
+            position: target_transform
+                .translation
+                .truncate()
+                .with_y(target_transform.translation.y + 16.),
+            attack_alert: true,
+            spotted_by: None,
+        });
+
+        SummonCommand::Attack(target_e)
+    } else {
+        return;
+    };
+
+    //места для Hold запоминаются заново при каждом приказе
+    for hold_e in hold_query.iter() {
+        commands.entity(hold_e).remove::<HoldPosition>();
+    }
+
+    *command = new_command;
+}
+
+//приказ перебивает скорость, которую выставили системы состояний в FixedUpdate,
+//сами состояния не трогаем, так что атаки по врагам в радиусе продолжаются
+fn obey_summon_command(
+    mut commands: Commands,
+    mut command: ResMut<SummonCommand>,
+    player_query: Query<(&Transform, &SummonQueue), With<Player>>,
+    mut summon_query: Query<
+        (
+            &Transform,
+            &mut LinearVelocity,
+            Option<&SearchAndPursue>,
+            Option<&HoldPosition>,
+        ),
+        (With<Friend>, With<Mob>, Without<Player>, Without<Orbital>, Without<Stun>),
+    >,
+    target_query: Query<&Transform, (With<Enemy>, With<Mob>)>,
+    time: Res<Time>,
+) {
+    let Ok((player_transform, summon_queue)) = player_query.get_single() else {
+        return;
+    };
+
+    let target_pos = match *command {
+        SummonCommand::Follow => return,
+        SummonCommand::Attack(target_e) => match target_query.get(target_e) {
+            Ok(transform) => Some(transform.translation.truncate()),
+            Err(_) => {
+                *command = SummonCommand::Follow;
+                return;
+            }
+        },
+        SummonCommand::Hold => None,
+        SummonCommand::Return => Some(player_transform.translation.truncate()),
+    };
+
+    let mut all_returned = true;
+
+    for summon_e in summon_queue.queue.iter().filter_map(|unit| unit.entity) {
+        let Ok((transform, mut linvel, search, hold)) = summon_query.get_mut(summon_e) else {
+            continue;
+        };
+
+        let pos = transform.translation.truncate();
+        let speed = search.map_or(2000., |search| search.speed);
+
+        let (goal, stop_radius) = match *command {
+            SummonCommand::Hold => match hold {
+                Some(hold) => (hold.0, 8.),
+                None => {
+                    commands.entity(summon_e).insert(HoldPosition(pos));
+                    (pos, 8.)
+                }
+            },
+            SummonCommand::Return => (target_pos.unwrap(), RETURN_RADIUS),
+            _ => (target_pos.unwrap(), 16.),
+        };
+
+        if pos.distance(goal) <= stop_radius {
+            if *command == SummonCommand::Hold {
+                linvel.0 = Vec2::ZERO;
+            }
+            continue;
+        }
+
+        all_returned = false;
+        linvel.0 = (goal - pos).normalize_or_zero() * speed * time.delta_seconds();
+    }
+
+    if *command == SummonCommand::Return && all_returned {
+        *command = SummonCommand::Follow;
+    }
+}
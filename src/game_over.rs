@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::{ui::*, utils::*, GameState};
+use crate::{summon_persistence::SummonRunStats, ui::*, utils::*, GameState};
 
 pub struct GameOverPlugin;
 
@@ -24,13 +24,14 @@ impl Plugin for GameOverPlugin {
                     despawn_all_with::<crate::mobs::Mob>,
                     despawn_all_with::<crate::wand::Wand>,
                     despawn_all_with::<crate::projectile::Projectile>,
-                    despawn_all_with::<crate::shield_spell::Shield>,
                     despawn_all_with::<crate::item::Item>,
                     despawn_all_with::<crate::ui::ElementBarUI>,
                     despawn_all_with::<crate::ui::ExpBarUI>,
                     despawn_all_with::<crate::ui::HPBarUI>,
                     despawn_all_with::<crate::ui::ItemUI>,
+                    despawn_all_with::<crate::ui::BossBarUI>,
                     despawn_all_with::<crate::obstacles::Obstacle>,
+                    despawn_all_with::<crate::hazards::Hazard>,
                 ),
             );
     }
@@ -39,7 +40,11 @@ impl Plugin for GameOverPlugin {
 #[derive(Component)]
 pub struct GameOverUI;
 
-fn spawn_gameover_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn spawn_gameover_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    summon_stats: Res<SummonRunStats>,
+) {
     let slicer = TextureSlicer {
         border: BorderRect::square(16.0),
         center_scale_mode: SliceScaleMode::Stretch,
@@ -62,6 +67,24 @@ fn spawn_gameover_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
         })
         .insert(GameOverUI)
         .with_children(|parent| {
+            if summon_stats.kills > 0 || summon_stats.carried_over > 0 {
+                parent.spawn(TextBundle::from_section(
+                    format!(
+                        "призванные: убийств {}, повышений {}, лучший уровень {}, перенесено {}",
+                        summon_stats.kills,
+                        summon_stats.levels_gained,
+                        summon_stats.best_level.max(1),
+                        summon_stats.carried_over
+                    ),
+                    TextStyle {
+                        font: asset_server.load("fonts/ebbe_bold.ttf"),
+                        font_size: 16.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ));
+            }
+
             parent
                 .spawn(ButtonBundle {
                     style: Style {
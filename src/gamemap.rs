@@ -5,7 +5,7 @@ use rand::Rng;
 use std::collections::HashMap;
 
 use crate::{
-    camera::YSort, chapter::ChapterManager, health::Health, obstacles::Obstacle, utils::get_random_index_with_weight, GameLayer, GameState
+    camera::YSort, chapter::ChapterManager, hazards::HazardType, health::Health, obstacles::Obstacle, secret_room::{CrackedWall, SecretRoomFloor}, utils::get_random_index_with_weight, GameLayer, GameState
 };
 
 pub const ROOM_SIZE: i32 = 32;
@@ -44,10 +44,16 @@ pub struct Walker {
 pub struct Tile{
     pub tiletype: TileType,
     pub mob_count: i16,
+    pub hazard: Option<HazardType>,
 }
 
 impl Tile{
-    pub fn new(tiletype: TileType, mob_count: i16) -> Self {Tile{tiletype, mob_count}}
+    pub fn new(tiletype: TileType, mob_count: i16) -> Self {Tile{tiletype, mob_count, hazard: None}}
+
+    // доп. цена прохода по тайлу для поиска пути
+    pub fn cost(&self) -> u16 {
+        self.hazard.map_or(0, |hazard| hazard.cost())
+    }
 }
 
 #[derive(Resource)]
@@ -63,10 +69,31 @@ impl Default for Map{
     }
 }
 
+//тайл, на котором стоит объект
+pub fn tile_of(translation: Vec3) -> (u16, u16) {
+    (
+        (translation.x / TILE_SIZE).round().max(0.) as u16,
+        (translation.y / TILE_SIZE).round().max(0.) as u16,
+    )
+}
+
 fn init_map(mut commands: Commands){
     commands.insert_resource(Map::default());
 }
 
+// тайная комната: треснувшая стена, которую можно сломать, и тайлы пола за ней
+#[derive(Clone)]
+pub struct SecretRoom {
+    pub cracked_wall: (u16, u16),
+    pub tiles: Vec<(u16, u16)>,
+}
+
+impl SecretRoom {
+    pub fn contains(&self, tile: (u16, u16)) -> bool {
+        self.tiles.contains(&tile)
+    }
+}
+
 #[derive(Resource)]
 pub struct LevelGenerator {
     pub grid: Vec<Vec<TileType>>,
@@ -80,6 +107,8 @@ pub struct LevelGenerator {
     obstacles: Vec<(f32, f32)>,
     max_walkers: usize,
     percent_to_fill: f32,
+    chance_secret_room: f32,
+    pub secret_room: Option<SecretRoom>,
 }
 
 impl Default for LevelGenerator {
@@ -96,6 +125,8 @@ impl Default for LevelGenerator {
             obstacles: vec![],
             max_walkers: 16,
             percent_to_fill: 0.1,
+            chance_secret_room: 0.35,
+            secret_room: None,
         }
     }
 }
@@ -107,6 +138,7 @@ impl LevelGenerator {
         self.create_floors();
         self.create_walls();
         self.remove_single_walls();
+        self.create_secret_room();
     }
 
     fn random_direction(&self) -> (f32, f32) {
@@ -141,6 +173,7 @@ impl LevelGenerator {
         self.walkers.push(new_walker);
 
         self.obstacles = Vec::new();
+        self.secret_room = None;
     }
 
     fn create_floors(&mut self) {
@@ -264,6 +297,84 @@ impl LevelGenerator {
         }
     }
 
+    // ищем стену рядом с полом, за которой есть место под комнату 3x3 со стенами вокруг
+    // стена между пещерой и комнатой становится треснувшей
+    fn create_secret_room(&mut self) {
+        let mut rng = rand::thread_rng();
+        if rng.gen::<f32>() > self.chance_secret_room {
+            return;
+        }
+
+        let mut candidates: Vec<((usize, usize), (isize, isize))> = vec![];
+
+        for x in 1..self.room_width - 1 {
+            for y in 1..self.room_height - 1 {
+                if self.grid[x][y] != TileType::Wall {
+                    continue;
+                }
+
+                for dir in [(0isize, 1isize), (0, -1), (1, 0), (-1, 0)] {
+                    let floor = (x as isize - dir.0, y as isize - dir.1);
+                    if self.grid[floor.0 as usize][floor.1 as usize] != TileType::Floor {
+                        continue;
+                    }
+
+                    if self.secret_room_fits((x, y), dir) {
+                        candidates.push(((x, y), dir));
+                    }
+                }
+            }
+        }
+
+        if candidates.is_empty() {
+            return;
+        }
+
+        let (wall, dir) = candidates[rng.gen_range(0..candidates.len())];
+        let center = (wall.0 as isize + dir.0 * 2, wall.1 as isize + dir.1 * 2);
+
+        let mut tiles = vec![];
+        for x in center.0 - 2..=center.0 + 2 {
+            for y in center.1 - 2..=center.1 + 2 {
+                if (x - center.0).abs() <= 1 && (y - center.1).abs() <= 1 {
+                    self.grid[x as usize][y as usize] = TileType::Floor;
+                    tiles.push((x as u16, y as u16));
+                }
+                else {
+                    self.grid[x as usize][y as usize] = TileType::Wall;
+                }
+            }
+        }
+
+        self.secret_room = Some(SecretRoom {
+            cracked_wall: (wall.0 as u16, wall.1 as u16),
+            tiles,
+        });
+    }
+
+    // комната 5x5 (пол 3x3 + стены) должна полностью лежать в пустоте, кроме самой треснувшей стены
+    fn secret_room_fits(&self, wall: (usize, usize), dir: (isize, isize)) -> bool {
+        let center = (wall.0 as isize + dir.0 * 2, wall.1 as isize + dir.1 * 2);
+
+        if center.0 < 2 || center.1 < 2
+        || center.0 > self.room_width as isize - 3 || center.1 > self.room_height as isize - 3 {
+            return false;
+        }
+
+        for x in center.0 - 2..=center.0 + 2 {
+            for y in center.1 - 2..=center.1 + 2 {
+                if (x as usize, y as usize) == wall {
+                    continue;
+                }
+                if self.grid[x as usize][y as usize] != TileType::Empty {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
     fn number_of_floors(&self) -> usize {
         self.grid.iter().flat_map(|row| row.iter()).filter(|&&space| space == TileType::Floor).count()
     }
@@ -284,6 +395,7 @@ pub fn spawn_map(
     let room_width = room.room_width;
     let grid = &room.grid;
     let obstacles = &room.obstacles;
+    let secret_room = &room.secret_room;
 
     for x in 0..room_width {
         for y in 0..room_height {
@@ -293,7 +405,16 @@ pub fn spawn_map(
                     let layout = TextureAtlasLayout::from_grid(UVec2::splat(32), 4, 1, None, None);
                     let texture_atlas_layout = texture_atlas_layouts.add(layout);
 
-                    map.map.insert((x as u16, y as u16), Tile::new(TileType::Floor, 0));
+                    let is_secret = secret_room.as_ref().is_some_and(|secret| secret.contains((x as u16, y as u16)));
+
+                    // пол тайной комнаты недоступен, пока стену не сломают
+                    if is_secret {
+                        map.map.insert((x as u16, y as u16), Tile::new(TileType::Empty, 0));
+                    }
+                    else {
+                        map.map.insert((x as u16, y as u16), Tile::new(TileType::Floor, 0));
+                    }
+
                     let floor = commands.spawn((
                         SpriteBundle {
                             sprite: Sprite {
@@ -316,6 +437,12 @@ pub fn spawn_map(
                     .insert(Floor)
                     .id();
 
+                if is_secret {
+                    commands.entity(floor)
+                        .insert(SecretRoomFloor)
+                        .insert(Visibility::Hidden);
+                }
+
                 if y < room_height
                 && grid[x as usize][y as usize + 1] == TileType::Wall {
                     commands.entity(floor).with_children(|parent| {
@@ -347,7 +474,7 @@ pub fn spawn_map(
                         }
                     };
 
-                    commands
+                    let wall = commands
                         .spawn(SpriteBundle {
                             texture: asset_server.load(texture_path),
                             transform: Transform::from_xyz(
@@ -361,7 +488,17 @@ pub fn spawn_map(
                         .insert(Collider::rectangle(TILE_SIZE - 0.01, TILE_SIZE - 0.01))
                         .insert(CollisionLayers::new(GameLayer::Wall, [GameLayer::Enemy, GameLayer::Player, GameLayer::Projectile]))
                         .insert(Wall)
-                        .insert(YSort(16.0));
+                        .insert(YSort(16.0))
+                        .id();
+
+                    if secret_room.as_ref().is_some_and(|secret| secret.cracked_wall == (x as u16, y as u16)) {
+                        commands.entity(wall)
+                            .insert(CrackedWall)
+                            .insert(Sprite {
+                                color: Color::srgb(0.75, 0.7, 0.7),
+                                ..default()
+                            });
+                    }
                 },
                 TileType::Empty => {
                     map.map.insert((x as u16, y as u16), Tile::new(TileType::Empty, 0));
@@ -0,0 +1,403 @@
+//опасные тайлы для каждого биома: зыбучий песок в пустыне, лужи в джунглях, лава в аду и шипы везде
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    chapter::ChapterManager,
+    elements::{ElementResistance, ElementType},
+    gamemap::{spawn_map, tile_of, Floor, Map, TileType, ROOM_SIZE, TILE_SIZE},
+    health::{Health, Hit},
+    invincibility::Invincibility,
+    mobs::Mob,
+    particles::{ParticlePattern, SpawnParticlesEvent},
+    pathfinding::{GraphChange, UpdateGraphEvent},
+    player::Player,
+    projectile::Projectile,
+    GameState,
+};
+
+const LAVA_DAMAGE: i32 = 4;
+const SPIKES_DAMAGE: i32 = 10;
+//множитель скорости в зыбучем песке, замедляет damage_zone::slow_in_zones
+pub const QUICKSAND_SLOW: f32 = 0.4;
+
+pub struct HazardPlugin;
+
+impl Plugin for HazardPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HazardTick::default())
+            .add_systems(OnEnter(GameState::Loading), spawn_hazards.after(spawn_map))
+            .add_systems(OnExit(GameState::InGame), clear_hazard_effects)
+            .add_systems(
+                Update,
+                (
+                    hazard_effects,
+                    tick_spike_traps,
+                    tick_soaked,
+                    hazard_element_interactions,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum HazardType {
+    Lava,
+    Puddle,
+    Quicksand,
+    Spikes,
+}
+
+impl HazardType {
+    //насколько мобы не хотят наступать на тайл
+    pub fn cost(&self) -> u16 {
+        match self {
+            HazardType::Lava => 8,
+            HazardType::Spikes => 4,
+            HazardType::Quicksand => 3,
+            HazardType::Puddle => 1,
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            HazardType::Lava => Color::srgb(2.5, 0.8, 0.2),
+            HazardType::Puddle => Color::srgba(0.3, 0.6, 1.2, 0.6),
+            HazardType::Quicksand => Color::srgba(0.9, 0.7, 0.35, 0.8),
+            HazardType::Spikes => Color::srgba(0.5, 0.5, 0.55, 0.5),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Hazard {
+    pub tile: (u16, u16),
+}
+
+#[derive(Component)]
+pub struct SpikeTrap {
+    timer: Timer,
+    pub active: bool,
+}
+
+impl Default for SpikeTrap {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(rand::thread_rng().gen_range(1.0..2.0), TimerMode::Repeating),
+            active: false,
+        }
+    }
+}
+
+//намокший в луже получает больше урона от воды, эффект держится еще немного после выхода
+#[derive(Component)]
+pub struct Soaked {
+    timer: Timer,
+}
+
+impl Default for Soaked {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(2.0, TimerMode::Once),
+        }
+    }
+}
+
+impl Soaked {
+    pub fn calculate_for(&self, damage: &mut i32, element: Option<ElementType>) {
+        if element == Some(ElementType::Water) {
+            *damage = (*damage as f32 * 1.5).round() as i32;
+        }
+    }
+}
+
+#[derive(Resource)]
+struct HazardTick(Timer);
+
+impl Default for HazardTick {
+    fn default() -> Self {
+        Self(Timer::from_seconds(0.5, TimerMode::Repeating))
+    }
+}
+
+fn spawn_hazards(
+    mut commands: Commands,
+    mut map: ResMut<Map>,
+    chapter_manager: Res<ChapterManager>,
+) {
+    let pool_type = match chapter_manager.get_current_chapter() {
+        1 => HazardType::Quicksand,
+        2 => HazardType::Puddle,
+        3 => HazardType::Lava,
+        _ => return,
+    };
+
+    let center = (ROOM_SIZE / 2) as i32;
+
+    //не ставим ничего рядом со спавном игрока
+    let floors: Vec<(u16, u16)> = map
+        .map
+        .iter()
+        .filter(|(pos, tile)| {
+            tile.tiletype == TileType::Floor
+                && (pos.0 as i32 - center).abs() + (pos.1 as i32 - center).abs() > 3
+        })
+        .map(|(pos, _)| *pos)
+        .collect();
+
+    if floors.is_empty() {
+        return;
+    }
+
+    let mut rng = rand::thread_rng();
+
+    //лужи/лава/песок - пятна из нескольких тайлов
+    for _ in 0..rng.gen_range(2..=4) {
+        let mut tile = floors[rng.gen_range(0..floors.len())];
+
+        for _ in 0..rng.gen_range(2..=6) {
+            spawn_hazard_tile(&mut commands, &mut map, tile, pool_type);
+
+            let dir = [(0, 1), (0, -1), (1, 0), (-1, 0)][rng.gen_range(0..4)];
+            let next = (
+                (tile.0 as i32 + dir.0) as u16,
+                (tile.1 as i32 + dir.1) as u16,
+            );
+
+            if floors.contains(&next) {
+                tile = next;
+            }
+        }
+    }
+
+    for _ in 0..rng.gen_range(1..=3) {
+        let tile = floors[rng.gen_range(0..floors.len())];
+        spawn_hazard_tile(&mut commands, &mut map, tile, HazardType::Spikes);
+    }
+}
+
+pub fn spawn_hazard_tile(
+    commands: &mut Commands,
+    map: &mut Map,
+    tile: (u16, u16),
+    hazard_type: HazardType,
+) {
+    let Some(map_tile) = map.map.get_mut(&tile) else {
+        return;
+    };
+
+    if map_tile.hazard.is_some() {
+        return;
+    }
+
+    map_tile.hazard = Some(hazard_type);
+
+    let hazard = commands
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                color: hazard_type.color(),
+                custom_size: Some(Vec2::splat(TILE_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_xyz(
+                tile.0 as f32 * TILE_SIZE,
+                tile.1 as f32 * TILE_SIZE,
+                -99.5,
+            ),
+            ..default()
+        })
+        .insert(Hazard { tile })
+        .id();
+
+    if hazard_type == HazardType::Spikes {
+        commands.entity(hazard).insert(SpikeTrap::default());
+    }
+}
+
+//постоянные эффекты: лава жжет, лужа мочит. Песок замедляет вместе с зонами в slow_in_zones
+fn hazard_effects(
+    mut commands: Commands,
+    mut tick: ResMut<HazardTick>,
+    map: Res<Map>,
+    mut actor_query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut Health,
+            Option<&ElementResistance>,
+            Has<Invincibility>,
+            Has<Mob>,
+        ),
+        Or<(With<Player>, With<Mob>)>,
+    >,
+    time: Res<Time>,
+) {
+    tick.0.tick(time.delta());
+
+    for (entity, transform, mut health, resistance, invincible, is_mob) in actor_query.iter_mut() {
+        let hazard = map
+            .map
+            .get(&tile_of(transform.translation))
+            .and_then(|tile| tile.hazard);
+
+        match hazard {
+            Some(HazardType::Lava) => {
+                if tick.0.just_finished() && !invincible {
+                    //сопротивление игрока считается в hit_player, мобов - здесь
+                    let mut damage = LAVA_DAMAGE;
+                    if let Some(resistance) = resistance.filter(|_| is_mob) {
+                        resistance.calculate_for(&mut damage, Some(ElementType::Fire));
+                    }
+
+                    health.hit_queue.push(Hit {
+                        damage,
+                        element: Some(ElementType::Fire),
+                        direction: Vec3::ZERO,
+                    });
+                }
+            }
+            Some(HazardType::Puddle) => {
+                commands.entity(entity).insert(Soaked::default());
+            }
+            _ => {}
+        }
+    }
+}
+
+fn tick_spike_traps(
+    mut trap_query: Query<(&Hazard, &mut SpikeTrap, &mut Sprite)>,
+    mut actor_query: Query<
+        (
+            &Transform,
+            &mut Health,
+            Option<&ElementResistance>,
+            Has<Invincibility>,
+            Has<Mob>,
+        ),
+        Or<(With<Player>, With<Mob>)>,
+    >,
+    time: Res<Time>,
+) {
+    for (hazard, mut trap, mut sprite) in trap_query.iter_mut() {
+        trap.timer.tick(time.delta());
+
+        if !trap.timer.just_finished() {
+            continue;
+        }
+
+        trap.active = !trap.active;
+
+        if !trap.active {
+            sprite.color = HazardType::Spikes.color();
+            continue;
+        }
+
+        sprite.color = Color::srgb(1.5, 1.5, 1.6);
+
+        for (transform, mut health, resistance, invincible, is_mob) in actor_query.iter_mut() {
+            if !invincible && tile_of(transform.translation) == hazard.tile {
+                let mut damage = SPIKES_DAMAGE;
+                if let Some(resistance) = resistance.filter(|_| is_mob) {
+                    resistance.calculate_for(&mut damage, None);
+                }
+
+                health.hit_queue.push(Hit {
+                    damage,
+                    element: None,
+                    direction: Vec3::ZERO,
+                });
+            }
+        }
+    }
+}
+
+//игрок переживает уровень, поэтому снимаем с него эффекты тайлов
+fn clear_hazard_effects(
+    mut commands: Commands,
+    player_query: Query<Entity, With<Player>>,
+) {
+    if let Ok(player_e) = player_query.get_single() {
+        commands.entity(player_e).remove::<Soaked>();
+    }
+}
+
+fn tick_soaked(
+    mut commands: Commands,
+    mut soaked_query: Query<(Entity, &mut Soaked)>,
+    time: Res<Time>,
+) {
+    for (entity, mut soaked) in soaked_query.iter_mut() {
+        soaked.timer.tick(time.delta());
+
+        if soaked.timer.just_finished() {
+            commands.entity(entity).remove::<Soaked>();
+        }
+    }
+}
+
+//вода тушит лаву в камень, огонь испаряет лужи
+fn hazard_element_interactions(
+    mut commands: Commands,
+    mut map: ResMut<Map>,
+    projectile_query: Query<(&Transform, &Projectile)>,
+    hazard_query: Query<(Entity, &Hazard, &Transform)>,
+    mut ev_spawn_particles: EventWriter<SpawnParticlesEvent>,
+    mut ev_update_graph: EventWriter<UpdateGraphEvent>,
+) {
+    for (projectile_transform, projectile) in projectile_query.iter() {
+        let tile_pos = tile_of(projectile_transform.translation);
+
+        //на чтение без get_mut, иначе Map каждый кадр помечается измененной
+        let Some(tile) = map.map.get(&tile_pos) else {
+            continue;
+        };
+
+        let turns_to_stone = match (projectile.element, tile.hazard) {
+            (ElementType::Water, Some(HazardType::Lava)) => true,
+            (ElementType::Fire, Some(HazardType::Puddle)) => false,
+            _ => continue,
+        };
+
+        if let Some(tile) = map.map.get_mut(&tile_pos) {
+            tile.hazard = None;
+        }
+
+        //у тайла поменялась цена, поля потока и пути через него надо пересчитать
+        ev_update_graph.send(UpdateGraphEvent {
+            tile: tile_pos,
+            change: GraphChange::ClearHazard,
+        });
+
+        for (hazard_e, hazard, hazard_transform) in hazard_query.iter() {
+            if hazard.tile != tile_pos {
+                continue;
+            }
+
+            commands.entity(hazard_e).despawn();
+
+            if turns_to_stone {
+                commands
+                    .spawn(SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::srgb(0.35, 0.3, 0.3),
+                            custom_size: Some(Vec2::splat(TILE_SIZE)),
+                            ..default()
+                        },
+                        transform: *hazard_transform,
+                        ..default()
+                    })
+                    .insert(Floor);
+            }
+
+            ev_spawn_particles.send(SpawnParticlesEvent {
+                pattern: ParticlePattern::Circle { radius: 8. },
+                position: hazard_transform.translation,
+                amount: 6,
+                color: ElementType::Steam.color(),
+                speed: 10.,
+                rotate: false,
+            });
+        }
+    }
+}
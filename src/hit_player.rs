@@ -9,11 +9,12 @@ use crate::{
     camera::CameraShakeEvent, 
     friend::Friend, 
     elements::ElementResistance, 
+    hazards::Soaked,
     health::{Health, Hit}, 
     invincibility::Invincibility, 
-    mobs::Mob, 
+    mobs::{Mob, MobDamageDealtEvent}, 
     player::{Player, PlayerDeathEvent, PlayerStats}, 
-    projectile::{Friendly, Hostile, Projectile}, 
+    projectile::{Friendly, Hostile, Projectile, ProjectileImpactEvent}, 
     GameState
 };
 
@@ -29,6 +30,7 @@ impl Plugin for HitPlayerPlugin {
 fn hit_player(
     mob_query: Query<(Entity, &Mob), (Without<Friend>, Without<Player>)>,
     mut player_query: Query<(&CollidingEntities, &mut Health), (With<Player>, Without<Invincibility>)>,
+    mut ev_damage_dealt: EventWriter<MobDamageDealtEvent>,
 ) {
     let Ok((colliding_e, mut health)) = player_query.get_single_mut() else {
         return;
@@ -41,6 +43,10 @@ fn hit_player(
                 element: None,
                 direction: Vec3::ZERO,
             });
+            ev_damage_dealt.send(MobDamageDealtEvent {
+                mob: mob_e,
+                damage: mob.damage,
+            });
 
             return;
         }
@@ -50,16 +56,21 @@ fn hit_player(
 //damage by projectiles
 fn proj_hit_player(
     mut commands: Commands,
-    mut projectile_query: Query<(Entity, &mut Projectile), With<Hostile>>,
-    mut player_query: Query<(&CollidingEntities, &mut Health), (With<Player>, Without<Invincibility>)>,
+    mut projectile_query: Query<&mut Projectile, With<Hostile>>,
+    mut player_query: Query<(Entity, &CollidingEntities, &mut Health), (With<Player>, Without<Invincibility>)>,
     player_stats: Res<PlayerStats>,
+    mut ev_impact: EventWriter<ProjectileImpactEvent>,
 ) {
-    let Ok((colliding_e, mut health)) = player_query.get_single_mut() else {
+    let Ok((player_e, colliding_e, mut health)) = player_query.get_single_mut() else {
         return;
     };
 
-    for (proj_e, mut projectile) in projectile_query.iter_mut() {
-        if colliding_e.contains(&proj_e) {
+    for proj_e in colliding_e.iter().copied() {
+        let Ok(mut projectile) = projectile_query.get_mut(proj_e) else {
+            continue;
+        };
+
+        if projectile.can_hit(player_e) {
             let deflect_check: f32 = rand::thread_rng().gen_range(0.0..1.0);
 
             if deflect_check <= player_stats.projectile_deflect_chance {
@@ -75,7 +86,11 @@ fn proj_hit_player(
                 direction: Vec3::ZERO,
             });
 
-            commands.entity(proj_e).despawn();
+            projectile.register_hit(player_e);
+            ev_impact.send(ProjectileImpactEvent {
+                projectile: proj_e,
+                target: Some(player_e),
+            });
         }
     }
 
@@ -85,6 +100,7 @@ fn damage_player(
     mut commands: Commands,
     mut ev_death: EventWriter<PlayerDeathEvent>,
     mut player_query: Query<(Entity, &mut Health, &ElementResistance), With<Player>>,
+    soaked_query: Query<&Soaked>,
     player_stats: Res<PlayerStats>,
 
     mut ev_shake_camera: EventWriter<CameraShakeEvent>,
@@ -109,6 +125,10 @@ fn damage_player(
         let mut damage = hit.damage;
         resistance.calculate_for(&mut damage, hit.element);
 
+        if let Ok(soaked) = soaked_query.get(player_e) {
+            soaked.calculate_for(&mut damage, hit.element);
+        }
+
         // наносим урон
         health.damage(damage);
 
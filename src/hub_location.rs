@@ -1,4 +1,4 @@
-use crate::{camera::YSort, chapter::ChapterManager, gamemap::{Floor, Wall, ROOM_SIZE, TILE_SIZE}, item::{ItemDatabase, ItemDatabaseHandle, ItemType, SpawnItemEvent}, GameState};
+use crate::{camera::YSort, chapter::ChapterManager, gamemap::{Floor, Wall, ROOM_SIZE, TILE_SIZE}, item::{ItemDatabase, ItemDatabaseHandle, ItemType, SpawnItemEvent}, wand::{SpawnWandEvent, WandType}, GameState};
 use avian2d::prelude::*;
 use bevy::prelude::*;
 pub struct HubPlugin;
@@ -16,6 +16,7 @@ fn spawn_hub(
     mut commands: Commands,
     mut ev_spawn_portal: EventWriter<crate::level_completion::PortalEvent>,
     mut ev_spawn_item: EventWriter<crate::item::SpawnItemEvent>,
+    mut ev_spawn_wand: EventWriter<SpawnWandEvent>,
 
     item_database: Res<Assets<ItemDatabase>>,
     handle: Res<ItemDatabaseHandle>,
@@ -104,6 +105,14 @@ fn spawn_hub(
         });
     }
 
+    // посох на продажу, платим опытом
+    let wand_type: WandType = rand::random();
+    ev_spawn_wand.send(SpawnWandEvent {
+        pos: Vec3::new((lower + 1) as f32 * TILE_SIZE, (lower + 1) as f32 * TILE_SIZE, 1.),
+        wand_type,
+        price: wand_type.price()
+    });
+
     ev_spawn_portal.send(crate::level_completion::PortalEvent {
         pos: Vec3::new((upper - 1) as f32 * TILE_SIZE, (lower + 1) as f32 * TILE_SIZE, 1.0),
     });
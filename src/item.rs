@@ -38,20 +38,29 @@ pub enum ItemType { // Keep enum variants in alphabetical order or it will break
     Amulet,
     Aquarius,
     Bacon,
+    Bellows,
     Blank,
     BlindRage,
     BloodGoblet,
+    DesertRose,
     ElementWheel,
     Fan,
     FieryShard,
     GhostInTheShell,
     Glider,
     Heart,
+    Hourglass,
+    IceCrystal,
+    LightningRod,
     LizardTail,
+    Meteorite,
     Mineral,
     NotchedPickaxe,
+    Obsidian,
     Shield,
+    SoulLantern,
     SpeedPotion,
+    Trident,
     Valve,
     VampireTooth,
     WaterbendingScroll,
@@ -64,24 +73,33 @@ impl ItemType {
             0 => ItemType::Amulet,
             1 => ItemType::Aquarius,
             2 => ItemType::Bacon,
-            3 => ItemType::Blank,
-            4 => ItemType::BlindRage,
-            5 => ItemType::BloodGoblet,
-            6 => ItemType::ElementWheel,
-            7 => ItemType::Fan,
-            8 => ItemType::FieryShard,
-            9 => ItemType::GhostInTheShell,
-            10 => ItemType::Glider,
-            11 => ItemType::Heart,
-            12 => ItemType::LizardTail,
-            13 => ItemType::Mineral,
-            14 => ItemType::NotchedPickaxe,
-            15 => ItemType::Shield,
-            16 => ItemType::SpeedPotion,
-            17 => ItemType::Valve,
-            18 => ItemType::VampireTooth,
-            19 => ItemType::WaterbendingScroll,
-            20 => ItemType::WispInAJar,
+            3 => ItemType::Bellows,
+            4 => ItemType::Blank,
+            5 => ItemType::BlindRage,
+            6 => ItemType::BloodGoblet,
+            7 => ItemType::DesertRose,
+            8 => ItemType::ElementWheel,
+            9 => ItemType::Fan,
+            10 => ItemType::FieryShard,
+            11 => ItemType::GhostInTheShell,
+            12 => ItemType::Glider,
+            13 => ItemType::Heart,
+            14 => ItemType::Hourglass,
+            15 => ItemType::IceCrystal,
+            16 => ItemType::LightningRod,
+            17 => ItemType::LizardTail,
+            18 => ItemType::Meteorite,
+            19 => ItemType::Mineral,
+            20 => ItemType::NotchedPickaxe,
+            21 => ItemType::Obsidian,
+            22 => ItemType::Shield,
+            23 => ItemType::SoulLantern,
+            24 => ItemType::SpeedPotion,
+            25 => ItemType::Trident,
+            26 => ItemType::Valve,
+            27 => ItemType::VampireTooth,
+            28 => ItemType::WaterbendingScroll,
+            29 => ItemType::WispInAJar,
             _ => ItemType::Amulet, 
         }
     }
@@ -89,7 +107,7 @@ impl ItemType {
 
 impl Distribution<ItemType> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> ItemType {
-        ItemType::from_index(rng.gen_range(0..=20))
+        ItemType::from_index(rng.gen_range(0..=29))
     }
 }
 
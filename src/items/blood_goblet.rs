@@ -1,4 +1,4 @@
-//Кубок с кровью - дает регенерацию здоровья, но теперь заклинания тратят здоровье (зависит также от кол-ва копий)
+//Кубок с кровью - дает регенерацию здоровья, но теперь заклинания тратят здоровье вместо части маны (зависит также от кол-ва копий)
 use bevy::prelude::*;
 
 use crate::{
@@ -36,6 +36,8 @@ fn apply_effect(
         if ev.item_type == ItemType::BloodGoblet {
             player_stats.health_regen += 1;
             player_stats.spell_cast_hp_fee += 5;
+            //часть цены заклинаний платится кровью, а не маной
+            player_stats.mana_cost_mult *= 0.8;
 
             if let Ok(entity) = player_query.get_single() {
                 commands.entity(entity).insert(RegenHealth {
@@ -0,0 +1,30 @@
+//Песочные часы - заклинания перезаряжаются быстрее и стоят меньше маны за каждую копию
+use bevy::prelude::*;
+
+use crate::{
+    item::{
+        ItemPickedUpEvent,
+        ItemType
+    },
+    player::PlayerStats
+};
+
+pub struct HourglassPlugin;
+
+impl Plugin for HourglassPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, apply_effect);
+    }
+}
+
+fn apply_effect(
+    mut ev_item_picked_up: EventReader<ItemPickedUpEvent>,
+    mut player_stats: ResMut<PlayerStats>,
+) {
+    for ev in ev_item_picked_up.read() {
+        if ev.item_type == ItemType::Hourglass {
+            player_stats.cooldown_mult *= 0.85;
+            player_stats.mana_cost_mult *= 0.9;
+        }   
+    }
+}
@@ -10,6 +10,9 @@ use speed_potion::SpeedPotionPlugin;
 mod heart;
 use heart::HeartPlugin;
 
+mod hourglass;
+use hourglass::HourglassPlugin;
+
 mod amulet;
 use amulet::AmuletPlugin;
 
@@ -41,6 +44,7 @@ impl Plugin for ItemEffectsPlugin {
             BaconPlugin,
             SpeedPotionPlugin,
             HeartPlugin,
+            HourglassPlugin,
             AmuletPlugin,
             LizardTailPlugin,
             ResistanceItemsPlugin,
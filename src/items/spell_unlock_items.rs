@@ -36,6 +36,13 @@ fn apply_effect(
                 ItemType::Shield => (Some(Spell::Shield), "shield"),
                 ItemType::Blank => (Some(Spell::Blank), "blank"),
                 ItemType::Aquarius => (Some(Spell::WaterElemental), "water_elemental"),
+                ItemType::LightningRod => (Some(Spell::Lightning), "lightning"),
+                ItemType::Obsidian => (Some(Spell::Magma), "magma"),
+                ItemType::DesertRose => (Some(Spell::Sandstorm), "sandstorm"),
+                ItemType::IceCrystal => (Some(Spell::Ice), "ice"),
+                ItemType::Meteorite => (Some(Spell::Meteor), "meteor"),
+                ItemType::Bellows => (Some(Spell::Flamethrower), "flamethrower"),
+                ItemType::Trident => (Some(Spell::WaterBeam), "water_beam"),
                 _ => (None, ""),
             };
 
@@ -25,6 +25,7 @@ impl Plugin for LevelCompletionPlugin {
                     despawn_all_with::<crate::black_hole::BlackHole>,
                     despawn_all_with::<crate::item::Item>,
                     despawn_all_with::<crate::obstacles::Obstacle>,
+                    despawn_all_with::<crate::hazards::Hazard>,
                     despawn_all_with::<crate::mobs::Mob>,
                     despawn_all_with::<Portal>,
                 ),
@@ -152,7 +153,7 @@ fn collision_portal(
                     game_state.set(GameState::Hub);
                 }
                 GameState::Hub => {
-                    if chapter_manager.get_current_chapter() == 3 && chapter_manager.get_current_level() == 2 {
+                    if chapter_manager.is_next_level_boss() {
                         game_state.set(GameState::LoadingBoss);
                     } else {
                         game_state.set(GameState::Loading);
@@ -7,7 +7,8 @@ use crate::{
     item::{
         ItemDatabase, ItemDatabaseHandle, ItemType, SpawnItemEvent
     },
-    mobs::MobDeathEvent
+    mobs::MobDeathEvent,
+    wand::SpawnWandEvent
 };
 
 pub struct LootPlugin;
@@ -25,6 +26,7 @@ fn loot_drop(
     mut ev_health_tank: EventWriter<SpawnHealthTankEvent>,
     mut ev_exp_tank: EventWriter<SpawnExpTankEvent>,
     mut ev_item: EventWriter<SpawnItemEvent>,
+    mut ev_wand: EventWriter<SpawnWandEvent>,
 
     item_database: Res<Assets<ItemDatabase>>,
     handle: Res<ItemDatabaseHandle>,
@@ -63,6 +65,13 @@ fn loot_drop(
                     item_description
                 });
             }
+            113..=115 => {
+                ev_wand.send(SpawnWandEvent {
+                    pos,
+                    wand_type: rand::random(),
+                    price: 0
+                });
+            }
             _ => {}
         }
     }
@@ -31,12 +31,17 @@ use wand::WandPlugin;
 mod elements;
 use elements::ElementsPlugin;
 
+mod channel;
+use channel::ChannelPlugin;
+
 mod hub_location;
 use hub_location::HubPlugin;
 
 mod projectile;
 use projectile::ProjectilePlugin;
 
+mod projectile_bench;
+
 mod experience;
 use experience::ExperiencePlugin;
 
@@ -51,11 +56,17 @@ mod health;
 mod health_tank;
 use health_tank::HealthTankPlugin;
 
+mod mana;
+use mana::ManaPlugin;
+
 mod pathfinding;
 use pathfinding::PathfindingPlugin;
 
 mod mobs;
-use mobs::{BossBehavoiurPlugin, MobAnimationPlugin, MobMovementPlugin, MobPlugin, MobSpawnPlugin};
+use mobs::{
+    AttackPatternPlugin, BossBehavoiurPlugin, ElitePlugin, MobAnimationPlugin, MobMovementPlugin, MobPlugin,
+    MobSpawnPlugin, PerceptionPlugin, SquadPlugin,
+};
 
 mod shield_spell;
 use shield_spell::ShieldSpellPlugin;
@@ -63,6 +74,12 @@ use shield_spell::ShieldSpellPlugin;
 mod black_hole;
 use black_hole::BlackHolePlugin;
 
+mod damage_zone;
+use damage_zone::DamageZonePlugin;
+
+mod meteor;
+use meteor::MeteorPlugin;
+
 mod game_over;
 use game_over::GameOverPlugin;
 
@@ -72,12 +89,18 @@ use invincibility::InvincibilityPlugin;
 mod stun;
 use stun::StunPlugin;
 
+mod burning;
+use burning::BurningPlugin;
+
 mod animation;
 mod utils;
 
 mod friend;
 use friend::FriendPlugin;
 
+mod summon_persistence;
+use summon_persistence::SummonPersistencePlugin;
+
 mod chapter;
 use chapter::ChapterPlugin;
 
@@ -87,10 +110,17 @@ use item::ItemPlugin;
 mod boss_room;
 use boss_room::BossRoomPlugin;
 
+mod secret_room;
+use secret_room::SecretRoomPlugin;
+
+mod hazards;
+use hazards::HazardPlugin;
+
 mod ui;
 use ui::{
     ElementsUIPlugin, ExperienceUIPlugin, HealthUIPlugin, ItemUIPlugin, LoadingScreenUIPlugin,
-    MainMenuPlugin, PauseUIPlguin, DebugConsolePlugin
+    MainMenuPlugin, PauseUIPlguin, DebugConsolePlugin, BossUIPlugin, SummonUIPlugin, ManaUIPlugin,
+    SpellbookUIPlugin
 };
 
 mod loot;
@@ -159,6 +189,11 @@ pub enum GameLayer {
 }
 
 fn main() {
+    if std::env::args().any(|arg| arg == "--bench-projectiles") {
+        projectile_bench::run();
+        return;
+    }
+
     let mut wpgu_settings = WgpuSettings::default();
     wpgu_settings
         .features
@@ -182,8 +217,14 @@ fn main() {
         .add_plugins(CameraPlugin)
         .add_plugins(PlayerPlugin)
         .add_plugins(WandPlugin)
-        .add_plugins((ElementsPlugin, ElementsUIPlugin))
-        .add_plugins((ShieldSpellPlugin, BlackHolePlugin, BlankSpellPlugin))
+        .add_plugins((ElementsPlugin, ElementsUIPlugin, ChannelPlugin))
+        .add_plugins((
+            ShieldSpellPlugin,
+            BlackHolePlugin,
+            BlankSpellPlugin,
+            DamageZonePlugin,
+            MeteorPlugin,
+        ))
         .add_plugins(ProjectilePlugin)
         .add_plugins((
             ExperiencePlugin,
@@ -192,6 +233,7 @@ fn main() {
             ExpTankPlugin,
         ))
         .add_plugins((HealthTankPlugin, HealthUIPlugin))
+        .add_plugins((ManaPlugin, ManaUIPlugin))
         .add_plugins(PathfindingPlugin)
         .add_plugins((
             StateMachinePlugin,
@@ -200,21 +242,27 @@ fn main() {
             MobSpawnPlugin,
             MobMovementPlugin,
             BossBehavoiurPlugin,
+            ElitePlugin,
+            AttackPatternPlugin,
+            SquadPlugin,
+            PerceptionPlugin,
             AlertPlugin,
         ))
         .add_plugins(GameOverPlugin)
         .add_plugins(LevelCompletionPlugin)
         .add_plugins(HitPlayerPlugin)
         .add_plugins(HubPlugin)
-        .add_plugins((InvincibilityPlugin, StunPlugin))
+        .add_plugins((InvincibilityPlugin, StunPlugin, BurningPlugin))
         .add_plugins(ChapterPlugin)
         .add_plugins((ItemPlugin, ItemUIPlugin, ItemEffectsPlugin))
         .add_plugins(LootPlugin)
-        .add_plugins((PausePlugin, PauseUIPlguin))
+        .add_plugins((PausePlugin, PauseUIPlguin, SpellbookUIPlugin))
         .add_plugins(LoadingScreenUIPlugin)
+        .add_plugins(BossUIPlugin)
+        .add_plugins(SummonUIPlugin)
         .add_plugins(ObstaclePlugin)
-        .add_plugins(BossRoomPlugin)
-        .add_plugins(FriendPlugin)
+        .add_plugins((BossRoomPlugin, SecretRoomPlugin, HazardPlugin))
+        .add_plugins((FriendPlugin, SummonPersistencePlugin))
         .add_plugins(ParticlesPlguin)
         .add_plugins(AudioPlugin)
         .add_plugins(SavePlugin)
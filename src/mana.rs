@@ -0,0 +1,119 @@
+//мана и перезарядка заклинаний: каждое заклинание стоит маны и какое-то время не кастуется снова
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{elements::Spell, player::PlayerStats, GameState};
+
+pub struct ManaPlugin;
+
+impl Plugin for ManaPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CastDeniedEvent>()
+            .insert_resource(Mana::default())
+            .add_systems(OnExit(GameState::MainMenu), init_mana)
+            .add_systems(
+                Update,
+                regen_mana.run_if(in_state(GameState::InGame).or_else(in_state(GameState::Hub))),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CastDenied {
+    NoMana,
+    Cooldown,
+}
+
+//заклинание не скастовалось, для подсветки в интерфейсе
+#[derive(Event)]
+pub struct CastDeniedEvent {
+    pub spell: Spell,
+    pub reason: CastDenied,
+}
+
+#[derive(Resource)]
+pub struct Mana {
+    pub current: f32,
+    pub max: f32,
+    pub regen: f32, //в секунду
+    cooldowns: HashMap<Spell, Timer>,
+}
+
+impl Default for Mana {
+    fn default() -> Self {
+        Self {
+            current: 100.,
+            max: 100.,
+            regen: 8.,
+            cooldowns: HashMap::new(),
+        }
+    }
+}
+
+impl Mana {
+    pub fn cost_of(&self, spell: Spell, player_stats: &PlayerStats) -> f32 {
+        spell.mana_cost() * player_stats.mana_cost_mult
+    }
+
+    //списывает ману и запускает перезарядку, если заклинание можно скастовать
+    pub fn try_cast(&mut self, spell: Spell, player_stats: &PlayerStats) -> Result<(), CastDenied> {
+        if self.cooldown_left(spell) > 0. {
+            return Err(CastDenied::Cooldown);
+        }
+
+        let cost = self.cost_of(spell, player_stats);
+        if self.current < cost {
+            return Err(CastDenied::NoMana);
+        }
+
+        self.current -= cost;
+
+        let cooldown = spell.cooldown() * player_stats.cooldown_mult;
+        if cooldown > 0. {
+            self.cooldowns
+                .insert(spell, Timer::from_seconds(cooldown, TimerMode::Once));
+        }
+
+        Ok(())
+    }
+
+    //поток тратит ману каждый кадр, false - мана кончилась
+    pub fn drain(&mut self, amount: f32) -> bool {
+        if self.current < amount {
+            self.current = 0.;
+            return false;
+        }
+
+        self.current -= amount;
+        true
+    }
+
+    pub fn cooldown_left(&self, spell: Spell) -> f32 {
+        self.cooldowns
+            .get(&spell)
+            .map(|timer| timer.remaining_secs())
+            .unwrap_or(0.)
+    }
+
+    //заклинания на перезарядке и сколько им осталось, от 1 до 0
+    pub fn cooldowns(&self) -> impl Iterator<Item = (Spell, f32)> + '_ {
+        self.cooldowns
+            .iter()
+            .map(|(spell, timer)| (*spell, timer.fraction_remaining()))
+    }
+}
+
+fn init_mana(mut commands: Commands) {
+    commands.insert_resource(Mana::default());
+}
+
+fn regen_mana(mut mana: ResMut<Mana>, time: Res<Time>) {
+    mana.current = (mana.current + mana.regen * time.delta_seconds()).min(mana.max);
+
+    for timer in mana.cooldowns.values_mut() {
+        timer.tick(time.delta());
+    }
+
+    mana.cooldowns.retain(|_, timer| !timer.finished());
+}
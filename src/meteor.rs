@@ -0,0 +1,164 @@
+//метеор: падает сверху в точку под курсором, бьет всех в радиусе и оставляет лужу магмы
+use bevy::prelude::*;
+
+use crate::{
+    audio::PlayAudioEvent,
+    damage_zone::{SpawnDamageZoneEvent, ZoneGrowth, ZoneShape},
+    elements::ElementType,
+    particles::{ParticlePattern, SpawnParticlesEvent},
+    utils::despawn_all_with,
+    GameState,
+};
+
+pub struct MeteorPlugin;
+
+impl Plugin for MeteorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpawnMeteorEvent>()
+            .add_systems(
+                OnExit(GameState::InGame),
+                (despawn_all_with::<Meteor>, despawn_all_with::<MeteorMark>),
+            )
+            .add_systems(
+                Update,
+                (spawn_meteor, fall_meteor)
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+//с какой высоты падает метеор
+const FALL_HEIGHT: f32 = 240.;
+
+#[derive(Event)]
+pub struct SpawnMeteorEvent {
+    pub target: Vec2,
+    pub damage: u32,
+    pub radius: f32,
+    pub fall_time: f32,
+}
+
+#[derive(Component)]
+pub struct Meteor {
+    target: Vec2,
+    damage: u32,
+    radius: f32,
+    fall: Timer,
+    mark: Entity,
+}
+
+//отметка на земле, куда упадет метеор
+#[derive(Component)]
+pub struct MeteorMark;
+
+fn spawn_meteor(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut ev_spawn_meteor: EventReader<SpawnMeteorEvent>,
+) {
+    for ev in ev_spawn_meteor.read() {
+        let mark = commands
+            .spawn(SpriteBundle {
+                texture: asset_server.load("textures/damage_zone.png"),
+                sprite: Sprite {
+                    color: Color::srgba(1.0, 0.3, 0.1, 0.3),
+                    custom_size: Some(Vec2::splat(ev.radius * 2.)),
+                    ..default()
+                },
+                transform: Transform::from_translation(ev.target.extend(-99.)),
+                ..default()
+            })
+            .insert(MeteorMark)
+            .id();
+
+        commands
+            .spawn(SpriteBundle {
+                texture: asset_server.load("textures/meteor.png"),
+                transform: Transform::from_translation(
+                    (ev.target + Vec2::new(-FALL_HEIGHT / 4., FALL_HEIGHT)).extend(5.),
+                ),
+                ..default()
+            })
+            .insert(Meteor {
+                target: ev.target,
+                damage: ev.damage,
+                radius: ev.radius,
+                fall: Timer::from_seconds(ev.fall_time, TimerMode::Once),
+                mark,
+            });
+    }
+}
+
+fn fall_meteor(
+    mut commands: Commands,
+    mut meteor_query: Query<(Entity, &mut Meteor, &mut Transform)>,
+    mut ev_spawn_zone: EventWriter<SpawnDamageZoneEvent>,
+    mut ev_spawn_particles: EventWriter<SpawnParticlesEvent>,
+    mut ev_play_audio: EventWriter<PlayAudioEvent>,
+    time: Res<Time>,
+) {
+    for (meteor_e, mut meteor, mut transform) in meteor_query.iter_mut() {
+        meteor.fall.tick(time.delta());
+
+        //летит по прямой сверху-слева, разгоняясь к земле
+        let t = meteor.fall.fraction().powi(2);
+        let start = meteor.target + Vec2::new(-FALL_HEIGHT / 4., FALL_HEIGHT);
+        let pos = start.lerp(meteor.target, t);
+        transform.translation.x = pos.x;
+        transform.translation.y = pos.y;
+        transform.scale = Vec3::splat(1. + t);
+
+        if !meteor.fall.just_finished() {
+            continue;
+        }
+
+        //удар: один тик урона по всей площади
+        ev_spawn_zone.send(SpawnDamageZoneEvent {
+            position: meteor.target.extend(0.),
+            shape: ZoneShape::Circle,
+            radius: meteor.radius,
+            duration: 0.3,
+            tick_rate: 0.25,
+            damage: meteor.damage as i32,
+            element: Some(ElementType::Fire),
+            color: Color::srgba(1.5, 0.6, 0.2, 0.8),
+            is_friendly: true,
+            follow_owner: None,
+            growth: ZoneGrowth::EaseOut { from: 0.2 },
+            effect: None,
+        });
+
+        //остывающая магма
+        ev_spawn_zone.send(SpawnDamageZoneEvent {
+            position: meteor.target.extend(0.),
+            shape: ZoneShape::Circle,
+            radius: meteor.radius * 0.6,
+            duration: 3.,
+            tick_rate: 0.5,
+            damage: (meteor.damage / 8).max(1) as i32,
+            element: Some(ElementType::Fire),
+            color: Color::srgba(1.0, 0.35, 0.1, 0.6),
+            is_friendly: true,
+            follow_owner: None,
+            growth: ZoneGrowth::Shrink { to: 0.5 },
+            effect: None,
+        });
+
+        ev_spawn_particles.send(SpawnParticlesEvent {
+            pattern: ParticlePattern::Circle {
+                radius: meteor.radius,
+            },
+            position: meteor.target.extend(1.),
+            amount: 16,
+            color: Color::srgb(1.5, 0.7, 0.3),
+            speed: 20.,
+            rotate: true,
+        });
+
+        ev_play_audio.send(PlayAudioEvent::from_file("earth.ogg").at(meteor.target, 256.));
+
+        commands.entity(meteor.mark).despawn();
+        commands.entity(meteor_e).despawn();
+    }
+}
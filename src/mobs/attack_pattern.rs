@@ -0,0 +1,317 @@
+//паттерны атак мобов: сколько снарядов, в каком секторе, очередью или залпом,
+//рывки и телеграфы перед ударом. Описываются в AttackComponent у бандла моба
+use std::{f32::consts::PI, time::Duration};
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    elements::ElementType,
+    projectile::{ProjectileModifier, SpawnProjectileEvent, Trajectory},
+    GameState,
+};
+
+use super::{AttackComponent, AttackType, BeforeAttackDelay, ProjectileType};
+
+pub struct AttackPatternPlugin;
+
+impl Plugin for AttackPatternPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                fire_projectile_bursts,
+                start_telegraph,
+                update_telegraph,
+            )
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(FixedUpdate, rush_charge.run_if(in_state(GameState::InGame)));
+    }
+}
+
+#[derive(Clone)]
+pub struct AttackPattern {
+    pub count: u32,
+    pub spread: f32,        //весь сектор в радианах, 2PI и больше - кольцо
+    pub speed: Option<f32>, //None - скорость по типу снаряда
+    pub interval: f32,      //задержка между снарядами очереди, 0 - все сразу
+    pub trajectory: Trajectory,
+    pub modifiers: Vec<ProjectileModifier>,
+}
+
+impl Default for AttackPattern {
+    fn default() -> Self {
+        Self {
+            count: 1,
+            spread: 0.,
+            speed: None,
+            interval: 0.,
+            trajectory: Trajectory::Straight,
+            modifiers: vec![],
+        }
+    }
+}
+
+impl AttackPattern {
+    //кольцо снарядов вокруг моба
+    pub fn ring(count: u32) -> Self {
+        Self {
+            count,
+            spread: 2. * PI,
+            ..default()
+        }
+    }
+
+    //веер в сторону цели
+    pub fn fan(count: u32, spread: f32) -> Self {
+        Self {
+            count,
+            spread,
+            ..default()
+        }
+    }
+
+    //очередь, spread - разброс каждого выстрела
+    pub fn stream(count: u32, interval: f32, spread: f32) -> Self {
+        Self {
+            count,
+            spread,
+            interval,
+            ..default()
+        }
+    }
+
+    pub fn with_modifier(mut self, modifier: ProjectileModifier) -> Self {
+        self.modifiers.push(modifier);
+        self
+    }
+
+    pub fn is_ring(&self) -> bool {
+        self.spread >= 2. * PI - 0.01
+    }
+
+    //угол i-го снаряда залпа относительно направления на цель
+    pub fn offset(&self, i: u32) -> f32 {
+        if self.is_ring() {
+            return i as f32 * 2. * PI / self.count as f32;
+        }
+
+        if self.count <= 1 {
+            return 0.;
+        }
+
+        -self.spread / 2. + self.spread * i as f32 / (self.count - 1) as f32
+    }
+}
+
+impl ProjectileType {
+    //чем стреляет тип снаряда, если моб не задал паттерн сам
+    pub fn default_pattern(&self) -> AttackPattern {
+        match self {
+            ProjectileType::BoneBolt => AttackPattern::default()
+                .with_modifier(ProjectileModifier::Homing { turn_rate: 2.0 }),
+            _ => AttackPattern::default(),
+        }
+    }
+}
+
+//шаг между частями копья, чем больше count - тем дальше колет
+pub const SPEAR_SEGMENT: f32 = 14.;
+
+impl AttackComponent {
+    //докуда достает атака, для телеграфа
+    pub fn reach(&self) -> f32 {
+        match self.attack_type {
+            AttackType::Spear => {
+                self.range + SPEAR_SEGMENT * (self.pattern().count.max(1) - 1) as f32 + 8.
+            }
+            _ => self.range,
+        }
+    }
+
+    pub fn pattern(&self) -> AttackPattern {
+        match (&self.pattern, &self.proj_type) {
+            (Some(pattern), _) => pattern.clone(),
+            (None, Some(proj_type)) => proj_type.default_pattern(),
+            (None, None) => AttackPattern::default(),
+        }
+    }
+}
+
+//оставшиеся снаряды очереди, висит на мобе пока очередь не кончится
+#[derive(Component)]
+pub struct ProjectileBurst {
+    pub remaining: u32,
+    pub timer: Timer,
+    pub target: Option<Entity>,
+    pub angle: f32,
+    pub spread: f32,
+    pub texture_path: String,
+    pub color: Color,
+    pub speed: f32,
+    pub damage: u32,
+    pub element: ElementType,
+    pub trajectory: Trajectory,
+    pub modifiers: Vec<ProjectileModifier>,
+    pub is_friendly: bool,
+}
+
+fn fire_projectile_bursts(
+    mut commands: Commands,
+    mut mob_query: Query<(Entity, &Transform, &mut ProjectileBurst)>,
+    transform_query: Query<&Transform, Without<ProjectileBurst>>,
+    mut ev_shoot: EventWriter<SpawnProjectileEvent>,
+    time: Res<Time>,
+) {
+    for (mob_e, transform, mut burst) in mob_query.iter_mut() {
+        burst.timer.tick(time.delta());
+
+        if !burst.timer.just_finished() {
+            continue;
+        }
+
+        //очередь ведет цель, если та еще жива
+        if let Some(target_transform) = burst.target.and_then(|t| transform_query.get(t).ok()) {
+            let dir = (target_transform.translation - transform.translation).truncate();
+            if dir != Vec2::ZERO {
+                burst.angle = dir.to_angle();
+            }
+        }
+
+        let jitter = if burst.spread > 0. {
+            rand::thread_rng().gen_range(-burst.spread / 2.0..burst.spread / 2.0)
+        } else {
+            0.
+        };
+
+        ev_shoot.send(SpawnProjectileEvent {
+            texture_path: burst.texture_path.clone(),
+            color: burst.color,
+            translation: transform.translation,
+            angle: burst.angle + jitter,
+            collider_radius: 8.0,
+            speed: burst.speed,
+            damage: burst.damage,
+            element: burst.element,
+            is_friendly: burst.is_friendly,
+            trajectory: burst.trajectory,
+            can_go_through_walls: false,
+            modifiers: burst.modifiers.clone(),
+        });
+
+        burst.remaining -= 1;
+        if burst.remaining == 0 {
+            commands.entity(mob_e).remove::<ProjectileBurst>();
+        }
+    }
+}
+
+//рывок к цели для AttackType::Rush, удар летит вместе с мобом
+#[derive(Component)]
+pub struct RushCharge {
+    pub dir: Vec2,
+    pub speed: f32,
+    pub timer: Timer,
+}
+
+impl RushCharge {
+    pub fn new(dir: Vec2, speed: f32) -> Self {
+        Self {
+            dir,
+            speed,
+            timer: Timer::new(Duration::from_millis(250), TimerMode::Once),
+        }
+    }
+}
+
+fn rush_charge(
+    mut commands: Commands,
+    mut mob_query: Query<(Entity, &mut LinearVelocity, &mut RushCharge)>,
+    time: Res<Time>,
+) {
+    for (mob_e, mut linvel, mut rush) in mob_query.iter_mut() {
+        rush.timer.tick(time.delta());
+
+        if rush.timer.finished() {
+            linvel.0 = Vec2::ZERO;
+            commands.entity(mob_e).remove::<RushCharge>();
+            continue;
+        }
+
+        linvel.0 = rush.dir * rush.speed;
+    }
+}
+
+//моб дольше замахивается и показывает линию, куда ударит или выстрелит
+#[derive(Component)]
+pub struct AttackTelegraph {
+    pub time: f32,
+}
+
+#[derive(Component)]
+pub struct TelegraphLine;
+
+fn start_telegraph(
+    mut commands: Commands,
+    mut mob_query: Query<
+        (Entity, &AttackTelegraph, &mut BeforeAttackDelay),
+        Added<BeforeAttackDelay>,
+    >,
+) {
+    for (mob_e, telegraph, mut delay) in mob_query.iter_mut() {
+        delay
+            .timer
+            .set_duration(Duration::from_secs_f32(telegraph.time));
+
+        commands.entity(mob_e).with_children(|parent| {
+            parent
+                .spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::srgba(1., 0.2, 0.2, 0.6),
+                        custom_size: Some(Vec2::new(1., 1.)),
+                        anchor: bevy::sprite::Anchor::CenterLeft,
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(0., 0., -0.1),
+                    ..default()
+                })
+                .insert(TelegraphLine);
+        });
+    }
+}
+
+//линия следит за целью, пока идет замах, и пропадает после атаки
+fn update_telegraph(
+    mut commands: Commands,
+    mob_query: Query<
+        (&Transform, &AttackComponent, Option<&BeforeAttackDelay>),
+        (With<AttackTelegraph>, Without<TelegraphLine>),
+    >,
+    target_query: Query<&Transform, Without<TelegraphLine>>,
+    mut line_query: Query<(Entity, &Parent, &mut Transform, &mut Sprite), With<TelegraphLine>>,
+) {
+    for (line_e, parent, mut transform, mut sprite) in line_query.iter_mut() {
+        let Ok((mob_transform, attack, delay)) = mob_query.get(parent.get()) else {
+            commands.entity(line_e).despawn();
+            continue;
+        };
+
+        let (Some(delay), Some(target)) = (delay, attack.target) else {
+            commands.entity(line_e).despawn();
+            continue;
+        };
+
+        let Ok(target_transform) = target_query.get(target) else {
+            continue;
+        };
+
+        let dir = (target_transform.translation - mob_transform.translation).truncate();
+
+        transform.rotation = Quat::from_rotation_z(dir.to_angle());
+        sprite.custom_size = Some(Vec2::new(dir.length().min(attack.reach()), 1.));
+        //к концу замаха линия становится ярче
+        sprite.color.set_alpha(0.3 + 0.6 * delay.timer.fraction());
+    }
+}
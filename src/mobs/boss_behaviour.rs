@@ -2,8 +2,9 @@
 use std::f32::consts::PI;
 use std::time::Duration;
 
-use avian2d::prelude::LinearVelocity;
+use avian2d::prelude::{CollisionLayers, LinearVelocity};
 use bevy::prelude::*;
+use bevy_common_assets::json::JsonAssetPlugin;
 use rand::{thread_rng, Rng};
 use seldom_state::trigger::Done;
 
@@ -11,9 +12,14 @@ use std::convert::TryFrom;
 
 use crate::alert::SpawnAlertEvent;
 use crate::blank_spell::SpawnBlankEvent;
-use crate::gamemap::Wall;
+use crate::camera::CameraShakeEvent;
+use crate::gamemap::{Map, TileType, Wall};
 use crate::health::Health;
+use crate::health_tank::SpawnHealthTankEvent;
+use crate::item::{ItemDatabase, ItemDatabaseHandle, ItemType, SpawnItemEvent};
 use crate::level_completion::PortalManager;
+use crate::obstacles::Corpse;
+use crate::particles::{ParticlePattern, SpawnParticlesEvent};
 use crate::projectile::{Friendly, Projectile, Trajectory};
 use crate::shield_spell::SpawnShieldEvent;
 use crate::GameState;
@@ -24,32 +30,41 @@ use crate::{
     projectile::SpawnProjectileEvent,
 };
 
-use super::{BossMovement, Enemy, Mob, NoSummons, SummonUnit, Teleport};
-use super::{MobSpawnEvent, MobType};
+use super::{damage_mobs, MobDeathEvent};
+use super::{BossMovement, BusyRaising, Enemy, Mob, NoSummons, SummonUnit, Teleport};
+use super::{EliteRoll, MobSpawnEvent, MobType};
 use super::{PhaseManager, SummonQueue};
+use super::{
+    load_boss_database, BossDatabase, BossDatabaseHandle, BossDefinition, BossMovementStyle,
+    BossScript, TransitionAction, WeightFactor,
+};
 
 pub struct BossBehavoiurPlugin;
 
 impl Plugin for BossBehavoiurPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                check_is_summon_alive,
-                tick_cooldown_boss,
-                recalculate_weights,
-                cast_shield,
-                boss_teleport,
-                boss_running,
-                cast_blank,
-                warn_player_abt_attack,
-                perform_attack,
-                tick_every_spell_cooldown,
-                switch_phase,
-                projectiles_check,
-                cast_out_of_order,
-            ).run_if(in_state(GameState::InGame)),
-        );
+        app.add_plugins(JsonAssetPlugin::<BossDatabase>::new(&["json"]))
+            .add_event::<BossTelegraphEvent>()
+            .add_systems(Startup, load_boss_database)
+            .add_systems(
+                Update,
+                (
+                    setup_boss_script,
+                    check_is_summon_alive,
+                    tick_cooldown_boss,
+                    recalculate_weights,
+                    cast_reactive_attacks,
+                    boss_teleport,
+                    boss_running,
+                    warn_player_abt_attack,
+                    perform_attack,
+                    tick_every_spell_cooldown,
+                    switch_phase,
+                    cast_out_of_order,
+                    move_burrowed_boss,
+                    drop_boss_loot.after(damage_mobs),
+                ).run_if(in_state(GameState::InGame)),
+            );
     }
 }
 
@@ -70,12 +85,38 @@ pub struct OnCooldownFlag;
 #[derive(Component, Clone)]
 pub struct PickAttackFlag;
 
-#[derive(Component)]
+//веса, кулдауны и готовность атак по индексу BossAttackType, заполняется из BossDefinition
+#[derive(Component, Default)]
 pub struct BossAttackSystem {
-    pub weight_array: Vec<i16>,
+    //None - атаки нет в текущей фазе или она на кулдауне
+    pub weight_array: Vec<Option<i32>>,
     pub cooldown_array: Vec<Timer>,
+    pub ready_array: Vec<bool>,
     pub cooldown_between_attacks: Timer,
-    pub cooldown_mask: u32,
+}
+
+impl BossAttackSystem {
+    pub fn new(definition: &BossDefinition) -> Self {
+        Self {
+            weight_array: vec![None; BOSS_ATTACK_COUNT],
+            cooldown_array: vec![Timer::default(); BOSS_ATTACK_COUNT],
+            ready_array: vec![true; BOSS_ATTACK_COUNT],
+            cooldown_between_attacks: Timer::from_seconds(
+                definition.cooldown_between_attacks,
+                TimerMode::Repeating,
+            ),
+        }
+    }
+
+    pub fn start_cooldown(&mut self, attack: BossAttackType, cooldown: f32) {
+        let index = attack as usize;
+        if index >= self.ready_array.len() {
+            return;
+        }
+        self.cooldown_array[index] = Timer::from_seconds(cooldown, TimerMode::Once);
+        self.ready_array[index] = false;
+        self.weight_array[index] = None;
+    }
 }
 
 const WALL_DIRECTIONS: [Vec2; 4] = [
@@ -87,7 +128,7 @@ const WALL_DIRECTIONS: [Vec2; 4] = [
 
 #[derive(Component)]
 pub struct OutOfOrderAttackQueue {
-    queue: Vec<BossAttackType>,
+    queue: Vec<TransitionAction>,
     timer: Timer,
 }
 
@@ -100,14 +141,20 @@ impl Default for OutOfOrderAttackQueue {
     }
 }
 
+//ивент для баннера над полоской босса, шлется вместе с восклицательным знаком
+#[derive(Event)]
+pub struct BossTelegraphEvent {
+    pub attack: BossAttackType,
+}
+
 #[derive(PartialEq)]
-#[repr(u8)]
 pub enum BossAttackFlag {
-    ProjectileSpells = 0,
+    ProjectileSpells,
     DefensiveSpells,
-    SpawnSpells,
+    SpawnSpells(MobType),
+    RaiseSpells,
 }
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Clone, Copy, serde::Deserialize)]
 #[repr(u8)]
 pub enum BossAttackType {
     SpawnEarthElemental = 0,
@@ -122,79 +169,144 @@ pub enum BossAttackType {
     Wall,
     //SpawnClayGolem,
     MegaStan,
+    Burrow,
+    SpawnJungleTurret,
+    Vines,
+    RaiseCorpses,
 }
 
-//система для смены фаз при необходимом кол-ве хп 
+pub const BOSS_ATTACK_COUNT: usize = 15;
+
+impl BossAttackType {
+    pub fn flag(&self) -> BossAttackFlag {
+        match self {
+            BossAttackType::SpawnEarthElemental => BossAttackFlag::SpawnSpells(MobType::EarthElemental),
+            BossAttackType::SpawnAirElemental => BossAttackFlag::SpawnSpells(MobType::AirElemental),
+            BossAttackType::SpawnFireElemental => BossAttackFlag::SpawnSpells(MobType::FireElemental),
+            BossAttackType::SpawnWaterElemental => BossAttackFlag::SpawnSpells(MobType::WaterElemental),
+            BossAttackType::SpawnJungleTurret => BossAttackFlag::SpawnSpells(MobType::JungleTurret),
+            BossAttackType::RaiseCorpses => BossAttackFlag::RaiseSpells,
+            BossAttackType::Shield | BossAttackType::Blank => BossAttackFlag::DefensiveSpells,
+            _ => BossAttackFlag::ProjectileSpells,
+        }
+    }
+
+    //текст предупреждения об атаке для баннера
+    pub fn telegraph(&self) -> &str {
+        match self {
+            BossAttackType::SpawnEarthElemental => "Призыв земляных духов",
+            BossAttackType::SpawnAirElemental => "Призыв воздушных духов",
+            BossAttackType::SpawnFireElemental => "Призыв огненных духов",
+            BossAttackType::SpawnWaterElemental => "Призыв водных духов",
+            BossAttackType::Radial => "Кольцо снарядов",
+            BossAttackType::ProjectilePattern => "Шквал снарядов",
+            BossAttackType::Shield => "Щит",
+            BossAttackType::FastPierce => "Пронзающий залп",
+            BossAttackType::Blank => "Пустышка",
+            BossAttackType::Wall => "Стена снарядов",
+            BossAttackType::MegaStan => "Вихрь",
+            BossAttackType::Burrow => "Уходит под землю",
+            BossAttackType::SpawnJungleTurret => "Ростки турелей",
+            BossAttackType::Vines => "Лозы",
+            BossAttackType::RaiseCorpses => "Поднятие мертвых",
+        }
+    }
+}
+
+//подтягиваем описание босса из базы, как только она загрузилась
+fn setup_boss_script(
+    mut boss_query: Query<(&mut BossScript, &mut BossAttackSystem, &mut PhaseManager)>,
+    boss_database: Res<Assets<BossDatabase>>,
+    handle: Res<BossDatabaseHandle>,
+) {
+    for (mut script, mut attack_system, mut phase_manager) in boss_query.iter_mut() {
+        if script.definition.is_some() {
+            continue;
+        }
+
+        let Some(definition) = boss_database
+            .get(handle.0.id())
+            .and_then(|database| database.get(&script.name))
+        else {
+            continue;
+        };
+
+        *attack_system = BossAttackSystem::new(definition);
+        phase_manager.max_phase = definition.phases.len() as u8;
+        script.definition = Some(definition.clone());
+    }
+}
+
+//система для смены фаз: следующая фаза начинается, когда хп опустились до ее порога
 fn switch_phase(
     mut commands: Commands,
-    mut query: Query<
-        (
-            Entity,
-            &Health,
-            &mut PhaseManager,
-            &MobType,
-            &mut SummonQueue,
-        ),
-        Without<NoSummons>,
-    >,
+    mut query: Query<(
+        Entity,
+        &Health,
+        &mut PhaseManager,
+        &BossScript,
+        &mut SummonQueue,
+    )>,
     mob_query: Query<&Mob, With<Enemy>>,
     mut portal_manager: ResMut<PortalManager>,
 ) {
-    let Ok((boss_e, health, mut phase_manager, boss_type, mut summons)) = query.get_single_mut()
+    let Ok((boss_e, health, mut phase_manager, script, mut summons)) = query.get_single_mut()
     else {
         return;
     };
-    if phase_manager.current_phase >= phase_manager.max_phase {
+    let Some(next_phase) = script.phase(phase_manager.current_phase + 1) else {
+        return;
+    };
+
+    if health.current > (health.max as f32 * next_phase.hp_threshold) as i32 {
+        return;
+    }
+
+    phase_manager.current_phase += 1;
+
+    if let Some(limit) = next_phase.summon_limit {
+        summons.resize(limit);
+        summons.queue.resize(
+            limit as usize,
+            SummonUnit {
+                entity: None,
+                mob_type: MobType::Mossling,
+            },
+        );
+        summons.amount_of_mobs = summons.amount_of_mobs.min(limit);
+    }
+
+    if next_phase.stop_summons {
         commands.entity(boss_e).insert(NoSummons);
         portal_manager.mobs = mob_query.iter().count() as u32;
-        return;
     }
-    if health.current
-        <= (health.max as f32
-            * phase_manager.phase_change_hp_multiplier[phase_manager.current_phase as usize - 1])
-            as i32
-    {
-        if *boss_type == MobType::Koldun {
-            if phase_manager.current_phase == 1 {
-                summons.resize(6);
-                summons.queue.resize(
-                    6,
-                    SummonUnit {
-                        entity: None,
-                        mob_type: MobType::Mossling,
-                    },
-                );
-                summons.amount_of_mobs = 6;
-            }
-        }
+
+    if !next_phase.on_enter.is_empty() {
         commands.entity(boss_e).insert(OutOfOrderAttackQueue {
-            queue: vec![
-                BossAttackType::Blank,
-                BossAttackType::Blank,
-                BossAttackType::Blank,
-            ],
+            queue: next_phase.on_enter.clone(),
             ..default()
         });
-        phase_manager.current_phase += 1;
     }
 }
 
 //система для каста заклинаний вне очереди(н.п. несколько пустышек между фазами)
 fn cast_out_of_order(
-    mut boss_query: Query<(Entity, &Transform, &mut OutOfOrderAttackQueue)>,
+    mut boss_query: Query<(Entity, &mut Transform, &mut OutOfOrderAttackQueue), Without<Player>>,
+    player_query: Query<&Transform, With<Player>>,
     time: Res<Time>,
     mut spawn_blank_ev: EventWriter<SpawnBlankEvent>,
+    mut cast_shield: EventWriter<SpawnShieldEvent>,
     mut commands: Commands,
 ) {
-    for (boss_e, pos, mut attack_queue) in boss_query.iter_mut() {
-        if attack_queue.queue.len() == 0 {
+    for (boss_e, mut pos, mut attack_queue) in boss_query.iter_mut() {
+        let Some(action) = attack_queue.queue.last().copied() else {
             commands.entity(boss_e).remove::<OutOfOrderAttackQueue>();
             return;
-        }
+        };
         attack_queue.timer.tick(time.delta());
         if attack_queue.timer.just_finished() {
-            match attack_queue.queue[attack_queue.queue.len() - 1] {
-                BossAttackType::Blank => {
+            match action {
+                TransitionAction::Blank => {
                     spawn_blank_ev.send(SpawnBlankEvent {
                         range: 18.,
                         position: Vec3::new(pos.translation.x, pos.translation.y, 1.0),
@@ -202,7 +314,19 @@ fn cast_out_of_order(
                         is_friendly: false,
                     });
                 }
-                _ => {
+                TransitionAction::Teleport => {
+                    if let Ok(player_pos) = player_query.get_single() {
+                        let old_pos = pos.translation;
+                        pos.translation += (player_pos.translation - old_pos) / 2.;
+                    }
+                }
+                TransitionAction::Shield => {
+                    cast_shield.send(SpawnShieldEvent {
+                        duration: 4.,
+                        owner: boss_e,
+                        is_friendly: false,
+                        size: 64,
+                    });
                 }
             };
             attack_queue.queue.pop();
@@ -282,19 +406,28 @@ fn perform_attack(
             &BossAttackFlagComp,
             &Transform,
             &PhaseManager,
+            &BossScript,
+            &CollisionLayers,
         ),
         Without<BeforeAttackDelayBoss>,
     >,
     player_query: Query<&Transform, With<Player>>,
+    corpse_query: Query<(Entity, &Transform, &Corpse), Without<BusyRaising>>,
+    map: Res<Map>,
     mut ev_mob_spawn: EventWriter<MobSpawnEvent>,
+    mut ev_spawn_particles: EventWriter<SpawnParticlesEvent>,
     mut commands: Commands,
 ) {
-    let Ok((boss_e, _boss_sys, attack_type, boss_position, phase_manager)) =
+    let Ok((boss_e, _boss_sys, attack_type, boss_position, phase_manager, script, layers)) =
         boss_query.get_single()
     else {
         return;
     };
 
+    let empowered = script
+        .attack(phase_manager.current_phase, attack_type.attack_picked)
+        .is_some_and(|attack| attack.empowered);
+
     let Ok(player_pos) = player_query.get_single() else {
         println!("NO PLAYER?????");
         return;
@@ -321,7 +454,7 @@ fn perform_attack(
 
             for i in (ROOM_SIZE / 2 - 7)..(ROOM_SIZE / 2 + 8) {
                 if i == to_skip
-                    || (phase_manager.current_phase == 3 && (i == to_skip - 1 || i == to_skip + 1))
+                    || (empowered && (i == to_skip - 1 || i == to_skip + 1))
                 {
                     continue;
                 }
@@ -340,9 +473,10 @@ fn perform_attack(
                     element,
                     is_friendly: false,
                     can_go_through_walls: false,
+                    modifiers: vec![],
                 });
 
-                if phase_manager.current_phase == 3 {
+                if empowered {
                     if i == second_to_skip {
                         continue;
                     }
@@ -361,6 +495,7 @@ fn perform_attack(
                         element,
                         is_friendly: false,
                         can_go_through_walls: false,
+                        modifiers: vec![],
                     });
                 }
             }
@@ -379,7 +514,7 @@ fn perform_attack(
             let to_skip = vec![rand::thread_rng().gen_range(0..amount_attack); amount_skip1];
             let second_to_skip = vec![rand::thread_rng().gen_range(0..amount_attack); amount_skip2];
             let collider_radius;
-            let texture = if phase_manager.current_phase == 3 {
+            let texture = if empowered {
                 collider_radius = 4.;
                 "textures/small_fire.png".to_string()
             } else {
@@ -406,10 +541,11 @@ fn perform_attack(
                         element,
                         is_friendly: false,
                         can_go_through_walls: true,
+                        modifiers: vec![],
                     });
                 }
 
-                if phase_manager.current_phase == 3 && !second_to_skip.contains(&i) {
+                if empowered && !second_to_skip.contains(&i) {
                     let direction = -Vec2::from_angle(i as f32 * offset);
                     let second_position = (player_pos.translation.truncate()
                         - direction * (second_radius as f32) / 10.)
@@ -427,13 +563,14 @@ fn perform_attack(
                         element,
                         is_friendly: false,
                         can_go_through_walls: true,
+                        modifiers: vec![],
                     });
                 }
             }
         }
         BossAttackType::FastPierce => {
             amount_attack += 2;
-            if phase_manager.current_phase == 3 {
+            if empowered {
                 amount_attack += 3;
             }
             let angle_disp = PI / (8 + amount_attack) as f32;
@@ -454,6 +591,7 @@ fn perform_attack(
                     element,
                     is_friendly: false,
                     can_go_through_walls: false,
+                    modifiers: vec![],
                 });
                 angle += angle_disp;
             }
@@ -473,6 +611,7 @@ fn perform_attack(
                     loot: None,
                     owner: Some(boss_e),
                     exp_amount: 0,
+                    elite: EliteRoll::Never,
                 });
 
                 position_drift += 16.;
@@ -494,6 +633,7 @@ fn perform_attack(
                     loot: None,
                     owner: Some(boss_e),
                     exp_amount: 0,
+                    elite: EliteRoll::Never,
                 });
 
                 position_drift += 128.;
@@ -514,6 +654,7 @@ fn perform_attack(
                     owner: Some(boss_e),
                     loot: None,
                     exp_amount: 0,
+                    elite: EliteRoll::Never,
                 });
             }
         }
@@ -531,6 +672,7 @@ fn perform_attack(
                     loot: None,
                     owner: Some(boss_e),
                     exp_amount: 0,
+                    elite: EliteRoll::Never,
                 });
 
                 position_drift += 64.;
@@ -547,6 +689,20 @@ fn perform_attack(
                 let position = boss_position.translation.truncate()
                     + Vec2::from_angle(angle * (j) as f32) * radius;
 
+                //каждое кольцо летит по-своему
+                let trajectory = match j % 4 {
+                    1 => Trajectory::Wave {
+                        amplitude: 12.,
+                        frequency: 1.5,
+                    },
+                    2 => Trajectory::Spiral { turn_rate: 0.8 },
+                    3 => Trajectory::Accelerating {
+                        accel: 120.,
+                        max_speed: 220.,
+                    },
+                    _ => Trajectory::Straight,
+                };
+
                 for i in 0..(amount_attack + j) {
                     let angle = offset * i as f32;
 
@@ -560,8 +716,9 @@ fn perform_attack(
                         damage: 20,
                         element,
                         is_friendly: false,
-                        trajectory: crate::projectile::Trajectory::Straight,
+                        trajectory,
                         can_go_through_walls: false,
+                        modifiers: vec![],
                     });
                 }
             }
@@ -611,6 +768,7 @@ fn perform_attack(
                     element: element,
                     is_friendly: false,
                     can_go_through_walls: true,
+                    modifiers: vec![],
                 });
             }
         }
@@ -630,10 +788,133 @@ fn perform_attack(
                     loot: None,
                     owner: Some(boss_e),
                     exp_amount: 0,
+                    elite: EliteRoll::Never,
                 });
                 angle += PI / 2.;
             }
         }
+        BossAttackType::Burrow => {
+            //голем уходит под песок и ползет к игроку, снаряды его не задевают
+            commands
+                .entity(boss_e)
+                .insert(Burrowed::new(*layers, empowered))
+                .insert(CollisionLayers::NONE)
+                .insert(Visibility::Hidden);
+
+            ev_spawn_particles.send(SpawnParticlesEvent {
+                pattern: ParticlePattern::Circle { radius: 24. },
+                position: boss_position.translation,
+                amount: 12,
+                color: ElementType::Earth.color(),
+                speed: 20.,
+                rotate: false,
+            });
+        }
+        BossAttackType::SpawnJungleTurret => {
+            //турели растут на свободных тайлах пола вокруг игрока
+            amount_attack += if empowered { 5 } else { 3 };
+
+            let player_tile = (
+                (player_pos.translation.x / TILE_SIZE).round() as i32,
+                (player_pos.translation.y / TILE_SIZE).round() as i32,
+            );
+
+            let mut offsets = vec![
+                (3, 0),
+                (-3, 0),
+                (0, 3),
+                (0, -3),
+                (2, 2),
+                (-2, 2),
+                (2, -2),
+                (-2, -2),
+            ];
+
+            while amount_attack > 0 && !offsets.is_empty() {
+                let (dx, dy) = offsets.remove(rand::thread_rng().gen_range(0..offsets.len()));
+                let tile = ((player_tile.0 + dx) as u16, (player_tile.1 + dy) as u16);
+
+                if !map.map.get(&tile).is_some_and(|tile| {
+                    tile.tiletype == TileType::Floor && tile.mob_count == 0 && tile.hazard.is_none()
+                }) {
+                    continue;
+                }
+
+                ev_mob_spawn.send(MobSpawnEvent {
+                    mob_type: MobType::JungleTurret,
+                    pos: Vec2::new(tile.0 as f32 * TILE_SIZE, tile.1 as f32 * TILE_SIZE),
+                    is_friendly: false,
+                    loot: None,
+                    owner: Some(boss_e),
+                    exp_amount: 0,
+                    elite: EliteRoll::Never,
+                });
+
+                amount_attack -= 1;
+            }
+        }
+        BossAttackType::Vines => {
+            //лозы веером ползут к игроку, каждая - цепочка семян, которые летят все быстрее
+            amount_attack += if empowered { 5 } else { 3 };
+
+            let angle_disp = PI / 10.;
+            let base_angle = (player_pos.translation - boss_position.translation)
+                .truncate()
+                .to_angle()
+                - angle_disp * (amount_attack - 1) as f32 / 2.;
+
+            for i in 0..amount_attack {
+                let angle = base_angle + angle_disp * i as f32;
+
+                for segment in 0..6 {
+                    ev_spawn_projectile.send(SpawnProjectileEvent {
+                        texture_path: "textures/earthquake.png".to_string(),
+                        color: ElementType::Earth.color(),
+                        translation: boss_position.translation,
+                        angle,
+                        trajectory: Trajectory::Straight,
+                        collider_radius: 6.0,
+                        speed: 40.0 + segment as f32 * 12.,
+                        damage: 15,
+                        element: ElementType::Earth,
+                        is_friendly: false,
+                        can_go_through_walls: false,
+                        modifiers: vec![],
+                    });
+                }
+            }
+        }
+        BossAttackType::RaiseCorpses => {
+            //поднимаем трупы на арене, усиленная версия поднимает все сразу
+            let limit = if empowered { usize::MAX } else { 4 };
+
+            for (corpse_e, corpse_pos, corpse) in corpse_query.iter().take(limit) {
+                ev_mob_spawn.send(MobSpawnEvent {
+                    mob_type: corpse.mob_type.clone(),
+                    pos: corpse_pos.translation.truncate(),
+                    is_friendly: false,
+                    loot: None,
+                    owner: Some(boss_e),
+                    exp_amount: 0,
+                    elite: EliteRoll::Never,
+                });
+
+                ev_spawn_particles.send(SpawnParticlesEvent {
+                    pattern: ParticlePattern::Burst {
+                        direction: Vec2::Y,
+                        distance: 24.,
+                        spread: PI / 3.,
+                    },
+                    position: corpse_pos.translation,
+                    amount: 8,
+                    color: Color::srgb(0.6, 0.2, 0.8),
+                    speed: 15.,
+                    rotate: false,
+                });
+
+                commands.entity(corpse_e).despawn_recursive();
+            }
+        }
         _ => {
             println!("ERROR OCCURED, NO ATTACK PICKED");
         }
@@ -672,46 +953,19 @@ impl TryFrom<usize> for BossAttackType {
             value if value == BossAttackType::Blank as usize => Ok(BossAttackType::Blank),
             value if value == BossAttackType::Wall as usize => Ok(BossAttackType::Wall),
             value if value == BossAttackType::MegaStan as usize => Ok(BossAttackType::MegaStan),
+            value if value == BossAttackType::Burrow as usize => Ok(BossAttackType::Burrow),
+            value if value == BossAttackType::SpawnJungleTurret as usize => {
+                Ok(BossAttackType::SpawnJungleTurret)
+            }
+            value if value == BossAttackType::Vines as usize => Ok(BossAttackType::Vines),
+            value if value == BossAttackType::RaiseCorpses as usize => {
+                Ok(BossAttackType::RaiseCorpses)
+            }
             _ => Err(()),
         }
     }
 }
 
-//weights depend on:
-//Cooldown,
-//range to the player
-//phase
-//boss hp
-//base value
-//position of player
-//is there such mobs
-
-//Увеличение веса защитных заклинаний в зависимости от расстояния проджектайлов игрока до босса
-pub fn projectiles_check(
-    friendly_projs_query: Query<(&Projectile, &Transform), With<Friendly>>,
-    mut big_boss_query: Query<(&Transform, &mut BossAttackSystem)>,
-) {
-    let Ok((boss_pos, mut weights_system)) = big_boss_query.get_single_mut() else {
-        return;
-    };
-    for (_proj, proj_pos) in friendly_projs_query.iter() {
-        weights_system.weight_array[BossAttackType::Blank as usize] += (7500.
-            / boss_pos
-                .translation
-                .truncate()
-                .distance(proj_pos.translation.truncate()))
-            as i16
-            + 1;
-        weights_system.weight_array[BossAttackType::Shield as usize] += (7500.
-            / boss_pos
-                .translation
-                .truncate()
-                .distance(proj_pos.translation.truncate()))
-            as i16
-            + 1;
-    }
-}
-
 //Система проверки и кика из очереди саммонов, если юнит убит
 pub fn check_is_summon_alive(mob_query: Query<&Mob>, mut summoner_query: Query<&mut SummonQueue>) {
     for mut summon_list in summoner_query.iter_mut() {
@@ -728,189 +982,132 @@ pub fn check_is_summon_alive(mob_query: Query<&Mob>, mut summoner_query: Query<&
     }
 }
 
-//система перерасчета веса атак босса, зависит от расстояния до игрока, от расстояния игрока до стен, от кол-ва хп, от кол-ва призывных юнитов, от текущей фазы босса
+//система перерасчета веса атак босса: базовый вес из описания фазы + факторы атаки + общая надбавка по типу атаки
 pub fn recalculate_weights(
     mut boss_query: Query<(
         &mut BossAttackSystem,
-        &mut SummonQueue,
+        &BossScript,
+        &SummonQueue,
         &Health,
         &Transform,
         &PhaseManager,
     )>,
     player_query: Query<&Transform, With<Player>>,
     wall_query: Query<&Transform, With<Wall>>,
+    friendly_projs_query: Query<&Transform, (With<Projectile>, With<Friendly>)>,
+    corpse_query: Query<&Corpse, Without<BusyRaising>>,
 ) {
-    let Ok((mut attack_system, summon_list, boss_hp, boss_transform, phase_manager)) =
+    let Ok((mut attack_system, script, summon_list, boss_hp, boss_transform, phase_manager)) =
         boss_query.get_single_mut()
     else {
         return;
     };
 
+    if script.phase(phase_manager.current_phase).is_none() {
+        return;
+    }
+
     let Ok(player_transform) = player_query.get_single() else {
         println!("Player died! or smth");
         return;
     };
-    let phase = phase_manager.current_phase;
 
-    let sorted_walls: Vec<&Transform> = wall_query
+    let wall_dist = wall_query
         .iter()
-        .sort_by::<&Transform>(|item1, item2| {
-            item1
-                .translation
-                .distance(player_transform.translation)
-                .total_cmp(&item2.translation.distance(player_transform.translation))
-        })
-        .collect();
+        .map(|wall| wall.translation.distance(player_transform.translation))
+        .min_by(|a, b| a.total_cmp(b))
+        .unwrap_or(TILE_SIZE)
+        .max(1.);
+
+    let boss_dist = player_transform
+        .translation
+        .distance(boss_transform.translation)
+        .max(1.);
+
+    let summons_alive = summon_list
+        .queue
+        .iter()
+        .filter(|summon_unit| summon_unit.mob_type != MobType::Mossling)
+        .count() as i32;
+
+    let lost_hp = boss_hp.max - boss_hp.current;
 
     for i in 0..attack_system.weight_array.len() {
-        let mut base_weight: i16 = i as i16 * 100;
+        let attack_type = BossAttackType::try_from(i).unwrap();
 
-        if attack_system.cooldown_mask & (1 << i) == 0 {
-            attack_system.weight_array[i] = -10000;
+        let Some(attack) = script.attack(phase_manager.current_phase, attack_type) else {
+            attack_system.weight_array[i] = None;
             continue;
-        }
-        let attack_flag;
-        let mut mob_spawn = MobType::Mossling;
-
-        match BossAttackType::try_from(i).unwrap() {
-            BossAttackType::SpawnEarthElemental => {
-                base_weight += (phase == 3) as i16 * i16::MIN
-                    + 5000
-                        * (summon_list
-                            .queue
-                            .iter()
-                            .filter(|summon_unit| summon_unit.mob_type != MobType::Mossling)
-                            .count()
-                            == 0) as i16;
-
-                mob_spawn = MobType::EarthElemental;
-                attack_flag = BossAttackFlag::SpawnSpells;
-            }
-            BossAttackType::SpawnAirElemental => {
-                base_weight += (phase != 1) as i16 * i16::MIN;
+        };
 
-                mob_spawn = MobType::AirElemental;
-                attack_flag = BossAttackFlag::SpawnSpells;
-            }
-            BossAttackType::SpawnFireElemental => {
-                base_weight += (phase == 3) as i16 * i16::MIN;
-
-                mob_spawn = MobType::FireElemental;
-                attack_flag = BossAttackFlag::SpawnSpells;
-            } /*
-            BossAttackType::SpawnClayGolem => {
-            base_weight += (phase != 1) as i16 * i16::MIN;
-
-            mob_spawn = MobType::ClayGolem;
-            attack_flag = BossAttackFlag::SpawnSpells;
-            } */
-            BossAttackType::SpawnWaterElemental => {
-                base_weight += (phase == 3) as i16 * i16::MIN;
-
-                mob_spawn = MobType::WaterElemental;
-                attack_flag = BossAttackFlag::SpawnSpells;
-            }
-            BossAttackType::Radial => {
-                base_weight += (phase == 1) as i16 * i16::MIN
-                    + (player_transform
-                        .translation
-                        .distance(sorted_walls[0].translation)
-                        / 30.) as i16;
-                attack_flag = BossAttackFlag::ProjectileSpells;
-            }
-            BossAttackType::Shield => {
-                base_weight += (phase != 2) as i16 * i16::MIN;
-                attack_flag = BossAttackFlag::DefensiveSpells;
-            }
-            BossAttackType::Blank => {
-                base_weight += (phase != 3) as i16 * i16::MIN;
-                attack_flag = BossAttackFlag::DefensiveSpells;
-            }
-            BossAttackType::Wall => {
-                base_weight += (phase == 1) as i16 * i16::MIN
-                    + (3000.
-                        / player_transform
-                            .translation
-                            .distance(sorted_walls[0].translation)) as i16; //add bonus when player near walls
-                attack_flag = BossAttackFlag::ProjectileSpells;
-            }
-            BossAttackType::MegaStan => {
-                base_weight += (phase <= 2) as i16 * i16::MIN
-                    + (5000.
-                        / player_transform
-                            .translation
-                            .distance(boss_transform.translation)) as i16;
-                attack_flag = BossAttackFlag::ProjectileSpells; //dd bonus when player far away from walls
-            }
-            BossAttackType::FastPierce => {
-                base_weight += (phase == 1) as i16 * i16::MIN
-                    + (player_transform
-                        .translation
-                        .distance(boss_transform.translation)
-                        / 30.) as i16;
-                attack_flag = BossAttackFlag::ProjectileSpells; // add bonus when player far from boss
-            }
-
-            BossAttackType::ProjectilePattern => {
-                base_weight += (phase != 2) as i16 * i16::MIN
-                    + (player_transform
-                        .translation
-                        .distance(boss_transform.translation)
-                        / 50.) as i16; //add bonus when player far away from walls
-                attack_flag = BossAttackFlag::ProjectileSpells;
-            }
+        if !attack_system.ready_array[i] {
+            attack_system.weight_array[i] = None;
+            continue;
         }
 
-        if base_weight <= i16::MIN / 2 {
-            attack_system.weight_array[i] = base_weight;
-            continue;
+        let mut weight = attack.weight;
+
+        for factor in attack.factors.iter() {
+            weight += match *factor {
+                WeightFactor::PlayerNearWall(k) => k / wall_dist,
+                WeightFactor::PlayerFarFromWall(k) => wall_dist / k,
+                WeightFactor::PlayerNearBoss(k) => k / boss_dist,
+                WeightFactor::PlayerFarFromBoss(k) => boss_dist / k,
+                WeightFactor::NoSummonsAlive(k) => k * (summons_alive == 0) as i32 as f32,
+                WeightFactor::PerSummonAlive(k) => k * summon_list.amount_of_mobs as f32,
+                WeightFactor::CorpsesAround(k) => k * corpse_query.iter().count() as f32,
+                //защищаемся, когда в босса летят снаряды игрока
+                WeightFactor::IncomingProjectiles(k) => friendly_projs_query
+                    .iter()
+                    .map(|proj_pos| {
+                        k / boss_transform
+                            .translation
+                            .truncate()
+                            .distance(proj_pos.translation.truncate())
+                            .max(1.)
+                            + 1.
+                    })
+                    .sum(),
+            } as i32;
         }
 
-        let dist = player_transform
-            .translation
-            .distance(boss_transform.translation)
-            .floor() as i16;
+        let dist = boss_dist.floor() as i32;
 
-        match attack_flag {
+        weight += match attack_type.flag() {
             BossAttackFlag::DefensiveSpells => {
-                base_weight += ((boss_hp.max - boss_hp.current) / 20 * 3) as i16
-                    + dist * (dist <= 200 || dist >= 400) as i16;
+                lost_hp / 20 * 3 + dist * (dist <= 200 || dist >= 400) as i32
             }
 
-            BossAttackFlag::ProjectileSpells => {
-                base_weight += ((boss_hp.max - boss_hp.current) / 20) as i16 + dist * 10;
-            }
+            BossAttackFlag::ProjectileSpells => lost_hp / 20 + dist * 10,
 
-            BossAttackFlag::SpawnSpells => {
-                base_weight += (summon_list.queue.len() + 5
-                    - summon_list
-                        .queue
-                        .iter()
-                        .filter(|summon_unit| summon_unit.mob_type != MobType::Mossling)
-                        .count()) as i16
-                    * 100
+            BossAttackFlag::SpawnSpells(mob_spawn) => {
+                (summon_list.queue.len() as i32 + 5 - summons_alive) * 100
                     - summon_list
                         .queue
                         .iter()
                         .filter(|x| x.mob_type == mob_spawn)
-                        .count() as i16
-                        * 50;
+                        .count() as i32
+                        * 50
+                    + lost_hp / 20
+            }
 
-                base_weight += ((boss_hp.max - boss_hp.current) / 20) as i16
-                    - (summon_list.amount_of_mobs as i32 * (150 * ((phase == 2) as i32 + 50)))
-                        as i16;
+            //кто встанет из трупов - неизвестно, поэтому без штрафа за одинаковых призванных
+            BossAttackFlag::RaiseSpells => {
+                (summon_list.queue.len() as i32 + 5 - summons_alive) * 100 + lost_hp / 20
             }
-        }
+        };
 
-        attack_system.weight_array[i] = base_weight;
+        attack_system.weight_array[i] = Some(weight);
     }
-    //calculate all factors, phase included once in a time like in 1 second
 }
 
 //Система для таймера между атак босса
 pub fn tick_cooldown_boss(
     mut commands: Commands,
-    mut attack_timers: Query<(Entity, &mut BossAttackSystem), With<OnCooldownFlag>>,
+    mut attack_timers: Query<
+        (Entity, &mut BossAttackSystem),
+        (With<OnCooldownFlag>, Without<Burrowed>),
+    >,
     time: Res<Time>,
 ) {
     //add on cooldown state, so we don't tick timer during attack
@@ -931,59 +1128,70 @@ pub fn tick_every_spell_cooldown(mut attack_timers: Query<&mut BossAttackSystem>
         return;
     };
     for i in 0..attack_system.cooldown_array.len() {
-        if attack_system.cooldown_mask & (1u32 << i) != 1 {
-            attack_system.cooldown_array[i].tick(time.delta());
-            
-            if attack_system.cooldown_array[i].just_finished() {
+        if attack_system.ready_array[i] {
+            continue;
+        }
 
-                attack_system.cooldown_mask |= 1u32 << i;
-            }
+        attack_system.cooldown_array[i].tick(time.delta());
+
+        if attack_system.cooldown_array[i].finished() {
+            attack_system.ready_array[i] = true;
         }
     }
 }
 
-//Каст пустышки при необходимом весе(вне очереди)
-pub fn cast_blank(
+//Каст защитных заклинаний вне очереди, когда их вес дошел до порога из описания (щит, пустышка)
+pub fn cast_reactive_attacks(
+    mut boss_query: Query<(Entity, &mut BossAttackSystem, &BossScript, &PhaseManager, &Transform)>,
     mut spawn_blank_ev: EventWriter<SpawnBlankEvent>,
-    mut boss_query: Query<(&mut BossAttackSystem, &Transform)>,
+    mut cast_shield: EventWriter<SpawnShieldEvent>,
 ) {
-    let Ok((mut attack_system, pos)) = boss_query.get_single_mut() else {
+    let Ok((boss_e, mut attack_system, script, phase_manager, pos)) = boss_query.get_single_mut()
+    else {
         return;
     };
 
-    if attack_system.weight_array[BossAttackType::Blank as usize] > 2500 {
-        attack_system.cooldown_mask ^= 1u32 << BossAttackType::Blank as usize;
-        spawn_blank_ev.send(SpawnBlankEvent {
-            range: 18.,
-            position: Vec3::new(pos.translation.x, pos.translation.y, 1.1),
-            speed: 4.5,
-            is_friendly: false,
-        });
-    }
-}
-
-//Каст щита вне очереди, если достаточно веса
-pub fn cast_shield(
-    mut boss_query: Query<(Entity, &mut BossAttackSystem)>,
-    mut cast_shield: EventWriter<SpawnShieldEvent>,
-) {
-    let Ok((boss_e, mut attack_system)) = boss_query.get_single_mut() else {
+    let Some(phase) = script.phase(phase_manager.current_phase) else {
         return;
     };
 
-    if attack_system.weight_array[BossAttackType::Shield as usize] >= 2500 {
-        attack_system.cooldown_mask ^= 1u32 << BossAttackType::Shield as usize;
-        cast_shield.send(SpawnShieldEvent {
-            duration: 4.,
-            owner: boss_e,
-            is_friendly: false,
-            size: 64,
-        });
+    for attack in phase.attacks.iter() {
+        let Some(threshold) = attack.reactive_threshold else {
+            continue;
+        };
+
+        let Some(Some(weight)) = attack_system.weight_array.get(attack.attack as usize) else {
+            continue;
+        };
+
+        if *weight < threshold {
+            continue;
+        }
+
+        match attack.attack {
+            BossAttackType::Blank => {
+                spawn_blank_ev.send(SpawnBlankEvent {
+                    range: 18.,
+                    position: Vec3::new(pos.translation.x, pos.translation.y, 1.1),
+                    speed: 4.5,
+                    is_friendly: false,
+                });
+            }
+            BossAttackType::Shield => {
+                cast_shield.send(SpawnShieldEvent {
+                    duration: 4.,
+                    owner: boss_e,
+                    is_friendly: false,
+                    size: 64,
+                });
+            }
+            _ => {}
+        }
+
+        attack_system.start_cooldown(attack.attack, attack.cooldown);
     }
-    //when weight overcomes certain value - cast and cooldown
 }
 
-
 #[derive(Component, Clone)]
 pub struct BeforeAttackDelayBoss {
     timer: Timer,
@@ -1007,11 +1215,16 @@ pub fn warn_player_abt_attack(
         &BossAttackFlagComp,
         &mut BeforeAttackDelayBoss,
         &Transform,
+        &BossScript,
+        &PhaseManager,
     )>,
     mut commands: Commands,
     mut ev_spawn_alert: EventWriter<SpawnAlertEvent>,
+    mut ev_telegraph: EventWriter<BossTelegraphEvent>,
 ) {
-    let Ok((boss_e, mut boss, attack_flag, mut delay, pos)) = boss_query.get_single_mut() else {
+    let Ok((boss_e, mut boss, attack_flag, mut delay, pos, script, phase_manager)) =
+        boss_query.get_single_mut()
+    else {
         return;
     };
     delay.timer.tick(time.delta());
@@ -1019,67 +1232,76 @@ pub fn warn_player_abt_attack(
         ev_spawn_alert.send(SpawnAlertEvent {
             position: pos.translation.truncate().with_y(pos.translation.y + 24.),
             attack_alert: true,
+            spotted_by: None,
+        });
+        ev_telegraph.send(BossTelegraphEvent {
+            attack: attack_flag.attack_picked,
         });
         //spawn marker
         delay.check = false;
     }
     if delay.timer.just_finished() {
-        let mask_xor = 0b0000011111111111^(1u32 << attack_flag.attack_picked.clone() as usize);
-        boss.cooldown_mask &= mask_xor;
+        if let Some(attack) = script.attack(phase_manager.current_phase, attack_flag.attack_picked) {
+            boss.start_cooldown(attack_flag.attack_picked, attack.cooldown);
+        }
         commands.entity(boss_e).remove::<BeforeAttackDelayBoss>();
     }
 }
 
-//триггер выбора атаки у босса
-pub fn pick_attack_to_perform_koldun(
+//триггер выбора атаки у босса: самая тяжелая готовая атака, иногда вторая по весу
+//защитные атаки с порогом кастуются отдельно и сюда не попадают
+pub fn pick_boss_attack(
     In(entity): In<Entity>,
-    attack_system: Query<&BossAttackSystem>,
+    boss_query: Query<(&BossAttackSystem, &BossScript, &PhaseManager)>,
 ) -> Option<Option<BossAttackType>> {
-    let Ok(attack_system) = attack_system.get(entity) else {
+    let Ok((attack_system, script, phase_manager)) = boss_query.get(entity) else {
         println!("No attacks system?");
         return None;
     };
-    let mut pick_1 = 0;
-    let mut pick_2 = -1;
 
-    let mut largest_value = attack_system.weight_array[0];
-
-    for i in 0..attack_system.weight_array.len() {
-        if attack_system.weight_array[i] > largest_value {
-            largest_value = attack_system.weight_array[i];
+    let mut candidates: Vec<(i32, BossAttackType)> = attack_system
+        .weight_array
+        .iter()
+        .enumerate()
+        .filter_map(|(i, weight)| Some(((*weight)?, BossAttackType::try_from(i).ok()?)))
+        .filter(|(_, attack_type)| {
+            script
+                .attack(phase_manager.current_phase, *attack_type)
+                .is_some_and(|attack| attack.reactive_threshold.is_none())
+        })
+        .collect();
 
-            pick_2 = pick_1;
-            pick_1 = i as i16;
-        }
+    //все атаки на кулдауне - ждем
+    if candidates.is_empty() {
+        return None;
     }
 
-    let chance_to_pick = rand::thread_rng().gen_range(0.0..1.0);
+    candidates.sort_by(|a, b| b.0.cmp(&a.0));
 
-    if chance_to_pick >= 0.65 && pick_2 > 0 {
-        return Some(Some(BossAttackType::try_from(pick_2 as usize).unwrap()));
-    }
+    let chance_to_pick = rand::thread_rng().gen_range(0.0..1.0);
 
-    if largest_value < 0 {
-        println!("ERROR VALUE");
-        return None;
+    if chance_to_pick >= 0.65 && candidates.len() > 1 {
+        return Some(Some(candidates[1].1));
     }
 
-    return Some(Some(BossAttackType::try_from(pick_1 as usize).unwrap()));
-    //pick with random attack including weights, like idk, use coeff or smth
+    return Some(Some(candidates[0].1));
 }
 
 //телепорт босса в половину ренджи до игрока раз в таймер
 fn boss_teleport(
     mut boss_query: Query<
-        (&mut Transform, &mut Teleport, &PhaseManager),
-        (Without<Player>, With<BossAttackSystem>),
+        (&mut Transform, &mut Teleport, &PhaseManager, &BossScript),
+        (Without<Player>, With<BossAttackSystem>, Without<Burrowed>),
     >,
     time: Res<Time>,
     mut player_query: Query<&Transform, With<Player>>,
 ) {
-    for (mut transform, mut teleport, phase_manager) in boss_query.iter_mut() {
-        if phase_manager.current_phase != 2 {
-            return;
+    for (mut transform, mut teleport, phase_manager, script) in boss_query.iter_mut() {
+        if !script
+            .phase(phase_manager.current_phase)
+            .is_some_and(|phase| phase.movement == BossMovementStyle::Teleport)
+        {
+            continue;
         }
 
         teleport.time_to_teleport.tick(time.delta());
@@ -1097,18 +1319,25 @@ fn boss_teleport(
 
 //передвижение босса в случайную сторону раз в тик таймера
 fn boss_running(
-    mut boss_query: Query<(
-        &Transform,
-        &mut BossMovement,
-        &mut LinearVelocity,
-        &PhaseManager,
-    )>,
+    mut boss_query: Query<
+        (
+            &Transform,
+            &mut BossMovement,
+            &mut LinearVelocity,
+            &PhaseManager,
+            &BossScript,
+        ),
+        Without<Burrowed>,
+    >,
     time: Res<Time>,
     player_query: Query<&Transform, With<Player>>,
 ) {
-    for (transform, mut movement_ability, mut linvel, phase_manager) in boss_query.iter_mut() {
-        if phase_manager.current_phase != 3 {
-            return;
+    for (transform, mut movement_ability, mut linvel, phase_manager, script) in boss_query.iter_mut() {
+        if !script
+            .phase(phase_manager.current_phase)
+            .is_some_and(|phase| phase.movement == BossMovementStyle::Wander)
+        {
+            continue;
         }
 
         linvel.0 = movement_ability.direction * movement_ability.speed * time.delta_seconds();
@@ -1134,3 +1363,149 @@ fn boss_running(
         }
     }
 }
+
+//босс под землей: слои коллизий сохраняем, чтобы вернуть их при выходе
+#[derive(Component)]
+pub struct Burrowed {
+    timer: Timer,
+    dust_timer: Timer,
+    layers: CollisionLayers,
+    empowered: bool,
+}
+
+impl Burrowed {
+    fn new(layers: CollisionLayers, empowered: bool) -> Self {
+        Self {
+            timer: Timer::new(Duration::from_millis(2500), TimerMode::Once),
+            dust_timer: Timer::new(Duration::from_millis(150), TimerMode::Repeating),
+            layers,
+            empowered,
+        }
+    }
+}
+
+//зарывшийся босс ползет к игроку, оставляя след пыли, и вылезает с кольцом снарядов
+fn move_burrowed_boss(
+    mut commands: Commands,
+    mut boss_query: Query<
+        (Entity, &mut Transform, &mut Burrowed, &mut LinearVelocity),
+        Without<Player>,
+    >,
+    player_query: Query<&Transform, With<Player>>,
+    time: Res<Time>,
+    mut ev_spawn_projectile: EventWriter<SpawnProjectileEvent>,
+    mut ev_spawn_particles: EventWriter<SpawnParticlesEvent>,
+    mut ev_shake_camera: EventWriter<CameraShakeEvent>,
+) {
+    for (boss_e, mut transform, mut burrowed, mut linvel) in boss_query.iter_mut() {
+        let Ok(player_pos) = player_query.get_single() else {
+            return;
+        };
+
+        let direction = (player_pos.translation - transform.translation)
+            .truncate()
+            .normalize_or_zero();
+        linvel.0 = direction * 7000. * time.delta_seconds();
+
+        //без коллизий стены не держат, поэтому не даем уползти за арену
+        let lower = (ROOM_SIZE / 2 - 7) as f32 * TILE_SIZE;
+        let upper = (ROOM_SIZE / 2 + 7) as f32 * TILE_SIZE;
+        transform.translation.x = transform.translation.x.clamp(lower, upper);
+        transform.translation.y = transform.translation.y.clamp(lower, upper);
+
+        burrowed.dust_timer.tick(time.delta());
+        if burrowed.dust_timer.just_finished() {
+            ev_spawn_particles.send(SpawnParticlesEvent {
+                pattern: ParticlePattern::Circle { radius: 8. },
+                position: transform.translation,
+                amount: 3,
+                color: ElementType::Earth.color(),
+                speed: 5.,
+                rotate: false,
+            });
+        }
+
+        burrowed.timer.tick(time.delta());
+        if !burrowed.timer.just_finished() {
+            continue;
+        }
+
+        linvel.0 = Vec2::ZERO;
+
+        let amount = if burrowed.empowered { 20 } else { 12 };
+        let offset = 2.0 * PI / amount as f32;
+
+        for i in 0..amount {
+            ev_spawn_projectile.send(SpawnProjectileEvent {
+                texture_path: "textures/earthquake.png".to_string(),
+                color: ElementType::Earth.color(),
+                translation: transform.translation.with_z(1.0),
+                angle: offset * i as f32,
+                trajectory: Trajectory::Straight,
+                collider_radius: 8.0,
+                speed: 90.0,
+                damage: 20,
+                element: ElementType::Earth,
+                is_friendly: false,
+                can_go_through_walls: false,
+                modifiers: vec![],
+            });
+        }
+
+        ev_spawn_particles.send(SpawnParticlesEvent {
+            pattern: ParticlePattern::Circle { radius: 32. },
+            position: transform.translation,
+            amount: 16,
+            color: ElementType::Earth.color(),
+            speed: 30.,
+            rotate: false,
+        });
+        ev_shake_camera.send(CameraShakeEvent);
+
+        commands
+            .entity(boss_e)
+            .insert(burrowed.layers)
+            .insert(Visibility::Inherited)
+            .remove::<Burrowed>();
+    }
+}
+
+//награда за босса: предметы и хилки из описания, damage_mobs кладет их в событие смерти до деспавна
+fn drop_boss_loot(
+    mut ev_mob_death: EventReader<MobDeathEvent>,
+    mut ev_item: EventWriter<SpawnItemEvent>,
+    mut ev_health_tank: EventWriter<SpawnHealthTankEvent>,
+    item_database: Res<Assets<ItemDatabase>>,
+    handle: Res<ItemDatabaseHandle>,
+) {
+    for ev in ev_mob_death.read() {
+        let Some(loot) = ev.boss_loot.as_ref() else {
+            continue;
+        };
+
+        for i in 0..loot.items {
+            let item: ItemType = rand::random();
+
+            let item_name: String = item_database.get(handle.0.id()).unwrap().items[item as usize]["name"].as_str().unwrap().to_string();
+            let texture_name: String = item_database.get(handle.0.id()).unwrap().items[item as usize]["texture_name"].as_str().unwrap().to_string();
+            let item_description: String = item_database.get(handle.0.id()).unwrap().items[item as usize]["description"].as_str().unwrap().to_string();
+
+            let texture_path = format!("textures/items/{}", texture_name);
+
+            ev_item.send(SpawnItemEvent {
+                pos: Vec3::new(ev.pos.x + (i as f32 - (loot.items - 1) as f32 / 2.) * TILE_SIZE, ev.pos.y, 1.),
+                item_type: item,
+                texture_path,
+                item_name,
+                item_description,
+            });
+        }
+
+        for i in 0..loot.health_tanks {
+            ev_health_tank.send(SpawnHealthTankEvent {
+                pos: Vec3::new(ev.pos.x + (i as f32 - (loot.health_tanks - 1) as f32 / 2.) * TILE_SIZE, ev.pos.y - TILE_SIZE, 1.),
+                hp: 30,
+            });
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//описание боссов данными: фазы, атаки с весами и кулдаунами, действия при смене фазы
+//сам бой считает один общий "мозг" босса в boss_behaviour.rs
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::BossAttackType;
+
+#[derive(Deserialize, Asset, TypePath)]
+pub struct BossDatabase {
+    pub bosses: Vec<BossDefinition>,
+}
+
+#[derive(Resource)]
+pub struct BossDatabaseHandle(pub Handle<BossDatabase>);
+
+pub fn load_boss_database(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(BossDatabaseHandle(asset_server.load("bosses.json")));
+}
+
+impl BossDatabase {
+    pub fn get(&self, name: &str) -> Option<&BossDefinition> {
+        self.bosses.iter().find(|boss| boss.name == name)
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct BossDefinition {
+    pub name: String,
+    //имя на полоске здоровья
+    pub title: String,
+    //пауза между атаками в секундах
+    pub cooldown_between_attacks: f32,
+    pub phases: Vec<PhaseDefinition>,
+    #[serde(default)]
+    pub loot: BossLoot,
+}
+
+//что падает с босса после смерти
+#[derive(Deserialize, Clone, Default)]
+pub struct BossLoot {
+    pub items: u8,
+    pub health_tanks: u8,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct PhaseDefinition {
+    //фаза начинается, когда хп <= max * hp_threshold (у первой фазы 1.0)
+    pub hp_threshold: f32,
+    pub movement: BossMovementStyle,
+    pub attacks: Vec<AttackDefinition>,
+    //новый размер очереди призванных юнитов
+    #[serde(default)]
+    pub summon_limit: Option<u8>,
+    //больше не призывает, портал ждет только оставшихся мобов
+    #[serde(default)]
+    pub stop_summons: bool,
+    //что босс делает вне очереди при входе в фазу
+    #[serde(default)]
+    pub on_enter: Vec<TransitionAction>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct AttackDefinition {
+    pub attack: BossAttackType,
+    pub weight: i32,
+    //кулдаун атаки в секундах
+    pub cooldown: f32,
+    #[serde(default)]
+    pub factors: Vec<WeightFactor>,
+    //усиленный вариант атаки (больше проджектайлов, вторая волна)
+    #[serde(default)]
+    pub empowered: bool,
+    //атака кастуется сразу, как только вес дошел до порога (щит, пустышка)
+    #[serde(default)]
+    pub reactive_threshold: Option<i32>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+pub enum BossMovementStyle {
+    Static,
+    Teleport,
+    Wander,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+pub enum TransitionAction {
+    Blank,
+    Teleport,
+    Shield,
+}
+
+//из чего складывается вес атаки помимо базового, число - коэффициент
+#[derive(Deserialize, Clone, Copy)]
+pub enum WeightFactor {
+    PlayerNearWall(f32),
+    PlayerFarFromWall(f32),
+    PlayerNearBoss(f32),
+    PlayerFarFromBoss(f32),
+    NoSummonsAlive(f32),
+    //обычно отрицательный - штраф призыву за каждого живого призванного
+    PerSummonAlive(f32),
+    CorpsesAround(f32),
+    IncomingProjectiles(f32),
+}
+
+//компонент босса, определение подтягивается из базы после загрузки
+#[derive(Component)]
+pub struct BossScript {
+    pub name: String,
+    pub definition: Option<BossDefinition>,
+}
+
+impl BossScript {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            definition: None,
+        }
+    }
+
+    //фазы в PhaseManager нумеруются с 1
+    pub fn phase(&self, current_phase: u8) -> Option<&PhaseDefinition> {
+        let index = (current_phase as usize).checked_sub(1)?;
+        self.definition.as_ref()?.phases.get(index)
+    }
+
+    pub fn attack(&self, current_phase: u8, attack: BossAttackType) -> Option<&AttackDefinition> {
+        self.phase(current_phase)
+            .and_then(|phase| phase.attacks.iter().find(|definition| definition.attack == attack))
+    }
+}
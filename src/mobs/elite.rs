@@ -0,0 +1,400 @@
+//элитные мобы: модификаторы навешиваются поверх любого MobAI при спавне
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    elements::{ElementResistance, ElementType},
+    health::{Health, Hit},
+    item::{ItemDatabase, ItemDatabaseHandle, ItemType, SpawnItemEvent},
+    particles::{ParticlePattern, SpawnParticlesEvent},
+    player::Player,
+    shield_spell::SpawnShieldEvent,
+    GameState,
+};
+
+use super::{
+    AttackComponent, Mob, MobDamageDealtEvent, MobLoot, MobSpawnEvent, MobType, Orbital,
+    Phasing, SearchAndPursue, Teleport,
+};
+
+pub struct ElitePlugin;
+
+impl Plugin for ElitePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<EliteDeathEvent>().add_systems(
+            Update,
+            (
+                setup_elites,
+                shrink_split_copies,
+                sync_elite_outline,
+                elite_shield,
+                elemental_aura,
+                vampiric_heal,
+                elite_death,
+            )
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+//может ли моб из ивента спавна стать элитным
+#[derive(Clone, Copy, PartialEq)]
+pub enum EliteRoll {
+    //обычный моб уровня, шанс зависит от главы
+    Roll,
+    //призванные, поднятые, боссы и друзья
+    Never,
+    //маленькая копия расколовшегося элитного моба
+    SplitCopy,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum EliteModifier {
+    Shielded,
+    Splitting,
+    ElementalAura(ElementType),
+    Hasted,
+    Vampiric,
+}
+
+impl EliteModifier {
+    fn random() -> Self {
+        match rand::thread_rng().gen_range(0..5) {
+            0 => EliteModifier::Shielded,
+            1 => EliteModifier::Splitting,
+            2 => EliteModifier::ElementalAura(rand::random()),
+            3 => EliteModifier::Hasted,
+            _ => EliteModifier::Vampiric,
+        }
+    }
+
+    //цвет обводки, значения больше 1 - свечение
+    fn color(&self) -> Color {
+        match self {
+            EliteModifier::Shielded => Color::srgb(1.0, 1.5, 2.5),
+            EliteModifier::Splitting => Color::srgb(1.5, 2.5, 1.0),
+            EliteModifier::ElementalAura(element) => element.color(),
+            EliteModifier::Hasted => Color::srgb(2.5, 2.5, 1.0),
+            EliteModifier::Vampiric => Color::srgb(2.5, 0.5, 0.5),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Elite {
+    pub modifiers: Vec<EliteModifier>,
+}
+
+impl Elite {
+    //шанс растет с главой: 5%, 10%, 15%, на поздних главах бывает несколько модификаторов
+    pub fn roll(chapter: u8) -> Option<Self> {
+        let mut rng = rand::thread_rng();
+        let chapter = chapter.clamp(1, 3);
+
+        if rng.gen_range(0.0..1.0) >= 0.05 * chapter as f32 {
+            return None;
+        }
+
+        let mut amount = 1;
+        if chapter >= 2 && rng.gen_range(0.0..1.0) < 0.3 {
+            amount += 1;
+        }
+        if chapter >= 3 && rng.gen_range(0.0..1.0) < 0.15 {
+            amount += 1;
+        }
+
+        let mut modifiers: Vec<EliteModifier> = vec![];
+        while modifiers.len() < amount {
+            let modifier = EliteModifier::random();
+            //две ауры разных стихий тоже считаем одинаковыми
+            if !modifiers.iter().any(|m| {
+                std::mem::discriminant(m) == std::mem::discriminant(&modifier)
+            }) {
+                modifiers.push(modifier);
+            }
+        }
+
+        Some(Self { modifiers })
+    }
+}
+
+#[derive(Component)]
+pub struct SplitCopy;
+
+#[derive(Component)]
+struct EliteOutline;
+
+#[derive(Component)]
+struct EliteShield {
+    timer: Timer,
+}
+
+#[derive(Component)]
+struct ElementalAura {
+    element: ElementType,
+    timer: Timer,
+}
+
+#[derive(Component)]
+struct Vampiric;
+
+//ивент из damage_mobs, моб к этому моменту уже деспавнен
+#[derive(Event)]
+pub struct EliteDeathEvent {
+    pub pos: Vec3,
+    pub mob_type: MobType,
+    pub modifiers: Vec<EliteModifier>,
+}
+
+const AURA_RADIUS: f32 = 56.;
+
+//раздаем элитному мобу статы и компоненты модификаторов
+fn setup_elites(
+    mut commands: Commands,
+    mut elite_query: Query<
+        (
+            Entity,
+            &Elite,
+            &mut Health,
+            &mut MobLoot,
+            &mut ElementResistance,
+            &Handle<Image>,
+            Option<&TextureAtlas>,
+            Option<&mut SearchAndPursue>,
+            Option<&mut Phasing>,
+            Option<&mut AttackComponent>,
+            Option<&mut Teleport>,
+            Option<&mut Orbital>,
+        ),
+        Added<Elite>,
+    >,
+) {
+    for (
+        elite_e,
+        elite,
+        mut health,
+        mut loot,
+        mut resistance,
+        texture,
+        atlas,
+        pursue,
+        phasing,
+        attack,
+        teleport,
+        orbital,
+    ) in elite_query.iter_mut()
+    {
+        health.max = (health.max as f32 * 1.5) as i32;
+        health.current = health.max;
+        loot.orbs = loot.orbs * 2 + 5 * elite.modifiers.len() as u32;
+
+        for modifier in elite.modifiers.iter() {
+            match *modifier {
+                EliteModifier::Shielded => {
+                    commands.entity(elite_e).insert(EliteShield {
+                        timer: Timer::from_seconds(6., TimerMode::Repeating),
+                    });
+                }
+                EliteModifier::Splitting => {}
+                EliteModifier::ElementalAura(element) => {
+                    if !resistance.elements.contains(&element) {
+                        resistance.elements.push(element);
+                    }
+                    resistance.resistance_percent[element as usize] = 100;
+
+                    commands.entity(elite_e).insert(ElementalAura {
+                        element,
+                        timer: Timer::from_seconds(1., TimerMode::Repeating),
+                    });
+                }
+                EliteModifier::Vampiric => {
+                    commands.entity(elite_e).insert(Vampiric);
+                }
+                EliteModifier::Hasted => {}
+            }
+        }
+
+        //ускорение трогает все, что есть у моба, поэтому работает с любым ИИ
+        if elite.modifiers.contains(&EliteModifier::Hasted) {
+            if let Some(mut pursue) = pursue {
+                pursue.speed *= 1.5;
+            }
+            if let Some(mut phasing) = phasing {
+                phasing.speed *= 1.5;
+            }
+            if let Some(mut orbital) = orbital {
+                orbital.speed *= 1.5;
+            }
+            if let Some(mut attack) = attack {
+                let duration = attack.cooldown.duration().mul_f32(0.6);
+                attack.cooldown.set_duration(duration);
+            }
+            if let Some(mut teleport) = teleport {
+                let duration = teleport.time_to_teleport.duration().mul_f32(0.6);
+                teleport.time_to_teleport.set_duration(duration);
+            }
+        }
+
+        //обводка - та же картинка позади моба, чуть крупнее и светится цветом первого модификатора
+        commands.entity(elite_e).with_children(|parent| {
+            let mut outline = parent.spawn(SpriteBundle {
+                texture: texture.clone(),
+                sprite: Sprite {
+                    color: elite.modifiers[0].color(),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0., 0., -0.05).with_scale(Vec3::splat(1.2)),
+                ..default()
+            });
+            outline.insert(EliteOutline);
+
+            if let Some(atlas) = atlas {
+                outline.insert(atlas.clone());
+            }
+        });
+    }
+}
+
+//копии расколовшегося моба меньше и слабее оригинала
+fn shrink_split_copies(
+    mut copy_query: Query<(&mut Transform, &mut Health, &mut Mob), Added<SplitCopy>>,
+) {
+    for (mut transform, mut health, mut mob) in copy_query.iter_mut() {
+        transform.scale = Vec3::splat(0.6);
+        health.max /= 2;
+        health.current = health.max;
+        mob.damage /= 2;
+    }
+}
+
+//обводка повторяет кадр анимации и разворот моба
+fn sync_elite_outline(
+    mut outline_query: Query<(&Parent, &mut Sprite, Option<&mut TextureAtlas>), With<EliteOutline>>,
+    elite_query: Query<(&Sprite, Option<&TextureAtlas>), Without<EliteOutline>>,
+) {
+    for (parent, mut sprite, atlas) in outline_query.iter_mut() {
+        let Ok((parent_sprite, parent_atlas)) = elite_query.get(parent.get()) else {
+            continue;
+        };
+
+        sprite.flip_x = parent_sprite.flip_x;
+
+        if let (Some(mut atlas), Some(parent_atlas)) = (atlas, parent_atlas) {
+            atlas.index = parent_atlas.index;
+        }
+    }
+}
+
+fn elite_shield(
+    mut shield_query: Query<(Entity, &mut EliteShield)>,
+    mut ev_shield: EventWriter<SpawnShieldEvent>,
+    time: Res<Time>,
+) {
+    for (elite_e, mut shield) in shield_query.iter_mut() {
+        shield.timer.tick(time.delta());
+
+        if shield.timer.just_finished() {
+            ev_shield.send(SpawnShieldEvent {
+                duration: 2.,
+                owner: elite_e,
+                is_friendly: false,
+                size: 48,
+            });
+        }
+    }
+}
+
+//аура раз в секунду бьет игрока рядом стихией моба
+fn elemental_aura(
+    mut aura_query: Query<(&Transform, &mut ElementalAura), Without<Player>>,
+    mut player_query: Query<(&Transform, &mut Health), With<Player>>,
+    mut ev_spawn_particles: EventWriter<SpawnParticlesEvent>,
+    time: Res<Time>,
+) {
+    let Ok((player_transform, mut player_health)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    for (transform, mut aura) in aura_query.iter_mut() {
+        aura.timer.tick(time.delta());
+
+        if !aura.timer.just_finished() {
+            continue;
+        }
+
+        ev_spawn_particles.send(SpawnParticlesEvent {
+            pattern: ParticlePattern::Circle {
+                radius: AURA_RADIUS,
+            },
+            position: transform.translation,
+            amount: 8,
+            color: aura.element.color(),
+            speed: 5.,
+            rotate: false,
+        });
+
+        let offset = player_transform.translation.truncate() - transform.translation.truncate();
+        if offset.length() <= AURA_RADIUS {
+            player_health.hit_queue.push(Hit {
+                damage: 5,
+                element: Some(aura.element),
+                direction: offset.extend(0.).normalize_or_zero(),
+            });
+        }
+    }
+}
+
+//вампир лечится на двойной урон, который нанес сам
+fn vampiric_heal(
+    mut vampire_query: Query<&mut Health, With<Vampiric>>,
+    mut ev_damage_dealt: EventReader<MobDamageDealtEvent>,
+) {
+    for ev in ev_damage_dealt.read() {
+        if let Ok(mut health) = vampire_query.get_mut(ev.mob) {
+            health.heal(ev.damage * 2);
+        }
+    }
+}
+
+//гарантированный предмет и осколки после смерти
+fn elite_death(
+    mut ev_elite_death: EventReader<EliteDeathEvent>,
+    mut ev_mob_spawn: EventWriter<MobSpawnEvent>,
+    mut ev_item: EventWriter<SpawnItemEvent>,
+    item_database: Res<Assets<ItemDatabase>>,
+    handle: Res<ItemDatabaseHandle>,
+) {
+    for ev in ev_elite_death.read() {
+        let item: ItemType = rand::random();
+
+        let item_name: String = item_database.get(handle.0.id()).unwrap().items[item as usize]["name"].as_str().unwrap().to_string();
+        let texture_name: String = item_database.get(handle.0.id()).unwrap().items[item as usize]["texture_name"].as_str().unwrap().to_string();
+        let item_description: String = item_database.get(handle.0.id()).unwrap().items[item as usize]["description"].as_str().unwrap().to_string();
+
+        let texture_path = format!("textures/items/{}", texture_name);
+
+        ev_item.send(SpawnItemEvent {
+            pos: ev.pos.with_z(1.),
+            item_type: item,
+            texture_path,
+            item_name,
+            item_description,
+        });
+
+        if !ev.modifiers.contains(&EliteModifier::Splitting) {
+            continue;
+        }
+
+        for offset in [Vec2::new(-12., 0.), Vec2::new(12., 0.)] {
+            ev_mob_spawn.send(MobSpawnEvent {
+                mob_type: ev.mob_type.clone(),
+                pos: ev.pos.truncate() + offset,
+                is_friendly: false,
+                owner: None,
+                loot: None,
+                exp_amount: 0,
+                elite: EliteRoll::SplitCopy,
+            });
+        }
+    }
+}
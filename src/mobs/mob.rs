@@ -1,8 +1,10 @@
 use std::time::Duration;
 
 use super::{
-    BossAttackSystem, BusyOrbital, ItemPicked, OnDeathEffect, OnHitEffect, PickupItem,
-    PickupItemQueue,
+    mark_bone_piles, reassemble_bone_piles, spawn_shield_sprite, sync_shield_sprite,
+    turn_shield, AttackPattern, ProjectileBurst, RushCharge, SPEAR_SEGMENT, BossAttackSystem, BossLoot, BossScript, BusyOrbital, Elite,
+    EliteDeathEvent, ItemPicked, OnDeathEffect, OnHitEffect, PickupItem, PickupItemQueue,
+    ShieldBlock,
 };
 
 use bevy_common_assets::json::JsonAssetPlugin;
@@ -23,6 +25,7 @@ pub const STATIC_MOBS: &[MobType] = &[
     MobType::FireMage,
     MobType::WaterMage,
     MobType::EarthElemental,
+    MobType::SkeletMage,
 ];
 
 use crate::{
@@ -36,18 +39,23 @@ use crate::{
 };
 use crate::{
     blank_spell::SpawnBlankEvent,
+    damage_zone::Blinded,
     elements::{ElementResistance, ElementType},
     exp_orb::SpawnExpOrbEvent,
     experience::PlayerExperience,
     friend::Friend,
     gamemap::Map,
+    hazards::Soaked,
     health::{Health, Hit},
     level_completion::{PortalEvent, PortalManager},
     mobs::timer_tick_orbital,
     obstacles::CorpseSpawnEvent,
     particles::SpawnParticlesEvent,
     player::Player,
-    projectile::{Friendly, Hostile, Projectile, SpawnProjectileEvent},
+    projectile::{
+        release_projectile, Friendly, Hostile, Projectile, ProjectileImpactEvent,
+        SpawnProjectileEvent,
+    },
     stun::Stun,
     GameLayer, GameState,
 };
@@ -62,6 +70,7 @@ impl Plugin for MobPlugin {
         app.add_plugins(JsonAssetPlugin::<MobDatabase>::new(&["json"]))
             .add_systems(Startup, load_mob_database)
             .add_event::<MobDeathEvent>()
+            .add_event::<MobDamageDealtEvent>()
             .add_event::<PushItemQueryEvent>()
             .add_event::<OnDeathEffectEvent>()
             .add_event::<OnHitEffectEvent>()
@@ -94,6 +103,17 @@ impl Plugin for MobPlugin {
                     set_state_thief,
                 )
                     .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                Update,
+                (
+                    turn_shield,
+                    spawn_shield_sprite,
+                    sync_shield_sprite,
+                    mark_bone_piles,
+                    reassemble_bone_piles,
+                )
+                    .run_if(in_state(GameState::InGame)),
             );
     }
 }
@@ -119,6 +139,14 @@ pub struct MobDeathEvent {
     pub pos: Vec3,
     pub dir: Vec3,
     pub is_spawned: bool,
+    pub boss_loot: Option<BossLoot>, //только у боссов, босс к моменту события уже деспавнен
+}
+
+///event for damage dealt by mob itself (melee attack or contact), for on-hit effects of the attacker
+#[derive(Event)]
+pub struct MobDamageDealtEvent {
+    pub mob: Entity,
+    pub damage: i32,
 }
 
 #[allow(dead_code)]
@@ -156,32 +184,35 @@ pub enum MobType {
     ClayGolem,      //walking tank(like fat, not shooting, attacks around)
     WaterElemental, //walking range mob
     FireElemental,  //like ghost(melee mob with phasing) // done
-    SkeletWarrior,  //mechanics
-    SkeletMage,     //mechanics
-    SkeletRanger,   // add arrow texture
+    SkeletWarrior,  //melee with a shield, blocks hits from the front
+    SkeletMage,     //teleports and casts homing bone bolts
+    SkeletRanger,   //shoots fast arrows from afar
     EarthElemental, //turret i guess? //done
     AirElemental,   // just an orbital?
     Thief,
+    SandGolem,       //boss of desert, burrows and emerges
+    PlantQueen,      //boss of jungle, spawns turrets and vines
+    NecromancerLord, //boss of inferno, raises corpses en masse
 }
 
-//projectile types
-#[allow(dead_code)]
+//projectile types, default patterns are in attack_pattern.rs
 #[derive(Component, Clone)]
 pub enum ProjectileType {
     // can use to create mobs with different types of projectiles
-    Circle,  // spawn some projectiles around
-    Missile, // like fireball
-    Gatling, // a lot of small ones
+    Circle,  // ring of projectiles around
+    Missile, // single slow fireball
+    Gatling, // fast stream of small ones
+    BoneBolt, // slow homing bone
+    Arrow,    // fast and straight
 }
 
 #[derive(Clone)]
-#[allow(dead_code)]
 pub enum AttackType {
     Slash,
-    Rush,
-    Spear,
+    Rush,  // charge to the target with slash
+    Spear, // long thrust, pattern.count - length
     Range,
-    Circle,
+    Circle, // slashes around, pattern.count - amount
 }
 
 //Pure components=========================================================================================================================================
@@ -247,6 +278,8 @@ pub struct SearchAndPursue {
     pub pursue_radius: f32,
     pub last_target_dir: Vec2,
     pub rays: Vec<Ray>,
+    pub squad_dir: Vec2, //поправка от группового поведения, см. squad.rs
+    pub last_known: Option<Vec2>, //где последний раз видел или слышал цель
 }
 
 impl Default for SearchAndPursue {
@@ -267,6 +300,8 @@ impl Default for SearchAndPursue {
             pursue_radius: 256.0,
             last_target_dir: Vec2::ZERO,
             rays,
+            squad_dir: Vec2::ZERO,
+            last_known: None,
         }
     }
 }
@@ -288,6 +323,8 @@ impl SearchAndPursue {
             pursue_radius: 512.0,
             last_target_dir: Vec2::ZERO,
             rays,
+            squad_dir: Vec2::ZERO,
+            last_known: None,
         }
     }
 }
@@ -373,6 +410,20 @@ pub struct IdleStatic;
 #[derive(Component, Clone)]
 pub struct Pursue;
 
+/// Mob lost the target from sight and goes to the last known position (for state machines)
+#[derive(Component, Clone)]
+pub struct Searching {
+    pub timer: Timer,
+}
+
+impl Default for Searching {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(4., TimerMode::Once),
+        }
+    }
+}
+
 /// This Flag is for mob attack animation
 #[derive(Component, Clone)]
 pub struct Attack {
@@ -397,6 +448,7 @@ pub struct AttackComponent {
     pub damage: i32,
     pub element: Option<ElementType>,
     pub proj_type: Option<ProjectileType>,
+    pub pattern: Option<AttackPattern>, //None - паттерн по умолчанию для proj_type
 }
 
 impl Default for AttackComponent {
@@ -410,6 +462,7 @@ impl Default for AttackComponent {
             damage: 1,
             element: None,
             proj_type: None,
+            pattern: None,
         }
     }
 }
@@ -500,7 +553,7 @@ fn mob_attack<Who: Component + std::default::Default>(
     mut commands: Commands,
     //    spatial_query: SpatialQuery,
     mob_query: Query<
-        (Entity, &mut LinearVelocity, &Transform, &AttackComponent),
+        (Entity, &mut LinearVelocity, &Transform, &AttackComponent, Has<Blinded>),
         (Changed<AttackFlag>, With<Who>),
     >,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
@@ -508,7 +561,7 @@ fn mob_attack<Who: Component + std::default::Default>(
     transform_query: Query<&Transform>,
     mut ev_shoot: EventWriter<SpawnProjectileEvent>,
 ) {
-    for (entity, _a, mob_transform, range) in mob_query.iter() {
+    for (entity, _a, mob_transform, range, blinded) in mob_query.iter() {
         let dir;
         match range.target {
             None => continue,
@@ -524,6 +577,13 @@ fn mob_attack<Who: Component + std::default::Default>(
             }
         };
 
+        //ослепленный песчаной бурей бьет куда попало
+        let dir = if blinded {
+            Vec2::from_angle(rand::thread_rng().gen_range(-PI / 2.0..PI / 2.0)).rotate(dir)
+        } else {
+            dir
+        };
+
         let hit_id: u16 = rand::thread_rng().gen::<u16>();
 
         let animation_config = AnimationConfig::new(0, 4, 24);
@@ -543,6 +603,9 @@ fn mob_attack<Who: Component + std::default::Default>(
         transform_attack.rotation = Quat::from_rotation_z(dir.normalize_or_zero().to_angle());
         let mut multiple_to_spawn = false;
         let mut amount_to_spawn = 1;
+        let mut spear_length = 1;
+
+        let pattern = range.pattern();
 
         match range.attack_type {
             AttackType::Slash => {
@@ -558,6 +621,8 @@ fn mob_attack<Who: Component + std::default::Default>(
                 if friendly {
                     texture_path = "textures/pierce.png";
                 }
+
+                spear_length = pattern.count.max(1);
             }
             AttackType::Circle => {
                 texture_path = "textures/slash_horisontal_enemy.png";
@@ -567,7 +632,7 @@ fn mob_attack<Who: Component + std::default::Default>(
                 } // change
 
                 multiple_to_spawn = true;
-                amount_to_spawn = 16;
+                amount_to_spawn = if pattern.count > 1 { pattern.count } else { 16 };
             }
             AttackType::Rush => {
                 texture_path = "textures/slash_horisontal_enemy.png";
@@ -575,7 +640,13 @@ fn mob_attack<Who: Component + std::default::Default>(
                 if friendly {
                     texture_path = "textures/slash_horisontal.png";
                 }
-            } // todo: change to choose from mob type?
+
+                // удар висит на мобе и летит вместе с ним
+                commands.entity(entity).insert(RushCharge::new(
+                    dir.normalize_or_zero(),
+                    pattern.speed.unwrap_or(300.),
+                ));
+            }
 
             AttackType::Range => {
                 commands.entity(entity).insert(Done::Success);
@@ -583,8 +654,8 @@ fn mob_attack<Who: Component + std::default::Default>(
                 let angle = dir.y.atan2(dir.x); //math
                 let texture_path: String;
                 let damage: u32;
+                let mut speed = 150.;
                 match range.proj_type {
-                    //todo: change this fragment, that we could spawn small and circle projs, maybe change event?
                     Some(ProjectileType::Circle) => {
                         texture_path = "textures/earthquake.png".to_string();
                         damage = 20;
@@ -597,29 +668,69 @@ fn mob_attack<Who: Component + std::default::Default>(
                         texture_path = "textures/small_fire.png".to_string();
                         damage = 10;
                     }
+                    Some(ProjectileType::BoneBolt) => {
+                        texture_path = "textures/rib.png".to_string();
+                        damage = 15;
+                        speed = 100.;
+                    }
+                    Some(ProjectileType::Arrow) => {
+                        texture_path = "textures/arrow.png".to_string();
+                        damage = 20;
+                        speed = 260.;
+                    }
                     None => continue,
                 };
 
-                let color = range
+                let speed = pattern.speed.unwrap_or(speed);
+                let trajectory = pattern.trajectory;
+                let element = range
                     .element
-                    .expect("Range attack without element, refactor this code.")
-                    .color();
-
-                ev_shoot.send(SpawnProjectileEvent {
-                    texture_path,
-                    color, //todo: change this fragment, that we could spawn different types of projectiles.
-                    translation: mob_transform.translation,
-                    angle,
-                    collider_radius: 8.0,
-                    speed: 150.,
-                    damage,
-                    element: range
-                        .element
-                        .expect("Range attack without element, refactor this code."),
-                    is_friendly: friendly,
-                    trajectory: crate::projectile::Trajectory::Straight,
-                    can_go_through_walls: false,
-                });
+                    .expect("Range attack without element, refactor this code.");
+
+                // очередь: первый снаряд сразу, остальные по таймеру
+                let volley = if pattern.interval > 0. && pattern.count > 1 {
+                    commands.entity(entity).insert(ProjectileBurst {
+                        remaining: pattern.count - 1,
+                        timer: Timer::from_seconds(pattern.interval, TimerMode::Repeating),
+                        target: range.target,
+                        angle,
+                        spread: pattern.spread,
+                        texture_path: texture_path.clone(),
+                        color: element.color(),
+                        speed,
+                        damage,
+                        element,
+                        trajectory,
+                        modifiers: pattern.modifiers.clone(),
+                        is_friendly: friendly,
+                    });
+                    1
+                } else {
+                    pattern.count.max(1)
+                };
+
+                for i in 0..volley {
+                    let offset = if volley == 1 && pattern.spread > 0. && !pattern.is_ring() {
+                        rand::thread_rng().gen_range(-pattern.spread / 2.0..pattern.spread / 2.0)
+                    } else {
+                        pattern.offset(i)
+                    };
+
+                    ev_shoot.send(SpawnProjectileEvent {
+                        texture_path: texture_path.clone(),
+                        color: element.color(),
+                        translation: mob_transform.translation,
+                        angle: angle + offset,
+                        collider_radius: 8.0,
+                        speed,
+                        damage,
+                        element,
+                        is_friendly: friendly,
+                        trajectory,
+                        can_go_through_walls: false,
+                        modifiers: pattern.modifiers.clone(),
+                    });
+                }
 
                 continue;
             }
@@ -666,29 +777,36 @@ fn mob_attack<Who: Component + std::default::Default>(
             }
             continue;
         }
-        commands.entity(entity).with_children(|parent| {
-            parent
-                .spawn(SpriteBundle {
-                    texture,
-                    transform: transform_attack,
-                    ..default()
-                })
-                .insert(animation_config)
-                .insert(Attack {
-                    damage: range.damage,
-                    element: range.element,
-                    dir: dir,
-                    hit_id: hit_id,
-                })
-                .insert(TextureAtlas {
-                    layout: texture_atlas_layout.clone(),
-                    index: 0,
-                })
-                .insert(Collider::rectangle(16., 16.))
-                .insert(Sensor)
-                .insert(LockedAxes::ROTATION_LOCKED)
-                .insert(Who::default());
-        });
+        // копье колет несколькими частями подряд, у остальных атак часть одна
+        for i in 0..spear_length {
+            let mut transform_segment = transform_attack;
+            transform_segment.translation +=
+                (dir.normalize_or_zero() * SPEAR_SEGMENT * i as f32).extend(0.);
+
+            commands.entity(entity).with_children(|parent| {
+                parent
+                    .spawn(SpriteBundle {
+                        texture: texture.clone(),
+                        transform: transform_segment,
+                        ..default()
+                    })
+                    .insert(animation_config.clone())
+                    .insert(Attack {
+                        damage: range.damage,
+                        element: range.element,
+                        dir: dir,
+                        hit_id: hit_id,
+                    })
+                    .insert(TextureAtlas {
+                        layout: texture_atlas_layout.clone(),
+                        index: 0,
+                    })
+                    .insert(Collider::rectangle(16., 16.))
+                    .insert(Sensor)
+                    .insert(LockedAxes::ROTATION_LOCKED)
+                    .insert(Who::default());
+            });
+        }
     }
 }
 
@@ -707,7 +825,7 @@ fn tick_attack_cooldown(time: Res<Time>, mut mob_query: Query<&mut AttackCompone
 
 //система для нанесения урона от мили атак
 fn attack_hit<Who: Component, Target: Component>(
-    mut attack_query: Query<(Entity, &mut Attack), (With<Who>, Without<Target>)>,
+    mut attack_query: Query<(Entity, &mut Attack, Option<&Parent>), (With<Who>, Without<Target>)>,
     mut target_query: Query<
         (
             &CollidingEntities,
@@ -717,10 +835,11 @@ fn attack_hit<Who: Component, Target: Component>(
         ),
         (Without<Who>, With<Target>, Without<Invincibility>),
     >,
+    mut ev_damage_dealt: EventWriter<MobDamageDealtEvent>,
 ) {
     for (entities, mut hp, el_res, mut hit_list) in target_query.iter_mut() {
         //maybe apply el_res?
-        for (attack_e, attack) in attack_query.iter_mut() {
+        for (attack_e, attack, attacker) in attack_query.iter_mut() {
             if entities.contains(&attack_e) && !hit_list.id_list.contains(&attack.hit_id) {
                 let mut damage = attack.damage;
                 el_res.calculate_for(&mut damage, attack.element);
@@ -734,6 +853,14 @@ fn attack_hit<Who: Component, Target: Component>(
                     element: attack.element,
                     direction: Vec3::new(attack.dir.x, attack.dir.y, 1.0),
                 });
+
+                //атака - дочерняя entity моба
+                if let Some(attacker) = attacker {
+                    ev_damage_dealt.send(MobDamageDealtEvent {
+                        mob: attacker.get(),
+                        damage,
+                    });
+                }
             }
         }
     }
@@ -742,21 +869,51 @@ fn attack_hit<Who: Component, Target: Component>(
 //система для нанесения урона проджектайлами
 fn hit_projectiles<Filter: Component, FilterTrue: Component, Side: Component>(
     mut commands: Commands,
-    projectile_query: Query<(Entity, &Projectile, &Transform), With<Side>>,
+    mut projectile_query: Query<(&mut Projectile, &Transform), With<Side>>,
     mut mob_query: Query<
         (
+            Entity,
             &CollidingEntities,
             &mut Health,
             &Transform,
             &ElementResistance,
+            Option<&ShieldBlock>,
         ),
         (With<Mob>, Without<Filter>, With<FilterTrue>),
     >,
     mut ev_spawn_particles: EventWriter<SpawnParticlesEvent>,
+    mut ev_impact: EventWriter<ProjectileImpactEvent>,
 ) {
-    for (colliding_e, mut health, mob_transform, resistance) in mob_query.iter_mut() {
-        for (proj_e, projectile, projectile_transform) in projectile_query.iter() {
-            if colliding_e.contains(&proj_e) {
+    for (mob_e, colliding_e, mut health, mob_transform, resistance, shield) in mob_query.iter_mut() {
+        //только то, с чем моб соприкасается, а не все снаряды на уровне
+        for proj_e in colliding_e.iter().copied() {
+            let Ok((mut projectile, projectile_transform)) = projectile_query.get_mut(proj_e)
+            else {
+                continue;
+            };
+
+            if projectile.can_hit(mob_e) {
+                // щит скелета гасит снаряды спереди
+                let from = (projectile_transform.translation - mob_transform.translation).truncate();
+                if shield.is_some_and(|shield| shield.blocks(from.normalize_or_zero())) {
+                    projectile.spent = true;
+                    commands.entity(proj_e).add(release_projectile);
+
+                    ev_spawn_particles.send(SpawnParticlesEvent {
+                        pattern: crate::particles::ParticlePattern::Burst {
+                            direction: from.normalize_or_zero(),
+                            distance: rand::thread_rng().gen_range(8.0..12.0),
+                            spread: PI / 2.,
+                        },
+                        position: projectile_transform.translation,
+                        amount: 5,
+                        color: Color::srgb(1.5, 1.5, 1.2),
+                        speed: 15.,
+                        rotate: false,
+                    });
+                    continue;
+                }
+
                 // считаем урон с учётом сопротивления к элементам
                 let mut damage = projectile.damage as i32;
                 resistance.calculate_for(&mut damage, Some(projectile.element));
@@ -772,8 +929,12 @@ fn hit_projectiles<Filter: Component, FilterTrue: Component, Side: Component>(
                     direction: shot_dir,
                 });
 
-                // деспавним снаряд
-                commands.entity(proj_e).despawn();
+                // что станет со снарядом, решают его модификаторы
+                projectile.register_hit(mob_e);
+                ev_impact.send(ProjectileImpactEvent {
+                    projectile: proj_e,
+                    target: Some(mob_e),
+                });
 
                 // спавним партиклы
                 ev_spawn_particles.send(SpawnParticlesEvent {
@@ -815,16 +976,19 @@ pub fn mob_type_to_tag_convert(mob_type: MobType) -> String {
         MobType::WaterMage => "water_mage.png",
         MobType::JungleTurret => "plant.png",
         MobType::Necromancer => "necromancer.png",
-        MobType::Koldun => "",
+        MobType::Koldun => "koldun.png",
         MobType::ClayGolem => "golem.png",
         MobType::WaterElemental => "water_elemental.png",
         MobType::FireElemental => "fire_elemental.png",
-        MobType::SkeletWarrior => "",
-        MobType::SkeletMage => "",
-        MobType::SkeletRanger => "",
+        MobType::SkeletWarrior => "skelet_warrior.png",
+        MobType::SkeletMage => "skelet_mage.png",
+        MobType::SkeletRanger => "skelet_ranger.png",
         MobType::EarthElemental => "earth_elemental.png",
         MobType::AirElemental => "air_elemental.png",
         MobType::Thief => "lurker.png",
+        MobType::SandGolem => "sand_golem.png",
+        MobType::PlantQueen => "plant_queen.png",
+        MobType::NecromancerLord => "necromancer_lord.png",
     }
     .to_string()
 }
@@ -843,6 +1007,7 @@ pub fn damage_mobs(
             &Transform,
             &MobLoot,
             &MobType,
+            Option<&Elite>,
         ),
         (With<Mob>, Without<Friend>),
     >,
@@ -855,13 +1020,17 @@ pub fn damage_mobs(
     mut on_hit_event: EventWriter<OnHitEffectEvent>,
     mut on_death_event: EventWriter<OnDeathEffectEvent>,
 
-    boss_query: Query<&BossAttackSystem>,
+    boss_query: Query<Option<&BossScript>, With<BossAttackSystem>>,
 
     mut global_transform_query: Query<&mut GlobalTransform, With<BusyOrbital>>,
 
     mut thief_query: Query<&mut PickupItemQueue>,
+
+    soaked_query: Query<&Soaked>,
+
+    mut ev_elite_death: EventWriter<EliteDeathEvent>,
 ) {
-    for (entity, mut health, _mob, transform, loot, mob_type) in mob_query.iter_mut() {
+    for (entity, mut health, _mob, transform, loot, mob_type, elite) in mob_query.iter_mut() {
         let mut translation = transform.translation;
 
         if *mob_type == MobType::AirElemental && global_transform_query.contains(entity) {
@@ -874,9 +1043,14 @@ pub fn damage_mobs(
         if !health.hit_queue.is_empty() {
             let hit = health.hit_queue.remove(0);
 
+            let mut damage = hit.damage;
+            if let Ok(soaked) = soaked_query.get(entity) {
+                soaked.calculate_for(&mut damage, hit.element);
+            }
+
             // наносим урон
-            health.damage(hit.damage);
-            ev_play_audio.send(PlayAudioEvent::from_file("mob_hit.ogg")); 
+            health.damage(damage);
+            ev_play_audio.send(PlayAudioEvent::from_file("mob_hit.ogg").at(translation.truncate(), 128.));
             
             if on_hit_query.contains(entity) {
                 let mut vec_objects = vec![];
@@ -953,8 +1127,22 @@ pub fn damage_mobs(
                     pos: translation,
                     dir: hit.direction,
                     is_spawned: false,
+                    boss_loot: boss_query
+                        .get(entity)
+                        .ok()
+                        .flatten()
+                        .and_then(|script| script.definition.as_ref())
+                        .map(|definition| definition.loot.clone()),
                 });
 
+                if let Some(elite) = elite {
+                    ev_elite_death.send(EliteDeathEvent {
+                        pos: translation,
+                        mob_type: mob_type.clone(),
+                        modifiers: elite.modifiers.clone(),
+                    });
+                }
+
                 // спавним труп на месте смерти моба
                 ev_corpse.send(CorpseSpawnEvent {
                     mob_type: mob_type.clone(),
@@ -1141,6 +1329,7 @@ pub fn on_death_effects_handler(
                         is_friendly: ev.is_friendly,
                         trajectory: crate::projectile::Trajectory::Straight,
                         can_go_through_walls: false,
+                        modifiers: vec![],
                     });
                 }
             }
@@ -4,7 +4,7 @@ use core::f32;
 use rand::Rng;
 use seldom_state::prelude::*;
 
-use crate::mobs::air_elemental_movement;
+use crate::mobs::{air_elemental_movement, line_of_sight, RushCharge, SquadBehaviour, Stealth};
 use crate::{
     alert::SpawnAlertEvent,
     blank_spell::Blank,
@@ -48,6 +48,8 @@ impl Plugin for MobMovementPlugin {
                 idle::<Friend, Enemy>,
                 pursue::<Friend, Enemy>,
                 idle_static::<Friend, Enemy>,
+                search_last_known::<Enemy, Friend>,
+                search_last_known::<Friend, Enemy>,
                 update_weights,
             ),
         );
@@ -79,7 +81,7 @@ fn teleport_mobs(
 //система для мобов, которые убегают от таргета
 fn runaway_mob(
     mut mob_query: Query<
-        (&mut LinearVelocity, &Transform, &mut Pathfinder),
+        (&mut LinearVelocity, &Transform, &mut Pathfinder, Option<&SquadBehaviour>),
         (
             Without<Stun>,
             Without<Teleport>,
@@ -88,16 +90,33 @@ fn runaway_mob(
         ),
     >,
     mut player_query: Query<&Transform, (With<Player>, Without<Mob>)>,
+    shield_query: Query<&Transform, (With<Enemy>, With<SearchAndPursue>, Without<RunawayRush>)>,
     time: Res<Time>,
 ) {
     if let Ok(player) = player_query.get_single_mut() {
         let player_pos = player.translation.truncate();
-        for (mut linvel, transform, pathfinder) in mob_query.iter_mut() {
-            let direction = Vec2::new(
-                transform.translation.x - player_pos.x,
-                transform.translation.y - player_pos.y,
-            )
-            .normalize();
+        for (mut linvel, transform, pathfinder, squad) in mob_query.iter_mut() {
+            let pos = transform.translation.truncate();
+            let mut direction = (pos - player_pos).normalize();
+
+            // прячемся за ближайшего союзника, чтобы он оказался между нами и игроком
+            if squad.is_some_and(|squad| squad.hide_behind_allies) {
+                let cover = shield_query
+                    .iter()
+                    .map(|t| t.translation.truncate())
+                    .filter(|ally| ally.distance(pos) < 256.)
+                    .min_by(|a, b| a.distance_squared(pos).total_cmp(&b.distance_squared(pos)));
+
+                if let Some(ally) = cover {
+                    let hide_spot = ally + (ally - player_pos).normalize_or_zero() * 32.;
+                    if hide_spot.distance(pos) > 8. {
+                        direction = (hide_spot - pos).normalize_or_zero();
+                    } else {
+                        direction = Vec2::ZERO;
+                    }
+                }
+            }
+
             linvel.0 = direction * pathfinder.speed * time.delta_seconds();
         }
     }
@@ -167,6 +186,7 @@ fn phasing_mob<Side: Component, Target: Component>(
                     .truncate()
                     .with_y(transform.translation.y + 16.),
                 attack_alert: true,
+                spotted_by: None,
             });
         }
     }
@@ -274,6 +294,7 @@ fn idle_static<Who: Component, Target: Component>(
                     .truncate()
                     .with_y(mob_transform.translation.y + 16.),
                 attack_alert: true,
+                spotted_by: None,
             });
         }
     }
@@ -285,11 +306,10 @@ fn idle<Who: Component, Target: Component>(
     spatial_query: SpatialQuery,
     mut mob_query: Query<
         (Entity, &Transform, &mut SearchAndPursue),
-        (With<Idle>, With<Who>, Without<Target>),
+        (With<Idle>, With<Who>, Without<Target>, Without<RushCharge>),
     >,
-    target_query: Query<(Entity, &Transform), With<Target>>,
+    target_query: Query<(Entity, &Transform, Option<&Stealth>), With<Target>>,
     mut ev_spawn_alert: EventWriter<SpawnAlertEvent>,
-    ignore_query: Query<Entity, Or<(With<Corpse>, With<Shield>, With<Blank>, With<Who>)>>,
     time: Res<Time>,
 ) {
     for (mob_e, mob_transform, mut mob) in mob_query.iter_mut() {
@@ -320,7 +340,7 @@ fn idle<Who: Component, Target: Component>(
             return;
         }
 
-        let sorted_targets: Vec<(Entity, &Transform)> = target_query
+        let sorted_targets: Vec<(Entity, &Transform, Option<&Stealth>)> = target_query
             .iter()
             .sort_by::<&Transform>(|item1, item2| {
                 item1
@@ -330,32 +350,17 @@ fn idle<Who: Component, Target: Component>(
             })
             .collect();
 
-        let (target_e, target_transform) = sorted_targets[0];
-
-        let dir = (target_transform.translation - mob_transform.translation)
-            .truncate()
-            .normalize();
+        let (_, target_transform, stealth) = sorted_targets[0];
+        let target_pos = target_transform.translation.truncate();
 
-        let Some(first_hit) = spatial_query.cast_ray_predicate(
-            mob_transform.translation.truncate(),
-            Dir2::new_unchecked(dir),
-            512.,
-            true,
-            SpatialQueryFilter::default().with_excluded_entities(&ignore_query),
-            &|entity| entity != mob_e,
-        ) else {
-            commands.entity(mob_e).insert(Done::Success);
-            continue;
-        };
-
-        if target_transform
-            .translation
-            .distance(mob_transform.translation)
-            <= mob.pursue_radius
+        //скрытную цель замечают с меньшего расстояния
+        if target_pos.distance(mob_transform.translation.truncate())
+            <= Stealth::detection_radius(stealth, mob.pursue_radius)
         {
-            if first_hit.entity == target_e {
+            if line_of_sight(&spatial_query, mob_transform.translation.truncate(), target_pos) {
                 commands.entity(mob_e).insert(Done::Success);
                 mob.search_time.reset();
+                mob.last_known = Some(target_pos);
 
                 ev_spawn_alert.send(SpawnAlertEvent {
                     position: mob_transform
@@ -363,6 +368,7 @@ fn idle<Who: Component, Target: Component>(
                         .truncate()
                         .with_y(mob_transform.translation.y + 16.),
                     attack_alert: false,
+                    spotted_by: Some(mob_e),
                 });
             }else{
                 commands.entity(mob_e).insert(Done::Failure);
@@ -383,10 +389,9 @@ fn pursue<Who: Component, Target: Component>(
             &mut SearchAndPursue,
             &mut AttackComponent,
         ),
-        (With<Pursue>, With<Who>, Without<Target>),
+        (With<Pursue>, With<Who>, Without<Target>, Without<RushCharge>),
     >,
     target_query: Query<(Entity, &Transform), With<Target>>,
-    ignore_query: Query<Entity, Or<(With<Corpse>, With<Shield>, With<Blank>, With<Enemy>)>>,
     mut ev_spawn_alert: EventWriter<SpawnAlertEvent>,
     time: Res<Time>,
 ) {
@@ -413,23 +418,19 @@ fn pursue<Who: Component, Target: Component>(
 
         let ray_sum_dir: Vec2 = mob.rays.iter().map(|ray| ray.direction * ray.weight).sum();
 
-        let Some(first_hit) = spatial_query.cast_ray_predicate(
-            mob_transform.translation.truncate(),
-            Dir2::new_unchecked(direction),
-            512.,
-            true,
-            SpatialQueryFilter::default().with_excluded_entities(&ignore_query),
-            &|entity| entity != mob_e,
-        ) else {
+        //цель пропала из виду - идем туда, где ее видели последний раз
+        let target_pos = target_transform.translation.truncate();
+        if !line_of_sight(&spatial_query, mob_transform.translation.truncate(), target_pos) {
             commands.entity(mob_e).insert(Done::Failure);
+            linvel.0 = Vec2::ZERO;
+            mob.last_target_dir = Vec2::ZERO;
             continue;
-        };
-
-        if first_hit.entity == target_e {
-            mob.last_target_dir = direction;
         }
-            
-        linvel.0 = (mob.last_target_dir + ray_sum_dir) * mob.speed * time.delta_seconds();
+
+        mob.last_target_dir = direction;
+        mob.last_known = Some(target_pos);
+
+        linvel.0 = (mob.last_target_dir + ray_sum_dir + mob.squad_dir) * mob.speed * time.delta_seconds();
 
         mob.search_time.tick(time.delta());
 
@@ -462,11 +463,80 @@ fn pursue<Who: Component, Target: Component>(
                     .truncate()
                     .with_y(mob_transform.translation.y + 16.),
                 attack_alert: true,
+                spotted_by: None,
             });
         }
     }
 }
 
+//цель скрылась - моб идет к месту, где видел ее последний раз, и ждет там, пока не кончится таймер
+fn search_last_known<Who: Component, Target: Component>(
+    mut commands: Commands,
+    spatial_query: SpatialQuery,
+    mut mob_query: Query<
+        (
+            Entity,
+            &mut LinearVelocity,
+            &Transform,
+            &mut SearchAndPursue,
+            &mut Searching,
+        ),
+        (With<Who>, Without<Target>, Without<RushCharge>),
+    >,
+    target_query: Query<(&Transform, Option<&Stealth>), With<Target>>,
+    mut ev_spawn_alert: EventWriter<SpawnAlertEvent>,
+    time: Res<Time>,
+) {
+    for (mob_e, mut linvel, mob_transform, mut mob, mut searching) in mob_query.iter_mut() {
+        searching.timer.tick(time.delta());
+
+        let pos = mob_transform.translation.truncate();
+
+        //снова увидели цель - продолжаем преследование
+        let spotted = target_query.iter().any(|(target_transform, stealth)| {
+            let target_pos = target_transform.translation.truncate();
+            target_pos.distance(pos) <= Stealth::detection_radius(stealth, mob.pursue_radius)
+                && line_of_sight(&spatial_query, pos, target_pos)
+        });
+
+        if spotted {
+            commands.entity(mob_e).insert(Done::Success);
+            mob.search_time.reset();
+
+            ev_spawn_alert.send(SpawnAlertEvent {
+                position: pos.with_y(mob_transform.translation.y + 16.),
+                attack_alert: false,
+                spotted_by: Some(mob_e),
+            });
+            continue;
+        }
+
+        let Some(last_known) = mob.last_known else {
+            commands.entity(mob_e).insert(Done::Failure);
+            linvel.0 = Vec2::ZERO;
+            continue;
+        };
+
+        if searching.timer.finished() {
+            commands.entity(mob_e).insert(Done::Failure);
+            linvel.0 = Vec2::ZERO;
+            mob.last_known = None;
+            continue;
+        }
+
+        //дошли - стоим и осматриваемся
+        if pos.distance(last_known) < 12. {
+            linvel.0 = Vec2::ZERO;
+            continue;
+        }
+
+        let ray_sum_dir: Vec2 = mob.rays.iter().map(|ray| ray.direction * ray.weight).sum();
+        let direction = (last_known - pos).normalize_or_zero();
+
+        linvel.0 = (direction + ray_sum_dir) * mob.speed * time.delta_seconds();
+    }
+}
+
 //система для обновления весов юнитов, которые двигаются на рейкастинге
 fn update_weights(
     spatial_query: SpatialQuery,
@@ -15,14 +15,14 @@ use crate::{
     level_completion::PortalManager,
     mobs::{mob::*, mob_types::*, MultistateAnimationFlag, OnCooldownFlag},
     obstacles::Corpse,
-    pathfinding::{create_new_graph, FriendRush},
+    pathfinding::{create_new_graph, FriendRush, PathCostProfile},
     stun::Stun,
     GameState,
 };
 
 use super::{
-    pick_attack_to_perform_koldun, BeforeAttackDelayBoss, BossAttackFlagComp, BossAttackType,
-    PickAttackFlag,
+    pick_boss_attack, BeforeAttackDelayBoss, BossAttackFlagComp, BossAttackType, Elite,
+    EliteRoll, PickAttackFlag, SplitCopy,
 };
 
 pub struct MobSpawnPlugin;
@@ -71,6 +71,9 @@ const INFERNO_MOBS: &[MobType] = &[
     MobType::FireMage,
     MobType::Knight,
     MobType::FireElemental,
+    MobType::SkeletWarrior,
+    MobType::SkeletMage,
+    MobType::SkeletRanger,
 ];
 //maybe add some minibosses? const BOSSES: &[MobType] = &[MobType::Koldun];
 
@@ -83,6 +86,7 @@ pub struct MobSpawnEvent {
     pub owner: Option<Entity>,
     pub loot: Option<ItemPicked>,
     pub exp_amount: i8,
+    pub elite: EliteRoll,
 }
 pub enum MobAI {
     MeleeWithATK,
@@ -93,7 +97,7 @@ pub enum MobAI {
     Phasing,
     Orbital,
     Thief,
-    Koldun,
+    Boss,
     //etc, add later
 }
 //structures for init=======================================================================================================================
@@ -172,15 +176,15 @@ impl SpawnKit<'_> {
         Self {
             frame_count: 2,
             fps: 3,
-            texture_path: "textures/mobs/fire_mage1.png",
+            texture_path: "textures/mobs/skelet_mage.png",
+            ai_type: MobAI::RangeWithTP,
             ..default()
         }
     }
     fn skelet_warrior() -> Self {
         Self {
-            frame_count: 2,
-            fps: 3,
-            texture_path: "textures/mobs/fire_mage2.png",
+            frame_count: 4,
+            texture_path: "textures/mobs/skelet_warrior.png",
             ..default()
         }
     }
@@ -188,7 +192,8 @@ impl SpawnKit<'_> {
         Self {
             frame_count: 2,
             fps: 3,
-            texture_path: "textures/mobs/fire_mage3.png",
+            texture_path: "textures/mobs/skelet_ranger.png",
+            ai_type: MobAI::RangeMoving,
             ..default()
         }
     }
@@ -271,7 +276,40 @@ impl SpawnKit<'_> {
             can_flip: true,
             has_animation: true,
             pixel_size: 48,
-            ai_type: MobAI::Koldun,
+            ai_type: MobAI::Boss,
+            ..default()
+        }
+    }
+    fn sand_golem() -> Self {
+        Self {
+            frame_count: 4,
+            fps: 12,
+            texture_path: "textures/mobs/clay_golem.png",
+            pixel_size: 32,
+            can_flip: true,
+            ai_type: MobAI::Boss,
+            ..default()
+        }
+    }
+    fn plant_queen() -> Self {
+        Self {
+            texture_path: "textures/mobs/plant_body.png",
+            rotation_path: "textures/mobs/plant_head.png",
+            rotation_entity: true,
+            has_animation: false,
+            pixel_size: 24,
+            ai_type: MobAI::Boss,
+            ..default()
+        }
+    }
+    fn necromancer_lord() -> Self {
+        Self {
+            frame_count: 4,
+            fps: 12,
+            texture_path: "textures/mobs/necromancer.png",
+            pixel_size: 24,
+            can_flip: true,
+            ai_type: MobAI::Boss,
             ..default()
         }
     }
@@ -337,6 +375,7 @@ pub fn spawn_mob(
     mut ev_mob_spawn: EventReader<MobSpawnEvent>,
     mut portal_manager: ResMut<PortalManager>,
     mut summon_queue_ev: EventWriter<PushMobQueueEvent>,
+    chapter_manager: Res<ChapterManager>,
 ) {
     for ev in ev_mob_spawn.read() {
         if !ev.is_friendly {
@@ -397,6 +436,15 @@ pub fn spawn_mob(
             MobType::Thief => {
                 spawn_kit = SpawnKit::thief();
             }
+            MobType::SandGolem => {
+                spawn_kit = SpawnKit::sand_golem();
+            }
+            MobType::PlantQueen => {
+                spawn_kit = SpawnKit::plant_queen();
+            }
+            MobType::NecromancerLord => {
+                spawn_kit = SpawnKit::necromancer_lord();
+            }
         }
 
         //get texture and layout
@@ -417,13 +465,13 @@ pub fn spawn_mob(
         let mob: Entity;
 
         match spawn_kit.ai_type {
-            MobAI::Koldun => {
+            MobAI::Boss => {
                 mob = commands
                     .spawn((
                         StateMachine::default()
                             .trans::<OnCooldownFlag, _>(done(Some(Done::Success)), PickAttackFlag)
                             .trans_builder(
-                                pick_attack_to_perform_koldun,
+                                pick_boss_attack,
                                 |_: &PickAttackFlag, attack_type| {
                                     Some(match attack_type {
                                         Some(val) => match val {
@@ -484,7 +532,9 @@ pub fn spawn_mob(
                                 done(Some(Done::Success)),
                                 BeforeAttackDelay::default(),
                             )
-                            .trans::<Pursue, _>(done(Some(Done::Failure)), Idle)
+                            .trans::<Pursue, _>(done(Some(Done::Failure)), Searching::default())
+                            .trans::<Searching, _>(done(Some(Done::Success)), Pursue)
+                            .trans::<Searching, _>(done(Some(Done::Failure)), Idle)
                             .trans::<BeforeAttackDelay, _>(done(Some(Done::Success)), AttackFlag)
                             .trans::<AttackFlag, _>(done(Some(Done::Success)), Idle),
                         Idle,
@@ -504,7 +554,9 @@ pub fn spawn_mob(
                                     done(Some(Done::Success)),
                                     BeforeAttackDelay::default(),
                                 )
-                                .trans::<Pursue, _>(done(Some(Done::Failure)), Idle)
+                                .trans::<Pursue, _>(done(Some(Done::Failure)), Searching::default())
+                                .trans::<Searching, _>(done(Some(Done::Success)), Pursue)
+                                .trans::<Searching, _>(done(Some(Done::Failure)), Idle)
                                 .trans::<BeforeAttackDelay, _>(
                                     done(Some(Done::Success)),
                                     AttackFlag,
@@ -522,7 +574,9 @@ pub fn spawn_mob(
                                     done(Some(Done::Success)),
                                     BeforeAttackDelay::default(),
                                 )
-                                .trans::<Pursue, _>(done(Some(Done::Failure)), Idle)
+                                .trans::<Pursue, _>(done(Some(Done::Failure)), Searching::default())
+                                .trans::<Searching, _>(done(Some(Done::Success)), Pursue)
+                                .trans::<Searching, _>(done(Some(Done::Failure)), Idle)
                                 .trans::<BeforeAttackDelay, _>(
                                     done(Some(Done::Success)),
                                     AttackFlag,
@@ -655,6 +709,9 @@ pub fn spawn_mob(
         if spawn_kit.can_flip {
             commands.entity(mob).insert(FlipEntity);
         }
+        commands.entity(mob).insert(PathCostProfile::for_mob(&ev.mob_type));
+        commands.entity(mob).insert(SquadBehaviour::for_ai(&spawn_kit.ai_type));
+
         match ev.mob_type {
             MobType::Knight => {
                 commands
@@ -710,12 +767,58 @@ pub fn spawn_mob(
                     .insert(MeleeMobBundle::clay_golem())
                     .insert(SearchAndPursue::default());
             }
-            MobType::SkeletMage => {}
-            MobType::SkeletWarrior => {}
-            MobType::SkeletRanger => {}
+            MobType::SkeletMage => {
+                commands.entity(mob).insert(MageBundle::skelet_mage());
+            }
+            MobType::SkeletWarrior => {
+                commands
+                    .entity(mob)
+                    .insert(MeleeMobBundle::skelet_warrior())
+                    .insert(SearchAndPursue::default())
+                    .insert(ShieldBlock::default())
+                    .insert(AttackTelegraph { time: 0.5 })
+                    .insert(MultistateAnimationFlag);
+            }
+            MobType::SkeletRanger => {
+                commands
+                    .entity(mob)
+                    .insert(RangeMobBundle::skelet_ranger())
+                    .insert(AttackTelegraph { time: 0.8 });
+            }
             MobType::Thief => {
                 commands.entity(mob).insert(ThiefBundle::default());
             }
+            //боссы глав - те же спрайты, что у обычных мобов, но крупнее и другого цвета
+            MobType::SandGolem => {
+                commands
+                    .entity(mob)
+                    .insert(BossBundle::sand_golem())
+                    .insert(Sprite {
+                        color: Color::srgb(1.4, 1.2, 0.7),
+                        ..default()
+                    })
+                    .insert(Transform::from_xyz(x, y, 1.0).with_scale(Vec3::splat(1.75)));
+            }
+            MobType::PlantQueen => {
+                commands
+                    .entity(mob)
+                    .insert(BossBundle::plant_queen())
+                    .insert(Sprite {
+                        color: Color::srgb(1.3, 0.8, 1.3),
+                        ..default()
+                    })
+                    .insert(Transform::from_xyz(x, y, 1.0).with_scale(Vec3::splat(2.0)));
+            }
+            MobType::NecromancerLord => {
+                commands
+                    .entity(mob)
+                    .insert(BossBundle::necromancer_lord())
+                    .insert(Sprite {
+                        color: Color::srgb(1.5, 0.6, 0.6),
+                        ..default()
+                    })
+                    .insert(Transform::from_xyz(x, y, 1.0).with_scale(Vec3::splat(2.0)));
+            }
         }
 
         if spawn_kit.rotation_entity {
@@ -766,6 +869,17 @@ pub fn spawn_mob(
                 item_name: item,
             });
         }
+        match ev.elite {
+            EliteRoll::Roll => {
+                if let Some(elite) = Elite::roll(chapter_manager.get_current_chapter()) {
+                    commands.entity(mob).insert(elite);
+                }
+            }
+            EliteRoll::SplitCopy => {
+                commands.entity(mob).insert(SplitCopy);
+            }
+            EliteRoll::Never => {}
+        }
     }
 }
 
@@ -812,6 +926,7 @@ pub fn first_spawn_mobs(
                     owner: None, 
                     loot: None,  
                     exp_amount: -1,
+                    elite: EliteRoll::Roll,
                 });
             }
         }
@@ -846,6 +961,7 @@ fn spawner_mob_spawn(
                 owner: None,
                 loot: None,
                 exp_amount: 0,
+                elite: EliteRoll::Never,
             });
 
             commands.entity(raising.corpse_id).despawn();
@@ -865,13 +981,21 @@ fn handle_raising(
     }
 }
 
-//спавн босса
+//спавн босса главы, после третьей главы - колдун
 fn boss_spawn(
     mut ev_spawn: EventWriter<MobSpawnEvent>,
     mut game_state: ResMut<NextState<GameState>>,
+    chapter_manager: Res<ChapterManager>,
 ) {
+    let mob_type = match chapter_manager.get_current_chapter() {
+        1 => MobType::SandGolem,
+        2 => MobType::PlantQueen,
+        3 => MobType::NecromancerLord,
+        _ => MobType::Koldun,
+    };
+
     ev_spawn.send(MobSpawnEvent {
-        mob_type: MobType::Koldun,
+        mob_type,
         pos: Vec2::new(
             (ROOM_SIZE / 2) as f32 * 32.,
             (ROOM_SIZE / 2 - 5) as f32 * 32.,
@@ -880,6 +1004,7 @@ fn boss_spawn(
         owner: None,
         loot: None,
         exp_amount: -1,
+        elite: EliteRoll::Never,
     });
     game_state.set(GameState::InGame);
 }
@@ -943,6 +1068,7 @@ pub fn push_mob_to_queue(
                     dir: Vec3::ZERO,
                     mob_unlock_tag: mob_unlock_tag,
                     is_spawned: true,
+                    boss_loot: None,
                 });
             }
         }
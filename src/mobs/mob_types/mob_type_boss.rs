@@ -1,7 +1,9 @@
+//Боссы глав: песчаный голем (пустыня), королева растений (джунгли), лорд некромантов (ад)
 //Колдун - финальный босс, имеет 3 фазы. 1 - спавн юнитов, 2 - уменьшенный спавн юнитов и заклинания с проджектайлами, 3 - буллетхелл
 //как деф механики может использовать щит на 2 стадии, на 3 - пустышки. Между стадиями также использует пустышки.
 //во 2 фазе телепортируется ближе к игроку
 //в 3 фазе может ходить в разные стороны
+//фазы, атаки и их веса описаны в assets/bosses.json
 use std::time::Duration;
 
 use avian2d::prelude::*;
@@ -10,14 +12,22 @@ use bevy::prelude::*;
 use crate::{
     elements::{ElementResistance, ElementType},
     health::Health,
-    mobs::{mob::*, BossAttackSystem, BossAttackType},
+    mobs::{mob::*, BossAttackSystem, BossScript},
     Bundle, Timer,
 };
 #[derive(Component)]
 pub struct PhaseManager {
     pub current_phase: u8,
     pub max_phase: u8,
-    pub phase_change_hp_multiplier: Vec<f32>,
+}
+
+impl Default for PhaseManager {
+    fn default() -> Self {
+        Self {
+            current_phase: 1,
+            max_phase: 1,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -115,6 +125,7 @@ pub struct BossBundle {
     pub summon_queue: SummonQueue, 
     pub boss_attacks: BossAttackSystem,
     pub phase_manager: PhaseManager,
+    pub script: BossScript,
 }
 
 impl MobBundle {
@@ -142,33 +153,76 @@ impl MobBundle {
             ..default()
         }
     }
+
+    //коллайдеры боссов глав маленькие, т.к. спрайт и коллайдер растягиваются через scale
+    pub fn sand_golem() -> Self {
+        Self {
+            phys_bundle: PhysicalBundle {
+                collider: Collider::circle(12.),
+                ..default()
+            },
+            resistance: ElementResistance {
+                elements: vec![ElementType::Earth, ElementType::Fire],
+                resistance_percent: vec![30, 0, 60, 0, 0],
+            },
+            mob_type: MobType::SandGolem,
+            mob: Mob::new(30),
+            exp_loot: MobLoot { orbs: 60 },
+            body_type: RigidBody::Dynamic,
+            health: Health::new(2000),
+            hit_list: HitList::default(),
+            ..default()
+        }
+    }
+
+    pub fn plant_queen() -> Self {
+        Self {
+            phys_bundle: PhysicalBundle {
+                collider: Collider::circle(10.),
+                ..default()
+            },
+            resistance: ElementResistance {
+                elements: vec![ElementType::Water, ElementType::Earth],
+                resistance_percent: vec![0, 60, 60, 0, 0],
+            },
+            mob_type: MobType::PlantQueen,
+            mob: Mob::new(30),
+            exp_loot: MobLoot { orbs: 80 },
+            body_type: RigidBody::Static,
+            health: Health::new(2600),
+            hit_list: HitList::default(),
+            ..default()
+        }
+    }
+
+    pub fn necromancer_lord() -> Self {
+        Self {
+            phys_bundle: PhysicalBundle {
+                collider: Collider::circle(8.),
+                ..default()
+            },
+            resistance: ElementResistance {
+                elements: vec![ElementType::Fire],
+                resistance_percent: vec![50, 0, 0, 0, 0],
+            },
+            mob_type: MobType::NecromancerLord,
+            mob: Mob::new(30),
+            exp_loot: MobLoot { orbs: 90 },
+            body_type: RigidBody::Dynamic,
+            health: Health::new(3000),
+            hit_list: HitList::default(),
+            ..default()
+        }
+    }
 }
 
 impl BossBundle {
-    pub fn koldun() -> Self {
-        let mut cooldowns = vec![Timer::new(Duration::from_millis(12500), TimerMode::Repeating); 12];
-
-        cooldowns[BossAttackType::MegaStan as usize] =
-            Timer::new(Duration::from_millis(12400), TimerMode::Repeating);
-      /*   cooldowns[BossAttackType::SpawnClayGolem as usize] =
-            Timer::new(Duration::from_millis(9000), TimerMode::Repeating); */
-/*        cooldowns[BossAttackType::SpawnAirElemental as usize] =
-            Timer::new(Duration::from_millis(9100), TimerMode::Repeating);
-        cooldowns[BossAttackType::ProjectilePattern as usize] =
-            Timer::new(Duration::from_millis(9050), TimerMode::Repeating);
- */
+    //общее для всех боссов, остальное поведение берется из bosses.json по имени
+    fn new(mob_bundle: MobBundle, script_name: &str, max_summons: u8) -> Self {
         Self {
-            mob_bundle: MobBundle::koldun(),
-            boss_attacks: BossAttackSystem {
-                //4 tiers of attacks
-                weight_array: vec![0; 11], //amount of attacks
-                cooldown_array: cooldowns,
-                cooldown_between_attacks: Timer::new(
-                    Duration::from_millis(3500),
-                    TimerMode::Repeating,
-                ),
-                cooldown_mask: 0b0000011111111111, //bitmask for cooldown, use bitwise to get what you need, equal to 4095
-            },
+            mob_bundle,
+            boss_attacks: BossAttackSystem::default(),
+            script: BossScript::new(script_name),
             pathfinder: BossMovement {
                 speed: 2850.,
                 timer: Timer::new(Duration::from_millis(4000), TimerMode::Repeating),
@@ -185,16 +239,28 @@ impl BossBundle {
                         entity: None,
                         mob_type: MobType::Mossling
                     };
-                    10
+                    max_summons as usize
                 ],
                 amount_of_mobs: 0,
-                max_amount: 10,
-            },
-            phase_manager: PhaseManager {
-                current_phase: 1,
-                max_phase: 3,
-                phase_change_hp_multiplier: vec![0.5, 0.2],
+                max_amount: max_summons,
             },
+            phase_manager: PhaseManager::default(),
         }
     }
+
+    pub fn koldun() -> Self {
+        Self::new(MobBundle::koldun(), "koldun", 10)
+    }
+
+    pub fn sand_golem() -> Self {
+        Self::new(MobBundle::sand_golem(), "sand_golem", 4)
+    }
+
+    pub fn plant_queen() -> Self {
+        Self::new(MobBundle::plant_queen(), "plant_queen", 8)
+    }
+
+    pub fn necromancer_lord() -> Self {
+        Self::new(MobBundle::necromancer_lord(), "necromancer_lord", 12)
+    }
 }
@@ -6,7 +6,8 @@ use {avian2d::prelude::*, bevy::prelude::*, rand::Rng, std::time::Duration};
 use crate::{
     elements::{ElementResistance, ElementType},
     health::Health,
-    mobs::mob::*,
+    mobs::{mob::*, AttackPattern},
+    projectile::ProjectileModifier,
     Bundle, Timer,
 };
 
@@ -44,6 +45,20 @@ impl MobBundle {
             ..default()
         }
     }
+
+    pub fn skelet_mage() -> Self {
+        Self {
+            resistance: ElementResistance {
+                elements: vec![ElementType::Steam],
+                resistance_percent: vec![0, 0, 0, 0, 50],
+            },
+            mob_type: MobType::SkeletMage,
+            body_type: RigidBody::Static,
+            health: Health::new(70),
+            exp_loot: MobLoot { orbs: 4 },
+            ..default()
+        }
+    }
 }
 
 impl MageBundle {
@@ -92,4 +107,36 @@ impl MageBundle {
             search_and_pursue: SearchAndPursue::range_units(),
         }
     }
+
+    //кидает медленные кости, которые доворачивают за целью и раскалываются при попадании
+    pub fn skelet_mage() -> Self {
+        let timer: u64 = rand::thread_rng().gen_range(3500..5500);
+
+        Self {
+            mob_bundle: MobBundle::skelet_mage(),
+            teleport_ability: Teleport {
+                amount_of_tiles: 4,
+                place_to_teleport: vec![],
+                time_to_teleport: Timer::new(Duration::from_millis(timer), TimerMode::Repeating),
+            },
+            shoot_ability: AttackComponent {
+                range: 300.,
+                attack_type: AttackType::Range,
+                cooldown: Timer::new(Duration::from_millis(timer), TimerMode::Repeating),
+                damage: 15,
+                element: Some(ElementType::Steam),
+                proj_type: Some(ProjectileType::BoneBolt),
+                pattern: Some(
+                    AttackPattern::default()
+                        .with_modifier(ProjectileModifier::Homing { turn_rate: 2.0 })
+                        .with_modifier(ProjectileModifier::Splitting {
+                            count: 3,
+                            spread: 1.2,
+                        }),
+                ),
+                ..default()
+            },
+            search_and_pursue: SearchAndPursue::range_units(),
+        }
+    }
 }
@@ -7,7 +7,7 @@ use std::time::Duration;
 use crate::{
     elements::{ElementResistance, ElementType},
     health::Health,
-    mobs::mob::*,
+    mobs::{mob::*, AttackPattern},
     pathfinding::Pathfinder,
     GameLayer, Timer,
 };
@@ -61,6 +61,19 @@ impl MobBundle {
         }
     }
 
+    pub fn skelet_warrior() -> Self {
+        Self {
+            resistance: ElementResistance {
+                elements: vec![ElementType::Earth],
+                resistance_percent: vec![0, 0, 20, 0, 0],
+            },
+            mob_type: MobType::SkeletWarrior,
+            health: Health::new(110),
+            exp_loot: MobLoot { orbs: 4 },
+            ..default()
+        }
+    }
+
     pub fn clay_golem() -> Self {
         Self {
             phys_bundle: PhysicalBundle::default(),
@@ -102,6 +115,27 @@ impl MeleeMobBundle<PlayerRush> {
         }
     }
 
+    pub fn skelet_warrior() -> Self {
+        Self {
+            mob_bundle: MobBundle::skelet_warrior(),
+            path_finder: Pathfinder {
+                speed: 1800.,
+                ..default()
+            },
+            behaviour: PlayerRush,
+            attack: AttackComponent {
+                attack_type: AttackType::Spear,
+                damage: 25,
+                cooldown: Timer::new(Duration::from_millis(2500), TimerMode::Repeating),
+                pattern: Some(AttackPattern {
+                    count: 2,
+                    ..default()
+                }),
+                ..default()
+            },
+        }
+    }
+
     pub fn clay_golem() -> Self {
         Self {
             mob_bundle: MobBundle::clay_golem(),
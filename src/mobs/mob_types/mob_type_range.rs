@@ -5,7 +5,7 @@ use {bevy::prelude::*, std::time::Duration};
 use crate::{
     elements::{ElementResistance, ElementType},
     health::Health,
-    mobs::mob::*,
+    mobs::{mob::*, AttackPattern},
     Bundle, Timer,
     pathfinding::Pathfinder,
 };
@@ -30,6 +30,15 @@ impl MobBundle {
             ..default()
         }
     }
+
+    pub fn skelet_ranger() -> Self {
+        Self {
+            mob_type: MobType::SkeletRanger,
+            health: Health::new(70),
+            exp_loot: MobLoot { orbs: 4 },
+            ..default()
+        }
+    }
 }
 
 impl RangeMobBundle {
@@ -49,4 +58,23 @@ impl RangeMobBundle {
             path_finder: Pathfinder::default(),
         }
     }
+
+    //лучник, кидает быстрые стрелы очередью, AttackTelegraph навешивается при спавне, дистанцию держит SquadBehaviour
+    pub fn skelet_ranger() -> Self {
+        Self {
+            mob_bundle: MobBundle::skelet_ranger(),
+            attack_ability: AttackComponent {
+                range: 320.,
+                attack_type: AttackType::Range,
+                cooldown: Timer::new(Duration::from_millis(2500), TimerMode::Repeating),
+                damage: 20,
+                element: Some(ElementType::Earth),
+                proj_type: Some(ProjectileType::Arrow),
+                pattern: Some(AttackPattern::stream(3, 0.15, 0.1)),
+                ..default()
+            },
+            search_and_pursue: SearchAndPursue::range_units(),
+            path_finder: Pathfinder::default(),
+        }
+    }
 }
@@ -0,0 +1,198 @@
+//components and systems for skeletons, bundles are in melee/mage/range files
+//скелеты: воин со щитом, маг с самонаводящимися костями и лучник
+//некромансер поблизости собирает их кости обратно
+use {bevy::prelude::*, rand::Rng};
+
+use crate::{
+    friend::Friend,
+    mobs::{mob::*, EliteRoll, MobSpawnEvent},
+    obstacles::Corpse,
+    particles::{ParticlePattern, SpawnParticlesEvent},
+    player::Player,
+    Timer,
+};
+
+//щит воина, блокирует снаряды, прилетевшие спереди
+#[derive(Component)]
+pub struct ShieldBlock {
+    pub facing: Vec2,
+    pub arc: f32,        //половина угла сектора, в радианах
+    pub turn_speed: f32, //радиан в секунду
+    pub raised: bool,
+}
+
+impl Default for ShieldBlock {
+    fn default() -> Self {
+        Self {
+            facing: Vec2::X,
+            arc: std::f32::consts::PI / 3.,
+            turn_speed: 2.5,
+            raised: true,
+        }
+    }
+}
+
+impl ShieldBlock {
+    //from - направление от щита к снаряду
+    pub fn blocks(&self, from: Vec2) -> bool {
+        self.raised && self.facing.angle_between(from).abs() <= self.arc
+    }
+}
+
+#[derive(Component)]
+pub struct ShieldSprite;
+
+//кости скелета, пока жив некромансер - собираются обратно
+#[derive(Component)]
+pub struct BonePile {
+    pub timer: Timer,
+}
+
+//щит воина медленно доворачивается к ближайшей цели, во время замаха щит опущен
+pub fn turn_shield(
+    mut warrior_query: Query<(
+        &Transform,
+        &mut ShieldBlock,
+        Has<Enemy>,
+        Has<BeforeAttackDelay>,
+        Has<AttackFlag>,
+    )>,
+    ally_query: Query<&Transform, (Or<(With<Player>, With<Friend>)>, Without<ShieldBlock>)>,
+    enemy_query: Query<&Transform, (With<Enemy>, Without<ShieldBlock>)>,
+    time: Res<Time>,
+) {
+    for (transform, mut shield, is_enemy, winding_up, attacking) in warrior_query.iter_mut() {
+        shield.raised = !winding_up && !attacking;
+
+        let pos = transform.translation.truncate();
+        let targets = if is_enemy {
+            ally_query.iter().collect::<Vec<_>>()
+        } else {
+            enemy_query.iter().collect::<Vec<_>>()
+        };
+
+        let Some(target) = targets
+            .iter()
+            .map(|t| t.translation.truncate())
+            .min_by(|a, b| a.distance_squared(pos).total_cmp(&b.distance_squared(pos)))
+        else {
+            continue;
+        };
+
+        let wanted = (target - pos).normalize_or_zero();
+        if wanted == Vec2::ZERO {
+            continue;
+        }
+
+        let diff = shield.facing.angle_between(wanted);
+        let max_turn = shield.turn_speed * time.delta_seconds();
+
+        shield.facing = Vec2::from_angle(diff.clamp(-max_turn, max_turn)).rotate(shield.facing);
+    }
+}
+
+//спрайт щита висит перед воином со стороны, куда он смотрит
+pub fn spawn_shield_sprite(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    warrior_query: Query<Entity, Added<ShieldBlock>>,
+) {
+    for warrior in warrior_query.iter() {
+        commands.entity(warrior).with_children(|parent| {
+            parent
+                .spawn(SpriteBundle {
+                    texture: asset_server.load("textures/mobs/skelet_shield.png"),
+                    transform: Transform::from_xyz(8., 0., 0.1),
+                    ..default()
+                })
+                .insert(ShieldSprite);
+        });
+    }
+}
+
+pub fn sync_shield_sprite(
+    warrior_query: Query<&ShieldBlock>,
+    mut sprite_query: Query<(&Parent, &mut Transform, &mut Visibility), With<ShieldSprite>>,
+) {
+    for (parent, mut transform, mut visibility) in sprite_query.iter_mut() {
+        let Ok(shield) = warrior_query.get(parent.get()) else {
+            continue;
+        };
+
+        let offset = shield.facing * 8.;
+        transform.translation = offset.extend(if shield.facing.y > 0. { -0.1 } else { 0.1 });
+        transform.rotation = Quat::from_rotation_z(shield.facing.to_angle());
+
+        *visibility = if shield.raised {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+//трупы скелетов - это кучи костей
+pub fn mark_bone_piles(
+    mut commands: Commands,
+    corpse_query: Query<(Entity, &Corpse), Added<Corpse>>,
+) {
+    for (corpse_e, corpse) in corpse_query.iter() {
+        match corpse.mob_type {
+            MobType::SkeletWarrior | MobType::SkeletMage | MobType::SkeletRanger => {
+                commands.entity(corpse_e).insert(BonePile {
+                    timer: Timer::from_seconds(
+                        rand::thread_rng().gen_range(6.0..9.0),
+                        TimerMode::Once,
+                    ),
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+//пока на уровне есть живой некромансер (или их лорд), кости сами собираются обратно
+pub fn reassemble_bone_piles(
+    mut commands: Commands,
+    mut pile_query: Query<(Entity, &Transform, &Corpse, &mut BonePile), Without<BusyRaising>>,
+    necromancer_query: Query<&MobType, With<Enemy>>,
+    mut ev_spawn: EventWriter<MobSpawnEvent>,
+    mut ev_spawn_particles: EventWriter<SpawnParticlesEvent>,
+    time: Res<Time>,
+) {
+    if !necromancer_query
+        .iter()
+        .any(|mob_type| matches!(mob_type, MobType::Necromancer | MobType::NecromancerLord))
+    {
+        return;
+    }
+
+    for (pile_e, transform, corpse, mut pile) in pile_query.iter_mut() {
+        pile.timer.tick(time.delta());
+
+        if !pile.timer.finished() {
+            continue;
+        }
+
+        ev_spawn.send(MobSpawnEvent {
+            mob_type: corpse.mob_type.clone(),
+            pos: transform.translation.truncate(),
+            is_friendly: false,
+            owner: None,
+            loot: None,
+            exp_amount: 0,
+            elite: EliteRoll::Never,
+        });
+
+        ev_spawn_particles.send(SpawnParticlesEvent {
+            pattern: ParticlePattern::Circle { radius: 12. },
+            position: transform.translation,
+            amount: 6,
+            color: Color::srgb(0.9, 0.9, 0.8),
+            speed: 10.,
+            rotate: false,
+        });
+
+        commands.entity(pile_e).despawn();
+    }
+}
@@ -266,6 +266,7 @@ pub fn thief_collide(
                         pos: transform.translation,
                         dir: Vec3::ZERO,
                         is_spawned: false,
+                        boss_loot: None,
                     });
                     
                     break;
@@ -70,7 +70,7 @@ impl TurretBundle {
                 cooldown: Timer::new(Duration::from_millis(3500),TimerMode::Repeating),
                 damage: 30,
                 element: Some(ElementType::Earth),
-                proj_type: Some(ProjectileType::Missile),                
+                proj_type: Some(ProjectileType::Missile),
                 ..default()
             },
             search_and_pursue: SearchAndPursue::range_units(),
@@ -20,4 +20,7 @@ mod mob_type_range;
 pub use mob_type_range::*;
 
 mod mob_type_special;
-pub use mob_type_special::*;
\ No newline at end of file
+pub use mob_type_special::*;
+
+mod mob_type_skeleton;
+pub use mob_type_skeleton::*;
@@ -14,4 +14,20 @@ mod mob_types;
 
 mod boss_behaviour;
 pub use  boss_behaviour::*;
-pub use mob_types::*;
\ No newline at end of file
+pub use mob_types::*;
+
+mod boss_script;
+pub use boss_script::*;
+
+mod elite;
+pub use elite::*;
+
+mod attack_pattern;
+pub use attack_pattern::*;
+
+mod squad;
+pub use squad::*;
+
+
+mod perception;
+pub use perception::*;
\ No newline at end of file
@@ -0,0 +1,113 @@
+//что мобы видят и слышат: линия взгляда сквозь стены не проходит,
+//шум (заклинания, удары) привлекает ближайших, Stealth уменьшает радиус обнаружения
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use seldom_state::prelude::*;
+
+use crate::{alert::SpawnAlertEvent, audio::PlayAudioEvent, GameLayer, GameState};
+
+use super::{Enemy, Idle, SearchAndPursue, Searching};
+
+pub struct PerceptionPlugin;
+
+impl Plugin for PerceptionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<NoiseEvent>().add_systems(
+            Update,
+            (audio_noise, hear_noise)
+                .chain()
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+//цель, которую сложнее заметить, detection_mult множит радиус обнаружения моба
+#[derive(Component)]
+pub struct Stealth {
+    pub detection_mult: f32,
+}
+
+impl Stealth {
+    pub fn detection_radius(stealth: Option<&Stealth>, radius: f32) -> f32 {
+        stealth.map_or(radius, |stealth| radius * stealth.detection_mult)
+    }
+}
+
+#[derive(Event)]
+pub struct NoiseEvent {
+    pub position: Vec2,
+    pub radius: f32,
+}
+
+//видно ли цель: луч до нее не задевает стены
+pub fn line_of_sight(spatial_query: &SpatialQuery, from: Vec2, to: Vec2) -> bool {
+    let diff = to - from;
+    let Ok(dir) = Dir2::new(diff) else {
+        return true;
+    };
+
+    spatial_query
+        .cast_ray(
+            from,
+            dir,
+            diff.length(),
+            true,
+            SpatialQueryFilter::from_mask(GameLayer::Wall),
+        )
+        .is_none()
+}
+
+//звуки с источником тоже шум
+fn audio_noise(
+    mut ev_play_audio: EventReader<PlayAudioEvent>,
+    mut ev_noise: EventWriter<NoiseEvent>,
+) {
+    for ev in ev_play_audio.read() {
+        if let Some(source) = ev.source {
+            ev_noise.send(NoiseEvent {
+                position: source.position,
+                radius: source.radius,
+            });
+        }
+    }
+}
+
+//услышавшие идут проверить, откуда шум: из Idle через Pursue, который без цели в поле зрения
+//сразу уходит в Searching
+fn hear_noise(
+    mut commands: Commands,
+    mut ev_noise: EventReader<NoiseEvent>,
+    mut mob_query: Query<
+        (Entity, &Transform, &mut SearchAndPursue, Option<&mut Searching>, Has<Idle>),
+        (With<Enemy>, Or<(With<Idle>, With<Searching>)>),
+    >,
+    mut ev_spawn_alert: EventWriter<SpawnAlertEvent>,
+) {
+    for ev in ev_noise.read() {
+        for (mob_e, transform, mut mob, searching, idle) in mob_query.iter_mut() {
+            if transform.translation.truncate().distance(ev.position) > ev.radius {
+                continue;
+            }
+
+            mob.last_known = Some(ev.position);
+
+            if let Some(mut searching) = searching {
+                searching.timer.reset();
+            }
+
+            if idle {
+                commands.entity(mob_e).insert(Done::Success);
+                mob.search_time.reset();
+
+                ev_spawn_alert.send(SpawnAlertEvent {
+                    position: transform
+                        .translation
+                        .truncate()
+                        .with_y(transform.translation.y + 16.),
+                    attack_alert: false,
+                    spotted_by: None,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//групповое поведение мобов: мили окружают цель, стрелки держат дистанцию и линию огня,
+//заметивший игрока будит соседей. Настраивается по MobAI при спавне
+use std::f32::consts::PI;
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use seldom_state::prelude::*;
+
+use crate::{
+    alert::SpawnAlertEvent, friend::Friend, player::Player, GameState,
+};
+
+use super::{line_of_sight, Enemy, Idle, Mob, MobAI, Pursue, SearchAndPursue};
+
+pub struct SquadPlugin;
+
+impl Plugin for SquadPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, wake_allies.run_if(in_state(GameState::InGame)))
+            .add_systems(
+                FixedUpdate,
+                squad_steering.run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+#[derive(Component, Clone)]
+pub struct SquadBehaviour {
+    pub surround: bool,              //занимать место вокруг цели, а не толпиться в одной точке
+    pub hold_distance: Option<f32>,  //отходить, если цель ближе
+    pub keep_line_of_sight: bool,    //обходить стены, которые закрывают цель
+    pub alert_radius: f32,           //кого будит, когда замечает цель, 0 - никого
+    pub hide_behind_allies: bool,    //прятаться за спинами союзников
+}
+
+impl Default for SquadBehaviour {
+    fn default() -> Self {
+        Self {
+            surround: false,
+            hold_distance: None,
+            keep_line_of_sight: false,
+            alert_radius: 0.,
+            hide_behind_allies: false,
+        }
+    }
+}
+
+impl SquadBehaviour {
+    pub fn for_ai(ai: &MobAI) -> Self {
+        match ai {
+            MobAI::MeleeWithATK => Self {
+                surround: true,
+                alert_radius: 160.,
+                ..default()
+            },
+            MobAI::RangeMoving => Self {
+                hold_distance: Some(120.),
+                keep_line_of_sight: true,
+                alert_radius: 160.,
+                ..default()
+            },
+            MobAI::RangeWithTP | MobAI::Turret => Self {
+                alert_radius: 200.,
+                ..default()
+            },
+            MobAI::Phasing | MobAI::Orbital => Self {
+                alert_radius: 120.,
+                ..default()
+            },
+            MobAI::Spawner => Self {
+                hide_behind_allies: true,
+                ..default()
+            },
+            MobAI::Thief | MobAI::Boss => Self::default(),
+        }
+    }
+}
+
+//радиус, в котором мили мобы расталкивают друг друга
+const SEPARATION_RADIUS: f32 = 24.;
+//с какого расстояния мили начинают расходиться по местам вокруг цели
+const SURROUND_RADIUS: f32 = 96.;
+
+//добавка к направлению преследования, pursue прибавляет ее к лучам
+fn squad_steering(
+    spatial_query: SpatialQuery,
+    mut mob_query: Query<
+        (Entity, &Transform, &mut SearchAndPursue, &SquadBehaviour, Has<Enemy>),
+        With<Pursue>,
+    >,
+    player_query: Query<&Transform, With<Player>>,
+    enemy_mob_query: Query<&Transform, (With<Enemy>, With<Mob>)>,
+    friend_mob_query: Query<&Transform, (With<Friend>, With<Mob>)>,
+) {
+    //мили, окружающие цель, распределяются по кругу в порядке id
+    let mut surrounders: Vec<(Entity, bool)> = mob_query
+        .iter()
+        .filter(|(_, _, _, squad, _)| squad.surround)
+        .map(|(e, _, _, _, is_enemy)| (e, is_enemy))
+        .collect();
+    surrounders.sort_by_key(|(e, _)| *e);
+
+    let enemy_positions: Vec<Vec2> = enemy_mob_query
+        .iter()
+        .map(|t| t.translation.truncate())
+        .collect();
+    let friend_positions: Vec<Vec2> = friend_mob_query
+        .iter()
+        .map(|t| t.translation.truncate())
+        .collect();
+
+    for (mob_e, transform, mut mob, squad, is_enemy) in mob_query.iter_mut() {
+        let pos = transform.translation.truncate();
+
+        let nearest = if is_enemy {
+            player_query
+                .iter()
+                .map(|t| t.translation.truncate())
+                .chain(friend_positions.iter().copied())
+                .min_by(|a, b| a.distance_squared(pos).total_cmp(&b.distance_squared(pos)))
+        } else {
+            enemy_positions
+                .iter()
+                .copied()
+                .min_by(|a, b| a.distance_squared(pos).total_cmp(&b.distance_squared(pos)))
+        };
+
+        let Some(target) = nearest else {
+            mob.squad_dir = Vec2::ZERO;
+            continue;
+        };
+
+        let to_target = (target - pos).normalize_or_zero();
+        let distance = target.distance(pos);
+        let mut squad_dir = Vec2::ZERO;
+
+        if squad.surround {
+            //не стакаемся с соседями своей стороны
+            let same_side = if is_enemy {
+                &enemy_positions
+            } else {
+                &friend_positions
+            };
+            for other in same_side.iter() {
+                let away = pos - *other;
+                let len = away.length();
+                if len > 0. && len < SEPARATION_RADIUS {
+                    squad_dir += away / len * (1. - len / SEPARATION_RADIUS);
+                }
+            }
+
+            //вблизи цели идем на свое место вокруг нее
+            let side: Vec<Entity> = surrounders
+                .iter()
+                .filter(|(_, enemy)| *enemy == is_enemy)
+                .map(|(e, _)| *e)
+                .collect();
+            if let Some(slot) = side.iter().position(|e| *e == mob_e) {
+                if distance < SURROUND_RADIUS && side.len() > 1 {
+                    let angle = slot as f32 * 2. * PI / side.len() as f32;
+                    let place = target + Vec2::from_angle(angle) * (SURROUND_RADIUS / 3.);
+                    squad_dir += (place - pos).normalize_or_zero() - to_target;
+                }
+            }
+        }
+
+        if let Some(hold) = squad.hold_distance {
+            if distance < hold {
+                squad_dir -= to_target * 2.;
+            }
+        }
+
+        if squad.keep_line_of_sight {
+            let blocked = !line_of_sight(&spatial_query, pos, target);
+
+            //стена на пути - стрейфим вбок, сторона зависит от id, чтобы не идти всем в одну
+            if blocked {
+                let perp = to_target.perp();
+                squad_dir += if mob_e.index() % 2 == 0 { perp } else { -perp };
+            }
+        }
+
+        mob.squad_dir = squad_dir;
+    }
+}
+
+//моб заметил цель - соседи той же стороны тоже начинают преследование
+fn wake_allies(
+    mut commands: Commands,
+    mut ev_alert: EventReader<SpawnAlertEvent>,
+    spotter_query: Query<(&Transform, &SquadBehaviour, Has<Enemy>)>,
+    mut idle_query: Query<(Entity, &Transform, &mut SearchAndPursue, Has<Enemy>), With<Idle>>,
+) {
+    for ev in ev_alert.read() {
+        let Some(spotter) = ev.spotted_by else {
+            continue;
+        };
+
+        let Ok((spotter_transform, squad, spotter_enemy)) = spotter_query.get(spotter) else {
+            continue;
+        };
+
+        if squad.alert_radius <= 0. {
+            continue;
+        }
+
+        for (mob_e, transform, mut mob, is_enemy) in idle_query.iter_mut() {
+            if mob_e == spotter || is_enemy != spotter_enemy {
+                continue;
+            }
+
+            if transform.translation.distance(spotter_transform.translation) > squad.alert_radius {
+                continue;
+            }
+
+            //идут к заметившему, дальше цель подхватит pursue, а если ее не видно - поиск
+            commands.entity(mob_e).insert(Done::Success);
+            mob.search_time.reset();
+            mob.last_known = Some(spotter_transform.translation.truncate());
+            mob.last_target_dir = (spotter_transform.translation - transform.translation)
+                .truncate()
+                .normalize_or_zero();
+        }
+    }
+}
@@ -1,32 +1,38 @@
+use std::collections::HashMap;
+
 use avian2d::prelude::*;
 use bevy::prelude::*;
 use seldom_state::trigger::Done;
 
 use crate::{
-    audio::PlayAudioEvent, camera::YSort, health::{Health, Hit}, mobs::*, pathfinding::Pathfinder, projectile::{Friendly, Projectile}, stun::Stun, GameLayer, GameState
+    audio::PlayAudioEvent, camera::YSort, gamemap::tile_of, health::{Health, Hit}, mobs::*, pathfinding::{GraphChange, Pathfinder, UpdateGraphEvent}, projectile::{Friendly, Projectile, ProjectileImpactEvent}, stun::Stun, GameLayer, GameState
 };
 
 pub struct ObstaclePlugin;
 
 impl Plugin for ObstaclePlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<CorpseSpawnEvent>().add_systems(
-            Update,
-            (
-                spawn_corpse,
-                damage_obstacles::<Obstacle>,
-                hit_obstacles::<Obstacle>,
-                corpse_collision,
-            )
-                .run_if(in_state(GameState::InGame)),
-        );
+        app.add_event::<CorpseSpawnEvent>()
+            .init_resource::<ObstacleTiles>()
+            .add_systems(OnExit(GameState::InGame), clear_obstacle_tiles)
+            .add_systems(
+                Update,
+                (
+                    spawn_corpse,
+                    damage_obstacles::<Obstacle>,
+                    hit_obstacles::<Obstacle>,
+                    corpse_collision,
+                    update_obstacles_in_graph,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
     }
 }
 //event to spawn corpse
 //Corpse component for necromancer.
 #[derive(Component)]
 pub struct Corpse {
-    mob_type: MobType,
+    pub mob_type: MobType,
 }
 
 #[derive(Event)]
@@ -39,6 +45,10 @@ pub struct CorpseSpawnEvent {
 #[derive(Component)]
 pub struct Obstacle;
 
+//тайлы препятствий текущего уровня, граф нового уровня собирается с нуля
+#[derive(Resource, Default)]
+pub struct ObstacleTiles(HashMap<Entity, (u16, u16)>);
+
 fn corpse_collision(
     mut commands: Commands,
     mut summoner_query: Query<
@@ -81,15 +91,51 @@ fn corpse_collision(
     }
 }
 
+//препятствия не двигаются, поэтому запоминаем их тайл при появлении и снимаем при удалении.
+//удаления с прошлого уровня в ObstacleTiles уже нет, их пропускаем
+fn update_obstacles_in_graph(
+    obstacle_query: Query<(Entity, &Transform), Added<Obstacle>>,
+    mut removed_obstacles: RemovedComponents<Obstacle>,
+    mut obstacle_tiles: ResMut<ObstacleTiles>,
+    mut ev_update_graph: EventWriter<UpdateGraphEvent>,
+) {
+    for (obstacle_e, transform) in obstacle_query.iter() {
+        let tile = tile_of(transform.translation);
+        obstacle_tiles.0.insert(obstacle_e, tile);
+        ev_update_graph.send(UpdateGraphEvent {
+            tile,
+            change: GraphChange::AddObstacle,
+        });
+    }
+
+    for obstacle_e in removed_obstacles.read() {
+        if let Some(tile) = obstacle_tiles.0.remove(&obstacle_e) {
+            ev_update_graph.send(UpdateGraphEvent {
+                tile,
+                change: GraphChange::RemoveObstacle,
+            });
+        }
+    }
+}
+
+fn clear_obstacle_tiles(mut obstacle_tiles: ResMut<ObstacleTiles>) {
+    obstacle_tiles.0.clear();
+}
+
 fn hit_obstacles<T: Component>(
     //TODO: ADD LOOT DROP FROM OBSTACLES IDK, MAYBE ADD LOOT TO THEM
-    mut commands: Commands,
-    projectile_query: Query<(Entity, &Projectile, &Transform), With<Friendly>>,
-    mut obstacle_query: Query<(&CollidingEntities, &mut Health, &Transform), With<T>>,
+    mut projectile_query: Query<(&mut Projectile, &Transform), With<Friendly>>,
+    mut obstacle_query: Query<(Entity, &CollidingEntities, &mut Health, &Transform), With<T>>,
+    mut ev_impact: EventWriter<ProjectileImpactEvent>,
 ) {
-    for (colliding_e, mut health, obstacle_transform) in obstacle_query.iter_mut() {
-        for (proj_e, projectile, projectile_transform) in projectile_query.iter() {
-            if colliding_e.contains(&proj_e) {
+    for (obstacle_e, colliding_e, mut health, obstacle_transform) in obstacle_query.iter_mut() {
+        for proj_e in colliding_e.iter().copied() {
+            let Ok((mut projectile, projectile_transform)) = projectile_query.get_mut(proj_e)
+            else {
+                continue;
+            };
+
+            if projectile.can_hit(obstacle_e) {
                 // урон
                 let damage = projectile.damage as i32;
 
@@ -104,8 +150,11 @@ fn hit_obstacles<T: Component>(
                     direction: shot_dir,
                 });
 
-                // деспавним снаряд
-                commands.entity(proj_e).despawn();
+                projectile.register_hit(obstacle_e);
+                ev_impact.send(ProjectileImpactEvent {
+                    projectile: proj_e,
+                    target: Some(obstacle_e),
+                });
             }
         }
     }
@@ -192,21 +241,33 @@ fn spawn_corpse(
                 can_be_spawned = true;
             }
             MobType::SkeletMage => {
-                texture_path = "textures/mob_corpse_placeholder.png";
+                texture_path = "textures/rib.png";
                 can_be_spawned = true;
             }
             MobType::SkeletWarrior => {
-                texture_path = "textures/mob_corpse_placeholder.png";
+                texture_path = "textures/rib.png";
                 can_be_spawned = true;
             }
             MobType::SkeletRanger => {
-                texture_path = "textures/mob_corpse_placeholder.png";
+                texture_path = "textures/rib.png";
                 can_be_spawned = true;
             }
             MobType::Thief => {
                 texture_path = "textures/mobs/corpses/lurker_corpse.png";
                 can_be_spawned = false;
             }
+            MobType::SandGolem => {
+                texture_path = "textures/mobs/corpses/clay_golem_corpse.png";
+                can_be_spawned = false;
+            }
+            MobType::PlantQueen => {
+                texture_path = "textures/mobs/corpses/plant_corpse.png";
+                can_be_spawned = false;
+            }
+            MobType::NecromancerLord => {
+                texture_path = "textures/mobs/corpses/necromancer_corpse.png";
+                can_be_spawned = false;
+            }
         }
         let texture = asset_server.load(texture_path);
         let grave = commands
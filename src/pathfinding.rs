@@ -3,19 +3,22 @@ use bevy::prelude::*;
 use rand::Rng;
 use seldom_state::trigger::Done;
 use std::{
-    collections::{HashMap, LinkedList},
-    time::Duration,
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    marker::PhantomData,
+    time::{Duration, Instant},
 };
 
 use crate::{
     exp_tank::ExpTank,
     friend::Friend,
-    gamemap::{spawn_map, LevelGenerator, Map, TileType, ROOM_SIZE},
+    gamemap::{spawn_map, tile_of, LevelGenerator, Map, Tile, TileType, ROOM_SIZE},
+    hazards::HazardType,
     health_tank::HealthTank,
     item::Item,
     mobs::{
         damage_mobs, BusyRaising, CorpseRush, Enemy, ExpTankRush, HPTankRush, ItemRush,
-        ObstacleRush, Phasing, PlayerRush, RunawayRush, Summoning, Teleport,
+        MobType, ObstacleRush, PlayerRush, RunawayRush, Summoning, Teleport,
     },
     obstacles::{Corpse, Obstacle},
     player::Player,
@@ -28,23 +31,34 @@ impl Plugin for PathfindingPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(Graph::default());
         app.insert_resource(Map::default());
+        app.init_resource::<FlowField<Player>>();
+        app.init_resource::<FlowField<Friend>>();
+        app.add_event::<BenchmarkPathfindingEvent>();
+        app.add_event::<UpdateGraphEvent>();
         app.add_systems(
             OnEnter(GameState::Loading),
             create_new_graph.after(spawn_map),
         )
         .add_systems(
             Update,
-            pathfinding_with_tp.run_if(in_state(GameState::InGame)),
+            (pathfinding_with_tp, benchmark_pathfinding).run_if(in_state(GameState::InGame)),
         )
+        .add_systems(Update, update_graph.run_if(in_state(GameState::InGame)))
         .add_systems(
             Update,
             (
                 (change_target_appeared::<RunawayRush, Corpse, Summoning>,).after(damage_mobs),
                 (change_target_empty_query::<Corpse, CorpseRush, RunawayRush, FriendRush>,)
                     .after(damage_mobs),
-                a_pathfinding::<Player, PlayerRush, PlayerRush, Player>,
+                (
+                    update_flow_field::<Player, PlayerRush>,
+                    update_flow_field::<Friend, FriendRush>,
+                ),
+                (
+                    follow_flow_field::<Player, PlayerRush, Player>,
+                    follow_flow_field::<Friend, FriendRush, Enemy>,
+                ),
                 a_pathfinding::<Corpse, CorpseRush, BusyRaising, Corpse>,
-                a_pathfinding::<Friend, FriendRush, Enemy, Enemy>,
                 a_pathfinding::<Obstacle, ObstacleRush, Corpse, Friend>,
                 a_pathfinding::<Item, ItemRush, Corpse, Friend>,
                 a_pathfinding::<ExpTank, ExpTankRush, Corpse, Friend>,
@@ -92,6 +106,113 @@ impl Default for Pathfinder {
     }
 }
 
+//профиль цен для поиска пути, вешается на моба, без него используется профиль по умолчанию
+#[derive(Component, Clone, Copy, PartialEq)]
+pub struct PathCostProfile {
+    pub diagonal: bool,
+    pub ignore_walls: bool,
+    pub ignored_hazard: Option<HazardType>,
+    pub crowd_cost: u16,
+}
+
+impl Default for PathCostProfile {
+    fn default() -> Self {
+        Self {
+            diagonal: true,
+            ignore_walls: false,
+            ignored_hazard: None,
+            crowd_cost: 2,
+        }
+    }
+}
+
+impl PathCostProfile {
+    pub fn for_mob(mob_type: &MobType) -> Self {
+        match mob_type {
+            //огненные элементали проходят сквозь стены и не боятся лавы
+            MobType::FireElemental => Self {
+                ignore_walls: true,
+                ignored_hazard: Some(HazardType::Lava),
+                ..default()
+            },
+            _ => Self::default(),
+        }
+    }
+
+    //доп. цена тайла: опасности, препятствия и толпа мобов
+    fn tile_cost(&self, tile: &Tile, obstacles: u16) -> u32 {
+        let hazard = match tile.hazard {
+            Some(hazard) if Some(hazard) == self.ignored_hazard => 0,
+            _ => tile.cost(),
+        };
+        let crowd = tile.mob_count.clamp(0, 8) as u16 * self.crowd_cost;
+        let obstacle = obstacles.min(3) * 3;
+
+        (hazard + crowd + obstacle) as u32
+    }
+}
+
+//поле потока (карта Дейкстры) до ближайшей цели типа T, одно на всех мобов с профилем по умолчанию,
+//пересчитывается только когда цель сменила тайл или граф получил GraphChange
+#[derive(Resource)]
+pub struct FlowField<T: Component> {
+    distances: HashMap<(u16, u16), u32>,
+    targets: Vec<(u16, u16)>,
+    revision: Option<u32>, //ревизия графа, по которой поле собрано
+    _marker: PhantomData<T>,
+}
+
+impl<T: Component> Default for FlowField<T> {
+    fn default() -> Self {
+        Self {
+            distances: HashMap::new(),
+            targets: vec![],
+            revision: None,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Component> FlowField<T> {
+    pub fn rebuild(
+        &mut self,
+        graph: &Graph,
+        map: &Map,
+        targets: Vec<(u16, u16)>,
+    ) {
+        self.distances = graph.dijkstra_map(map, &targets, &PathCostProfile::default());
+        self.targets = targets;
+        self.revision = Some(graph.revision());
+    }
+
+    pub fn distance(&self, tile: (u16, u16)) -> Option<u32> {
+        self.distances.get(&tile).copied()
+    }
+
+    //соседний тайл, который ближе всего к цели
+    pub fn next_step(&self, graph: &Graph, map: &Map, tile: (u16, u16)) -> Option<(u16, u16)> {
+        let mut best = self.distance(tile);
+        let mut step = None;
+
+        for next in graph.neighbours(tile, map, &PathCostProfile::default(), &self.targets) {
+            let Some(distance) = self.distance(next) else {
+                continue;
+            };
+            if best.map_or(true, |best| distance < best) {
+                best = Some(distance);
+                step = Some(next);
+            }
+        }
+
+        step
+    }
+}
+
+#[derive(Event)]
+pub struct BenchmarkPathfindingEvent {
+    pub mobs: usize,
+}
+
 // структура для графа, ноды хранят в себе позицию и тип тайла, цена для поиска пути и путь в другой структуре
 
 #[derive(Clone, PartialEq)]
@@ -105,13 +226,29 @@ pub struct Node {
 #[derive(Resource)]
 pub struct Graph {
     adj_list: HashMap<(u16, u16), Vec<Node>>,
+    //временные преграды (щиты) - через тайл нельзя пройти, но граф не трогаем
+    blockers: HashMap<(u16, u16), u16>,
+    //препятствия (ящики, трупы) - пройти можно, но дороже
+    obstacles: HashMap<(u16, u16), u16>,
+    //растет с каждым изменением графа, по нему поля потока понимают, что устарели
+    revision: u32,
 }
 
-//структура нодов с ценами и путем, из них создается двумерная матрица по которой ищем и составляем путь
-#[derive(Clone)]
-struct CostNode {
-    cost: u16,
-    path: LinkedList<(u16, u16)>,
+//изменения графа приходят событиями от препятствий, щитов и изменений карты
+#[derive(Clone, Copy, PartialEq)]
+pub enum GraphChange {
+    Unblock,
+    AddBlocker,
+    RemoveBlocker,
+    AddObstacle,
+    RemoveObstacle,
+    ClearHazard,
+}
+
+#[derive(Event)]
+pub struct UpdateGraphEvent {
+    pub tile: (u16, u16),
+    pub change: GraphChange,
 }
 
 //имплементация структур
@@ -128,21 +265,281 @@ impl Default for Graph {
     fn default() -> Graph {
         Graph {
             adj_list: HashMap::new(),
+            blockers: HashMap::new(),
+            obstacles: HashMap::new(),
+            revision: 0,
         }
     }
 }
 
-impl CostNode {
-    fn new(cost_new: u16) -> Self {
-        CostNode {
-            cost: cost_new,
-            path: LinkedList::new(),
+impl Graph {
+    pub fn revision(&self) -> u32 {
+        self.revision
+    }
+
+    fn bump_revision(&mut self) {
+        self.revision = self.revision.wrapping_add(1);
+    }
+
+    //тайл стал полом (например, сломанная стена), пересобираем списки смежности, т.к. могли открыться диагонали
+    pub fn unblock_tile(&mut self, tile: (u16, u16), map: &mut Map) {
+        map.map.insert(tile, Tile::new(TileType::Floor, 0));
+        self.relink_around(tile, map);
+    }
+
+    //граф для уровней, собранных без LevelGenerator (арена босса)
+    pub fn rebuild_from_map(&mut self, map: &Map) {
+        self.adj_list.clear();
+        self.blockers.clear();
+        self.obstacles.clear();
+        self.bump_revision();
+
+        let floors: Vec<(u16, u16)> = map.map.keys().copied().collect();
+        for tile in floors {
+            self.relink_tile(tile, map);
+        }
+    }
+
+    pub fn add_blocker(&mut self, tile: (u16, u16)) {
+        *self.blockers.entry(tile).or_insert(0) += 1;
+    }
+
+    pub fn remove_blocker(&mut self, tile: (u16, u16)) {
+        remove_counted(&mut self.blockers, tile);
+    }
+
+    pub fn add_obstacle(&mut self, tile: (u16, u16)) {
+        *self.obstacles.entry(tile).or_insert(0) += 1;
+    }
+
+    pub fn remove_obstacle(&mut self, tile: (u16, u16)) {
+        remove_counted(&mut self.obstacles, tile);
+    }
+
+    pub fn is_blocked(&self, tile: (u16, u16)) -> bool {
+        self.blockers.contains_key(&tile)
+    }
+
+    fn relink_around(&mut self, tile: (u16, u16), map: &Map) {
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                let x = tile.0 as i32 + dx;
+                let y = tile.1 as i32 + dy;
+                if x < 0 || y < 0 {
+                    continue;
+                }
+                self.relink_tile((x as u16, y as u16), map);
+            }
         }
     }
-    fn change_cost(&mut self, cost_new: u16) {
-        self.cost = cost_new;
+
+    //ближайший к позиции тайл из графа
+    pub fn nearest_tile(&self, pos: Vec2) -> (u16, u16) {
+        let tile = tile_of(pos.extend(0.));
+        if self.adj_list.contains_key(&tile) {
+            return tile;
+        }
+        safe_get_pos(pos, self)
+    }
+
+    //можно ли стоять на тайле с этим профилем
+    fn passable(&self, tile: (u16, u16), map: &Map, profile: &PathCostProfile) -> bool {
+        if profile.ignore_walls {
+            return map
+                .map
+                .get(&tile)
+                .is_some_and(|t| t.tiletype != TileType::Empty);
+        }
+        self.adj_list.contains_key(&tile)
+    }
+
+    //в занятые щитами тайлы не ходим, кроме целей - к цели под щитом все равно подходим вплотную
+    fn neighbours(
+        &self,
+        tile: (u16, u16),
+        map: &Map,
+        profile: &PathCostProfile,
+        goals: &[(u16, u16)],
+    ) -> Vec<(u16, u16)> {
+        let mut result = vec![];
+
+        if profile.ignore_walls {
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    let x = tile.0 as i32 + dx;
+                    let y = tile.1 as i32 + dy;
+                    if (dx == 0 && dy == 0) || x < 0 || y < 0 {
+                        continue;
+                    }
+                    if self.passable((x as u16, y as u16), map, profile) {
+                        result.push((x as u16, y as u16));
+                    }
+                }
+            }
+        } else if let Some(list) = self.adj_list.get(&tile) {
+            //первый нод в списке - сам тайл
+            for node in list.iter().skip(1) {
+                result.push((
+                    (node.position.x / ROOM_SIZE as f32) as u16,
+                    (node.position.y / ROOM_SIZE as f32) as u16,
+                ));
+            }
+        }
+
+        result.retain(|next| {
+            if self.is_blocked(*next) && !goals.contains(next) {
+                return false;
+            }
+            let diagonal = next.0 != tile.0 && next.1 != tile.1;
+            if !diagonal {
+                return true;
+            }
+            //по диагонали не срезаем углы: оба соседних тайла должны быть проходимы
+            profile.diagonal
+                && self.passable((next.0, tile.1), map, profile)
+                && self.passable((tile.0, next.1), map, profile)
+        });
+
+        result
+    }
+
+    //A* с бинарной кучей, цены шагов 10 по прямой и 14 по диагонали, путь без стартового тайла
+    pub fn find_path(
+        &self,
+        map: &Map,
+        start: (u16, u16),
+        goal: (u16, u16),
+        profile: &PathCostProfile,
+    ) -> Option<Vec<(u16, u16)>> {
+        let heuristic = |tile: (u16, u16)| -> u32 {
+            let dx = (tile.0 as i32 - goal.0 as i32).unsigned_abs();
+            let dy = (tile.1 as i32 - goal.1 as i32).unsigned_abs();
+            if profile.diagonal {
+                10 * dx.max(dy) + 4 * dx.min(dy)
+            } else {
+                10 * (dx + dy)
+            }
+        };
+
+        let mut open = BinaryHeap::new();
+        let mut came_from: HashMap<(u16, u16), (u16, u16)> = HashMap::new();
+        let mut g_score: HashMap<(u16, u16), u32> = HashMap::new();
+        let mut closed: HashSet<(u16, u16)> = HashSet::new();
+
+        g_score.insert(start, 0);
+        open.push(Reverse((heuristic(start), start)));
+
+        while let Some(Reverse((_, current))) = open.pop() {
+            if current == goal {
+                let mut path = vec![current];
+                let mut node = current;
+                while let Some(prev) = came_from.get(&node) {
+                    node = *prev;
+                    path.push(node);
+                }
+                path.pop(); // удаляем точку, в которой уже стоит моб
+                path.reverse();
+                return Some(path);
+            }
+
+            //в куче могут лежать устаревшие записи, обрабатываем тайл только один раз
+            if !closed.insert(current) {
+                continue;
+            }
+
+            let current_g = g_score[&current];
+
+            for next in self.neighbours(current, map, profile, &[goal]) {
+                let Some(tile) = map.map.get(&next) else {
+                    continue;
+                };
+
+                let step = if next.0 != current.0 && next.1 != current.1 { 14 } else { 10 };
+                let new_g = current_g + step + 10 * profile.tile_cost(tile, self.obstacles.get(&next).copied().unwrap_or(0));
+
+                if g_score.get(&next).map_or(true, |&g| new_g < g) {
+                    g_score.insert(next, new_g);
+                    came_from.insert(next, current);
+                    open.push(Reverse((new_g + heuristic(next), next)));
+                }
+            }
+        }
+
+        None
+    }
+
+    //карта Дейкстры от целей: сколько стоит дойти из тайла до ближайшей цели
+    pub fn dijkstra_map(
+        &self,
+        map: &Map,
+        sources: &[(u16, u16)],
+        profile: &PathCostProfile,
+    ) -> HashMap<(u16, u16), u32> {
+        let mut distances: HashMap<(u16, u16), u32> = HashMap::new();
+        let mut open = BinaryHeap::new();
+
+        for source in sources {
+            distances.insert(*source, 0);
+            open.push(Reverse((0, *source)));
+        }
+
+        while let Some(Reverse((distance, current))) = open.pop() {
+            if distances.get(&current).is_some_and(|&best| distance > best) {
+                continue;
+            }
+
+            //идем от цели назад: шаг из next в current стоит цены тайла current
+            let Some(tile) = map.map.get(&current) else {
+                continue;
+            };
+            let tile_cost = 10 * profile.tile_cost(tile, self.obstacles.get(&current).copied().unwrap_or(0));
+
+            for next in self.neighbours(current, map, profile, sources) {
+                let step = if next.0 != current.0 && next.1 != current.1 { 14 } else { 10 };
+                let new_distance = distance + step + tile_cost;
+
+                if distances.get(&next).map_or(true, |&best| new_distance < best) {
+                    distances.insert(next, new_distance);
+                    open.push(Reverse((new_distance, next)));
+                }
+            }
+        }
+
+        distances
+    }
+
+    fn relink_tile(&mut self, tile: (u16, u16), map: &Map) {
+        if !is_floor(map, tile) {
+            return;
+        }
+
+        add_node_list(self, tile, node_at(tile));
+
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                let x = tile.0 as i32 + dx;
+                let y = tile.1 as i32 + dy;
+                if (dx == 0 && dy == 0) || x < 0 || y < 0 {
+                    continue;
+                }
+
+                let neighbour = (x as u16, y as u16);
+                if !is_floor(map, neighbour) {
+                    continue;
+                }
+                //по диагонали ходим, только если оба соседних тайла не стены
+                if dx != 0 && dy != 0
+                    && (!is_floor(map, (neighbour.0, tile.1)) || !is_floor(map, (tile.0, neighbour.1)))
+                {
+                    continue;
+                }
+
+                add_node_to_list(self, tile, node_at(neighbour));
+            }
+        }
     }
 }
+
 fn change_target_appeared<Before: Component, Filter1: Component, Filter2: Component>(
     mut mob_query: Query<Entity, (With<Before>, With<Pathfinder>, With<Filter2>)>,
     target_query: Query<Entity, (Changed<Filter1>, With<Filter1>)>,
@@ -200,18 +597,26 @@ fn add_node_to_list(slf: &mut Graph, tup: (u16, u16), node: Node) {
     slf.adj_list.insert(tup, vec);
 }
 
-//получение ноды где находится объект с помощью математики
-fn get_node_where_object_is(slf: &mut Graph, vec: &Vec2) -> Node {
-    let (i, j) = safe_get_pos(*vec, slf);
-
-    for k in slf.adj_list[&(i, j)].clone() {
-        if k.position == Vec2::new(vec.x, vec.y) {
-            return k.clone();
+fn remove_counted(counts: &mut HashMap<(u16, u16), u16>, tile: (u16, u16)) {
+    if let Some(count) = counts.get_mut(&tile) {
+        *count -= 1;
+        if *count == 0 {
+            counts.remove(&tile);
         }
     }
+}
+
+fn is_floor(map: &Map, tile: (u16, u16)) -> bool {
+    map.map
+        .get(&tile)
+        .is_some_and(|t| t.tiletype == TileType::Floor)
+}
 
-    return slf.adj_list[&(i, j)][0].clone();
-    //берем коорды, конвертим их в примерную вершину, в примерном векторе по идее - нужный нод нулевой, нужно посмотреть еще раз
+fn node_at(tile: (u16, u16)) -> Node {
+    Node::new(
+        TileType::Floor,
+        Vec2::new(tile.0 as f32 * ROOM_SIZE as f32, tile.1 as f32 * ROOM_SIZE as f32),
+    )
 }
 
 fn safe_get_pos(vec: Vec2, slf: &Graph) -> (u16, u16) {
@@ -240,14 +645,6 @@ fn safe_get_pos(vec: Vec2, slf: &Graph) -> (u16, u16) {
 
     return (best.x.floor() as u16, best.y.floor() as u16);
 }
-// получение массива нодов, в функции удаляется нод с которым смежны остальные в массиве
-fn get_list(slf: &Graph, vec: Vec2) -> Vec<Node> {
-    let (i, j) = safe_get_pos(vec, slf);
-
-    let ans = slf.adj_list[&(i, j)].clone();
-    return ans;
-}
-
 //function to avoid diagonal movement through walls
 fn sub_grid_new(grid: Vec<Vec<TileType>>, i: usize, j: usize) -> Vec<Vec<u8>> {
     let mut sub_grid: Vec<Vec<u8>> = vec![vec![0, 0, 0], vec![0, 0, 0], vec![0, 0, 0]];
@@ -275,42 +672,6 @@ fn sub_grid_new(grid: Vec<Vec<TileType>>, i: usize, j: usize) -> Vec<Vec<u8>> {
 
     return sub_grid;
 }
-// Функция возвращает путь, который хранится в мапе
-fn build_path(node: CostNode) -> Vec<(u16, u16)> {
-    let mut path: Vec<(u16, u16)> = Vec::new();
-    for i in node.path {
-        path.push(i);
-    }
-    path.remove(0); // удаляем точку, в которой уже стоит моб на момент создания пути
-    return path;
-}
-//функция выбора нода, эвристика, учитывает только расстояние до цели и длину пути,
-//можно добавить что-то, например кастомные тайлы пола, по которым не будут хотеть ходить мобы
-fn pick_node(reachable: Vec<Node>, goal_node: Node, cost_grid: Vec<Vec<CostNode>>) -> Node {
-    let mut min_cost: usize = usize::MAX;
-    let mut best_node: Node = Node::new(TileType::Floor, Vec2::new(0., 0.));
-
-    let path_coef = 100; // можно покрутить, зависимость от кол-ва пройденных нодов
-
-    for node in reachable {
-        //цена пути (учет кол-ва пройденных нодов, можно здесь подумать покрутить параметры)
-        let cost_to_start: usize = path_coef
-            * cost_grid[((node.position.x) / ROOM_SIZE as f32) as usize]
-                [((node.position.y) / ROOM_SIZE as f32) as usize]
-                .cost as usize;
-
-        //добавление цены за дистанцию до нода
-        let cost_node_to_goal: usize = distance(&node, &goal_node);
-        let total_cost: usize = cost_to_start + cost_node_to_goal;
-
-        if min_cost > total_cost {
-            min_cost = total_cost;
-            best_node = node;
-        }
-    }
-    return best_node;
-}
-
 //функция расчета дистанции для функции выбора нода
 fn distance(node1: &Node, node2: &Node) -> usize {
     return std::cmp::min(
@@ -323,6 +684,47 @@ fn distance(node1: &Node, node2: &Node) -> usize {
 }
 
 //Системы поиска пути и построения графа
+//применяем изменения графа и сразу заставляем пересчитать путь тех мобов, чей маршрут идет через
+//измененные тайлы, поля потока пересоберутся сами
+fn update_graph(
+    mut ev_update_graph: EventReader<UpdateGraphEvent>,
+    mut graph_search: ResMut<Graph>,
+    mut mob_map: ResMut<Map>,
+    mut pathfinder_query: Query<&mut Pathfinder>,
+) {
+    if ev_update_graph.is_empty() {
+        return;
+    }
+
+    let mut changed: Vec<(u16, u16)> = vec![];
+
+    for ev in ev_update_graph.read() {
+        changed.push(ev.tile);
+
+        match ev.change {
+            GraphChange::Unblock => graph_search.unblock_tile(ev.tile, &mut mob_map),
+            GraphChange::AddBlocker => graph_search.add_blocker(ev.tile),
+            GraphChange::RemoveBlocker => graph_search.remove_blocker(ev.tile),
+            GraphChange::AddObstacle => graph_search.add_obstacle(ev.tile),
+            GraphChange::RemoveObstacle => graph_search.remove_obstacle(ev.tile),
+            //тайл уже поправлен в Map, достаточно пересчитать поля и пути через него
+            GraphChange::ClearHazard => {}
+        }
+    }
+    graph_search.bump_revision();
+
+    for mut pathfinder in pathfinder_query.iter_mut() {
+        if !pathfinder.path.iter().any(|tile| changed.contains(tile)) {
+            continue;
+        }
+
+        let duration = pathfinder.update_path_timer.duration();
+        pathfinder
+            .update_path_timer
+            .set_elapsed(duration.saturating_sub(Duration::from_millis(1)));
+    }
+}
+
 //Поиск пути с телепортом, строим прямую из возможной точки телепортации до игрока, если нет стен - телепортируемся
 fn pathfinding_with_tp(
     player_query: Query<&Transform, With<Player>>,
@@ -385,6 +787,7 @@ fn pathfinding_with_tp(
                             }
 
                             if mob_map.map.get(&(i, j)).unwrap().tiletype != TileType::Floor // skip iter if tile isnt floor
+                            || mob_map.map.get(&(i, j)).unwrap().cost() > 0 // skip iter if tile is hazardous
                             || mob_map.map.get(&(i, j)).unwrap().mob_count != 0
                             // skip iter if there are mobs
                             {
@@ -433,7 +836,7 @@ fn pathfinding_with_tp(
         }
     }
 }
-//система Pathifinding-а, самописный A* используя средства беви, перекидываю граф, очереди мобов и игрока, после чего ищу от позиций мобов путь до игрока
+//система Pathifinding-а, A* на бинарной куче по графу, цена тайлов берется из Map с учетом профиля моба
 fn a_pathfinding<
     T: Component,
     P: Component,
@@ -442,19 +845,15 @@ fn a_pathfinding<
 >(
     target_query: Query<(Entity, &Transform), (With<T>, Without<FilterTarget>)>, //don't use globalTransform, please
     mut pathfinder_query: Query<
-        (Entity, &Transform, &mut Pathfinder),
-        (
-            Without<Teleport>,
-            With<P>,
-            Without<FilterPathfinder>,
-            Without<Phasing>,
-        ),
+        (Entity, &Transform, &mut Pathfinder, Option<&PathCostProfile>),
+        (Without<Teleport>, With<P>, Without<FilterPathfinder>),
     >,
-    mut graph_search: ResMut<Graph>,
+    graph_search: Res<Graph>,
+    mob_map: Res<Map>,
     time: Res<Time>,
     mut commands: Commands,
 ) {
-    for (pathfinder_e, pathfinder_transform, mut pathfinder) in pathfinder_query.iter_mut() {
+    for (pathfinder_e, pathfinder_transform, mut pathfinder, profile) in pathfinder_query.iter_mut() {
         pathfinder.update_path_timer.tick(time.delta());
         if pathfinder.update_path_timer.just_finished() {
             //получаем позицию игрока
@@ -481,156 +880,158 @@ fn a_pathfinding<
                 }
                 target = sorted_targets[1].1;
             }
-            //создаем нод где стоит моб
-            let start_node = Node::new(
-                TileType::Floor,
-                Vec2::new(
-                    pathfinder_transform.translation.x.floor(),
-                    pathfinder_transform.translation.y.floor(),
-                ),
-            );
 
-            let mut field: Vec<Vec<CostNode>> = Vec::new();
+            let profile = profile.copied().unwrap_or_default();
 
-            //задаем поле с ценами, ставим их как большое число, чтобы потом пересчитывать во время работы алгоритма
-            for i in 0..ROOM_SIZE {
-                field.push(Vec::new());
-                for _ in 0..ROOM_SIZE {
-                    field[i as usize].push(CostNode::new(u8::MAX as u16));
-                }
+            let start = graph_search.nearest_tile(pathfinder_transform.translation.truncate());
+            let goal = graph_search.nearest_tile(target.translation.truncate());
+
+            if let Some(path) = graph_search.find_path(&mob_map, start, goal, &profile) {
+                pathfinder.path = path;
             }
+        }
+    }
+}
 
-            //делаем на основе позиции игрока goal_node
-            let goal_node = get_node_where_object_is(
-                &mut graph_search,
-                &Vec2::new(target.translation.x.floor(), target.translation.y.floor()),
-            );
+//пересчитываем поле, только если цель перешла на другой тайл или граф изменился.
+//is_changed тут не годится: Map и Graph берут через ResMut и системы, которые их только читают
+fn update_flow_field<T: Component, FilterTarget: Component>(
+    mut field: ResMut<FlowField<T>>,
+    target_query: Query<&Transform, (With<T>, Without<FilterTarget>)>,
+    graph_search: Res<Graph>,
+    mob_map: Res<Map>,
+) {
+    let mut targets: Vec<(u16, u16)> = target_query
+        .iter()
+        .map(|transform| graph_search.nearest_tile(transform.translation.truncate()))
+        .collect();
+    targets.sort();
+    targets.dedup();
+
+    if targets == field.targets && field.revision == Some(graph_search.revision()) {
+        return;
+    }
 
-            //задаем нод где стоит моб нулевой ценой
-            field[pathfinder_transform.translation.x.floor() as usize / ROOM_SIZE as usize]
-                [pathfinder_transform.translation.y.floor() as usize / ROOM_SIZE as usize]
-                .change_cost(0);
+    field.rebuild(&graph_search, &mob_map, targets);
+}
 
-            //создаем хэшмапы для пройденных нодов и доступных
-            let mut reachable = HashMap::new();
-            let mut explored = HashMap::new();
+//мобы каждый тик берут следующий шаг из общего поля вместо своего A*.
+//Поле собрано по профилю по умолчанию, мобы со своим профилем (огненные элементали) ищут путь сами
+fn follow_flow_field<T: Component, P: Component, FilterPathfinder: Component>(
+    field: Res<FlowField<T>>,
+    graph_search: Res<Graph>,
+    mob_map: Res<Map>,
+    mut pathfinder_query: Query<
+        (Entity, &Transform, &mut Pathfinder, Option<&PathCostProfile>),
+        (Without<Teleport>, With<P>, Without<FilterPathfinder>),
+    >,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (pathfinder_e, pathfinder_transform, mut pathfinder, profile) in pathfinder_query.iter_mut() {
+        if field.targets.is_empty() {
+            commands.entity(pathfinder_e).insert(Done::Failure);
+            continue;
+        }
 
-            //добавляем нод с мобом в хэшмапу для доступных нодов
-            reachable.insert(
-                (
-                    start_node.position.x as usize,
-                    start_node.position.y as usize,
-                ),
-                start_node,
-            );
-
-            while reachable.len() > 0 {
-                //функция выбора нода описана ниже
-                let node: Node = pick_node(
-                    reachable.values().cloned().collect(),
-                    goal_node.clone(),
-                    field.clone(),
-                );
+        if let Some(profile) = profile.filter(|profile| **profile != PathCostProfile::default()) {
+            pathfinder.update_path_timer.tick(time.delta());
+            if !pathfinder.update_path_timer.just_finished() {
+                continue;
+            }
 
-                //нужно придумать что делать если нашли целевой нод, в теории путь можно сохранять в структуру к мобам?
-                if node == goal_node {
-                    field[(goal_node.position.x / ROOM_SIZE as f32) as usize]
-                        [(goal_node.position.y / ROOM_SIZE as f32) as usize]
-                        .path
-                        .push_back((
-                            ((goal_node.position.x) / ROOM_SIZE as f32) as u16,
-                            ((goal_node.position.y) / ROOM_SIZE as f32) as u16,
-                        ));
-
-                    pathfinder.path = build_path(
-                        field[((goal_node.position.x) / ROOM_SIZE as f32) as usize]
-                            [((goal_node.position.y) / ROOM_SIZE as f32) as usize]
-                            .clone(),
-                    );
-                    break;
-                }
+            let start = graph_search.nearest_tile(pathfinder_transform.translation.truncate());
+            let Some(goal) = field.targets.iter().copied().min_by_key(|target| {
+                let dx = target.0 as i32 - start.0 as i32;
+                let dy = target.1 as i32 - start.1 as i32;
+                dx * dx + dy * dy
+            }) else {
+                continue;
+            };
+
+            if let Some(path) = graph_search.find_path(&mob_map, start, goal, profile) {
+                pathfinder.path = path;
+            }
+            continue;
+        }
 
-                //записываем что мы прошли нод и убираем из доступных
-                reachable.remove(&(node.position.x as usize, node.position.y as usize));
-                explored.insert(
-                    (node.position.x as usize, node.position.y as usize),
-                    node.clone(),
-                );
+        //пока моб не дошел до центра следующего тайла, шаг не меняем, чтобы не срезать углы
+        if pathfinder.path.len() == 1 {
+            continue;
+        }
 
-                //берем ноды в которые можно прийти
-                let new_reachable_potential = get_list(&mut graph_search, node.position);
-                let mut new_reachable: Vec<Node> = Vec::new();
-
-                //записываем ноды, которые не были еще посещены и записываем их в new_reachable
-                for potential in 0..new_reachable_potential.len() {
-                    match explored.get(&(
-                        new_reachable_potential[potential].position.x as usize,
-                        new_reachable_potential[potential].position.y as usize,
-                    )) {
-                        Some(_) => {}
-                        None => {
-                            new_reachable.push(new_reachable_potential[potential].clone());
-                        }
-                    }
-                }
+        let tile = graph_search.nearest_tile(pathfinder_transform.translation.truncate());
 
-                //проходим по new_reachable, если нод уже есть в reachable, не добавляем
-                for adjacent in new_reachable {
-                    match reachable
-                        .get(&(adjacent.position.x as usize, adjacent.position.y as usize))
-                    {
-                        Some(_) => {}
-                        None => {
-                            reachable.insert(
-                                (adjacent.position.x as usize, adjacent.position.y as usize),
-                                adjacent.clone(),
-                            );
-                        }
-                    }
+        pathfinder.path = match field.next_step(&graph_search, &mob_map, tile) {
+            Some(step) => vec![step],
+            None => vec![],
+        };
+    }
+}
 
-                    //если цена нода больше чем цена текущего нода + 1, меняем кост в field, перерасчитываем путь
-                    if field[((node.position.x) / ROOM_SIZE as f32) as usize]
-                        [((node.position.y) / ROOM_SIZE as f32) as usize]
-                        .cost
-                        + 1
-                        < field[(adjacent.position.x / ROOM_SIZE as f32) as usize]
-                            [(adjacent.position.y / ROOM_SIZE as f32) as usize]
-                            .cost
-                    {
-                        field[((adjacent.position.x) / ROOM_SIZE as f32) as usize]
-                            [((adjacent.position.y) / ROOM_SIZE as f32) as usize]
-                            .path = field[((node.position.x) / ROOM_SIZE as f32) as usize]
-                            [((node.position.y) / ROOM_SIZE as f32) as usize]
-                            .path
-                            .clone();
-
-                        let k = (
-                            ((node.position.x) / ROOM_SIZE as f32) as u16,
-                            ((node.position.y) / ROOM_SIZE as f32) as u16,
-                        );
-
-                        field[((adjacent.position.x) / ROOM_SIZE as f32) as usize]
-                            [((adjacent.position.y) / ROOM_SIZE as f32) as usize]
-                            .path
-                            .push_back(k);
-
-                        field[((adjacent.position.x) / ROOM_SIZE as f32) as usize]
-                            [((adjacent.position.y) / ROOM_SIZE as f32) as usize]
-                            .cost = field[((node.position.x) / ROOM_SIZE as f32) as usize]
-                            [((node.position.y) / ROOM_SIZE as f32) as usize]
-                            .cost
-                            + 1;
-                    }
-                }
-            }
+//сравнение A* для каждого моба с одним полем потока на всех, вызывается из консоли: bench path <кол-во мобов>
+fn benchmark_pathfinding(
+    mut ev_benchmark: EventReader<BenchmarkPathfindingEvent>,
+    player_query: Query<&Transform, With<Player>>,
+    graph_search: Res<Graph>,
+    mob_map: Res<Map>,
+) {
+    for ev in ev_benchmark.read() {
+        let Ok(player_transform) = player_query.get_single() else {
+            continue;
+        };
+
+        let tiles: Vec<(u16, u16)> = graph_search.adj_list.keys().copied().collect();
+        if tiles.is_empty() {
+            continue;
+        }
+
+        let mut rng = rand::thread_rng();
+        let starts: Vec<(u16, u16)> = (0..ev.mobs)
+            .map(|_| tiles[rng.gen_range(0..tiles.len())])
+            .collect();
+
+        let goal = graph_search.nearest_tile(player_transform.translation.truncate());
+        let profile = PathCostProfile::default();
+
+        let timer = Instant::now();
+        for start in starts.iter() {
+            graph_search.find_path(&mob_map, *start, goal, &profile);
         }
+        let a_star_time = timer.elapsed();
+
+        let timer = Instant::now();
+        let mut field = FlowField::<Player>::default();
+        field.rebuild(&graph_search, &mob_map, vec![goal]);
+        for start in starts.iter() {
+            field.next_step(&graph_search, &mob_map, *start);
+        }
+        let flow_field_time = timer.elapsed();
+
+        info!(
+            "pathfinding benchmark, {} mobs: A* per mob - {:?}, shared flow field - {:?}",
+            ev.mobs, a_star_time, flow_field_time
+        );
     }
 }
 
 //система создания графа как листа смежности, граф идет как ресурс, мб стоит проверить, что с ним все нормально и он меняется и сохраняется
 pub fn create_new_graph(room: Res<LevelGenerator>, mut graph_search: ResMut<Graph>) {
     //берем мапу с LevelGenerator, потом надо будет вынести ее оттуда в отдельную структуру
-    let grid = room.grid.clone();
+    let mut grid = room.grid.clone();
+
+    //чистим граф с прошлого уровня
+    graph_search.adj_list.clear();
+    graph_search.blockers.clear();
+    graph_search.obstacles.clear();
+    graph_search.bump_revision();
+
+    //тайная комната попадет в граф только после того, как сломают стену
+    if let Some(secret_room) = &room.secret_room {
+        for tile in secret_room.tiles.iter() {
+            grid[tile.0 as usize][tile.1 as usize] = TileType::Empty;
+        }
+    }
 
     for i in 1..grid.len() - 1 {
         for j in 1..grid[i].len() - 1 {
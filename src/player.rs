@@ -50,6 +50,8 @@ pub struct PlayerStats {
     pub vampirism: i32,
     pub health_regen: i32,
     pub spell_cast_hp_fee: i32,
+    pub mana_cost_mult: f32,
+    pub cooldown_mult: f32,
     pub blind_rage_bonus: u32,
     pub element_damage_percent: [f32; 5],
 }
@@ -72,6 +74,8 @@ impl Default for PlayerStats {
             vampirism: 0,
             health_regen: 0,
             spell_cast_hp_fee: 0,
+            mana_cost_mult: 1.,
+            cooldown_mult: 1.,
             blind_rage_bonus: 0,
             element_damage_percent: [0., 0., 0., 0., 0.],
         }
@@ -1,29 +1,57 @@
 use core::f32;
-use std::f32::consts::PI;
+use std::{
+    collections::{HashMap, HashSet},
+    f32::consts::PI,
+};
 
 use avian2d::prelude::*;
 use bevy::prelude::*;
 use rand::Rng;
 
 use crate::{
-    blank_spell::Blank, elements::ElementType, friend::Friend, gamemap::Wall, mobs::Enemy,
-    particles::SpawnParticlesEvent, shield_spell::Shield, utils::Lifetime, GameLayer, GameState,
+    blank_spell::Blank,
+    burning::Burning,
+    elements::ElementType,
+    friend::Friend,
+    gamemap::Wall,
+    mobs::Enemy,
+    particles::SpawnParticlesEvent,
+    player::Player,
+    shield_spell::Shield,
+    stun::Stun,
+    utils::despawn_all_with,
+    GameLayer, GameState,
 };
 
 pub struct ProjectilePlugin;
 
 impl Plugin for ProjectilePlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<SpawnProjectileEvent>().add_systems(
-            Update,
-            (
-                spawn_projectile,
-                move_projectile,
-                hit_walls,
-                hit_shield::<Enemy, Friendly>,
-                hit_shield::<Friend, Hostile>,
-            ).run_if(in_state(GameState::InGame)),
-        );
+        app.add_event::<SpawnProjectileEvent>()
+            .add_event::<ProjectileImpactEvent>()
+            .init_resource::<ProjectilePool>()
+            .add_systems(
+                Update,
+                (
+                    spawn_projectile,
+                    move_projectile,
+                    steer_homing_projectile,
+                    hit_walls,
+                    hit_shield::<Enemy, Friendly>,
+                    hit_shield::<Friend, Hostile>,
+                    resolve_impacts,
+                    expire_projectiles,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                OnExit(GameState::InGame),
+                (despawn_all_with::<PoolKey>, reset_projectile_pool),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                (despawn_all_with::<PoolKey>, reset_projectile_pool),
+            );
     }
 }
 
@@ -41,6 +69,87 @@ pub enum Trajectory {
         pivot: Vec2,
         counter_clockwise: bool,
     },
+    //синусоида поперек направления полета, frequency - колебаний в секунду
+    Wave {
+        amplitude: f32,
+        frequency: f32,
+    },
+    //через return_after секунд летит обратно к тому, кто выпустил
+    Boomerang {
+        return_after: f32,
+    },
+    //разгоняется на accel в секунду, пока не достигнет max_speed
+    Accelerating {
+        accel: f32,
+        max_speed: f32,
+    },
+    //раскручивается от точки выстрела, радиус растет со скоростью снаряда, turn_rate - радиан в секунду
+    Spiral {
+        turn_rate: f32,
+    },
+}
+
+//модификаторы складываются: каждый вешает на снаряд свой компонент
+#[derive(Clone, Copy)]
+pub enum ProjectileModifier {
+    //доворачивает к ближайшей цели, turn_rate - радиан в секунду
+    Homing { turn_rate: f32 },
+    //пролетает насквозь еще через targets целей
+    Piercing { targets: u32 },
+    //отскакивает от стен bounces раз
+    Bouncing { bounces: u32 },
+    //после попадания перескакивает на ближайшую цель в radius, урон каждый раз множится на falloff
+    Chaining { jumps: u32, radius: f32, falloff: f32 },
+    //там, где снаряд пропал, разлетается на count осколков в секторе spread
+    Splitting { count: u32, spread: f32 },
+    //цель, в которую попал снаряд, замерзает на duration секунд
+    Freezing { duration: f32 },
+    //цель, в которую попал снаряд, горит duration секунд, damage за тик
+    Igniting { damage: u32, duration: f32 },
+}
+
+#[derive(Component)]
+pub struct Homing {
+    pub turn_rate: f32,
+}
+
+#[derive(Component)]
+pub struct Piercing {
+    pub remaining: u32,
+}
+
+#[derive(Component)]
+pub struct Bouncing {
+    pub remaining: u32,
+}
+
+#[derive(Component)]
+pub struct Freezing {
+    pub duration: f32,
+}
+
+#[derive(Component)]
+pub struct Igniting {
+    pub damage: u32,
+    pub duration: f32,
+}
+
+#[derive(Component)]
+pub struct Chaining {
+    pub jumps: u32,
+    pub radius: f32,
+    pub falloff: f32,
+}
+
+//осколкам нужно то же, что и событию спавна
+#[derive(Component)]
+pub struct Splitting {
+    pub count: u32,
+    pub spread: f32,
+    texture_path: String,
+    color: Color,
+    collider_radius: f32,
+    can_go_through_walls: bool,
 }
 
 #[allow(dead_code)]
@@ -52,6 +161,106 @@ pub struct Projectile {
     pub speed: f32,
     pub damage: u32,
     pub element: ElementType,
+    pub hit: Vec<Entity>, //в кого уже попал, повторно не бьет
+    pub spent: bool,      //попадание засчитано, resolve_impacts решает, что дальше
+    pub origin: Vec2,     //откуда вылетел
+    pub elapsed: f32,     //сколько летит
+}
+
+impl Projectile {
+    pub fn can_hit(&self, target: Entity) -> bool {
+        !self.spent && !self.hit.contains(&target)
+    }
+
+    pub fn register_hit(&mut self, target: Entity) {
+        self.hit.push(target);
+        self.spent = true;
+    }
+}
+
+//сколько живет снаряд, если ни во что не попал
+const PROJECTILE_LIFETIME: f32 = 5.;
+
+//снаряды не удаляются, а прячутся и переиспользуются, ключ - текстура
+#[derive(Resource, Default)]
+pub struct ProjectilePool {
+    free: HashMap<String, Vec<Entity>>,
+}
+
+impl ProjectilePool {
+    //на всякий случай проверяем, что entity из пула еще живая
+    fn take(
+        &mut self,
+        key: &str,
+        pooled_query: &Query<(), (With<PoolKey>, Without<Projectile>)>,
+    ) -> Option<Entity> {
+        let free = self.free.get_mut(key)?;
+
+        while let Some(entity) = free.pop() {
+            if pooled_query.contains(entity) {
+                return Some(entity);
+            }
+        }
+
+        None
+    }
+
+    pub fn free_count(&self) -> usize {
+        self.free.values().map(|free| free.len()).sum()
+    }
+}
+
+#[derive(Component)]
+pub struct PoolKey(String);
+
+//снаряды пула удаляются вместе с уровнем, старые entity в пуле не нужны
+fn reset_projectile_pool(mut pool: ResMut<ProjectilePool>) {
+    pool.free.clear();
+}
+
+//вместо despawn для снарядов: commands.entity(proj_e).add(release_projectile)
+pub fn release_projectile(entity: Entity, world: &mut World) {
+    let Some(mut projectile) = world.get_entity_mut(entity) else {
+        return;
+    };
+
+    //уже в пуле
+    if !projectile.contains::<Projectile>() {
+        return;
+    }
+
+    let Some(key) = projectile.get::<PoolKey>().map(|key| key.0.clone()) else {
+        projectile.despawn();
+        return;
+    };
+
+    projectile.remove::<(
+        Projectile,
+        Friendly,
+        Hostile,
+        Homing,
+        Piercing,
+        Bouncing,
+        Chaining,
+        Splitting,
+        Freezing,
+        Igniting,
+    )>();
+    projectile.insert((Visibility::Hidden, CollisionLayers::NONE));
+
+    world
+        .resource_mut::<ProjectilePool>()
+        .free
+        .entry(key)
+        .or_default()
+        .push(entity);
+}
+
+//снаряд попал в цель (target) или в стену (None)
+#[derive(Event)]
+pub struct ProjectileImpactEvent {
+    pub projectile: Entity,
+    pub target: Option<Entity>,
 }
 
 #[derive(Bundle)]
@@ -74,6 +283,10 @@ impl Default for ProjectileBundle {
                 speed: 100.0,
                 damage: 100,
                 element: ElementType::Air,
+                hit: vec![],
+                spent: false,
+                origin: Vec2::ZERO,
+                elapsed: 0.,
             },
             collider: Collider::circle(8.0),
             collision_layers: CollisionLayers::new(
@@ -105,19 +318,22 @@ pub struct SpawnProjectileEvent {
     pub element: ElementType,
     pub is_friendly: bool,
     pub can_go_through_walls: bool,
+    pub modifiers: Vec<ProjectileModifier>,
 }
 
 fn spawn_projectile(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    mut pool: ResMut<ProjectilePool>,
+    pooled_query: Query<(), (With<PoolKey>, Without<Projectile>)>,
     mut ev_projectile_spawn: EventReader<SpawnProjectileEvent>,
 ) {
     for ev in ev_projectile_spawn.read() {
         let angle = match ev.trajectory {
-            Trajectory::Straight => ev.angle,
             Trajectory::Radial { pivot, .. } => {
                 (ev.translation.truncate() - pivot).normalize().to_angle()
             }
+            _ => ev.angle,
         };
         let collision_layers;
         if ev.can_go_through_walls {
@@ -145,7 +361,7 @@ fn spawn_projectile(
             );
         }
 
-        let mut projectile = commands.spawn(ProjectileBundle {
+        let bundle = ProjectileBundle {
             sprite: SpriteBundle {
                 transform: Transform {
                     translation: ev.translation,
@@ -167,11 +383,29 @@ fn spawn_projectile(
                 speed: ev.speed,
                 damage: ev.damage,
                 element: ev.element,
+                hit: vec![],
+                spent: false,
+                origin: ev.translation.truncate(),
+                elapsed: 0.,
             },
             collider: Collider::circle(ev.collider_radius),
             collision_layers: collision_layers,
             ..default()
-        });
+        };
+
+        //бандл целиком перезаписывает все, что осталось от прошлого выстрела
+        let mut projectile = match pool.take(&ev.texture_path, &pooled_query) {
+            Some(pooled_e) => {
+                let mut projectile = commands.entity(pooled_e);
+                projectile.insert(bundle);
+                projectile
+            }
+            None => {
+                let mut projectile = commands.spawn(bundle);
+                projectile.insert(PoolKey(ev.texture_path.clone()));
+                projectile
+            }
+        };
 
         if ev.is_friendly {
             //check which flag to add
@@ -180,15 +414,62 @@ fn spawn_projectile(
             projectile.insert(Hostile);
         }
 
-        projectile.insert(Lifetime::new(5.0));
+        for modifier in ev.modifiers.iter() {
+            match *modifier {
+                ProjectileModifier::Homing { turn_rate } => {
+                    projectile.insert(Homing { turn_rate });
+                }
+                ProjectileModifier::Piercing { targets } => {
+                    projectile.insert(Piercing { remaining: targets });
+                }
+                ProjectileModifier::Bouncing { bounces } => {
+                    projectile.insert(Bouncing { remaining: bounces });
+                }
+                ProjectileModifier::Chaining {
+                    jumps,
+                    radius,
+                    falloff,
+                } => {
+                    projectile.insert(Chaining {
+                        jumps,
+                        radius,
+                        falloff,
+                    });
+                }
+                ProjectileModifier::Splitting { count, spread } => {
+                    projectile.insert(Splitting {
+                        count,
+                        spread,
+                        texture_path: ev.texture_path.clone(),
+                        color: ev.color,
+                        collider_radius: ev.collider_radius * 0.75,
+                        can_go_through_walls: ev.can_go_through_walls,
+                    });
+                }
+                ProjectileModifier::Freezing { duration } => {
+                    projectile.insert(Freezing { duration });
+                }
+                ProjectileModifier::Igniting { damage, duration } => {
+                    projectile.insert(Igniting { damage, duration });
+                }
+            }
+        }
     }
 }
 
 fn move_projectile(
-    mut projectile_query: Query<(&mut Transform, &mut Projectile)>,
+    mut commands: Commands,
+    mut projectile_query: Query<(Entity, &mut Transform, &mut Projectile, Has<Friendly>)>,
+    player_query: Query<&Transform, (With<Player>, Without<Projectile>)>,
     time: Res<Time>,
 ) {
-    for (mut projectile_transform, mut projectile) in projectile_query.iter_mut() {
+    let dt = time.delta_seconds();
+
+    for (proj_e, mut projectile_transform, mut projectile, is_friendly) in
+        projectile_query.iter_mut()
+    {
+        projectile.elapsed += dt;
+
         match projectile.trajectory {
             Trajectory::Straight => {
                 projectile_transform.translation +=
@@ -219,10 +500,117 @@ fn move_projectile(
                 projectile_transform.translation = next_pos.extend(0.);
                 }
             }
+            Trajectory::Wave {
+                amplitude,
+                frequency,
+            } => {
+                //поперечная скорость - производная от amplitude * sin(2PI * frequency * t),
+                //так волна не ломается, если снаряд отскочил или довернул
+                let omega = 2. * PI * frequency;
+                let side = projectile.direction.perp()
+                    * amplitude
+                    * omega
+                    * (omega * projectile.elapsed).cos();
+                let velocity = projectile.direction * projectile.speed + side;
+
+                projectile_transform.translation += velocity.extend(0.) * dt;
+                projectile_transform.rotation = Quat::from_rotation_z(velocity.to_angle());
+            }
+            Trajectory::Boomerang { return_after } => {
+                if projectile.elapsed >= return_after {
+                    //дружественные возвращаются к игроку, вражеские - туда, откуда вылетели
+                    let home = if is_friendly {
+                        player_query
+                            .get_single()
+                            .map(|transform| transform.translation.truncate())
+                            .unwrap_or(projectile.origin)
+                    } else {
+                        projectile.origin
+                    };
+                    let to_home = home - projectile_transform.translation.truncate();
+
+                    if to_home.length() <= projectile.speed * dt + 4. {
+                        commands.entity(proj_e).add(release_projectile);
+                        continue;
+                    }
+
+                    //на обратном пути может снова задеть тех же
+                    if projectile.elapsed - dt < return_after {
+                        projectile.hit.clear();
+                    }
+
+                    projectile.direction = to_home.normalize();
+                }
+
+                projectile_transform.translation +=
+                    (projectile.direction * projectile.speed * dt).extend(0.);
+                projectile_transform.rotate_z(dt * 4. * PI);
+            }
+            Trajectory::Accelerating { accel, max_speed } => {
+                projectile.speed = (projectile.speed + accel * dt).clamp(0., max_speed);
+
+                projectile_transform.translation +=
+                    (projectile.direction * projectile.speed * dt).extend(0.);
+            }
+            Trajectory::Spiral { turn_rate } => {
+                projectile.angle += turn_rate * dt;
+
+                let next_pos = projectile.origin
+                    + Vec2::from_angle(projectile.angle) * projectile.speed * projectile.elapsed;
+                let diff = next_pos - projectile_transform.translation.truncate();
+
+                if diff != Vec2::ZERO {
+                    projectile.direction = diff.normalize();
+                    projectile_transform.rotation = Quat::from_rotation_z(diff.to_angle());
+                }
+
+                projectile_transform.translation = next_pos.extend(0.);
+            }
         }
     }
 }
 
+//самонаводящиеся снаряды ищут ближайшую цель своей противоположной стороны
+fn steer_homing_projectile(
+    mut projectile_query: Query<(&mut Transform, &mut Projectile, &Homing, Has<Friendly>)>,
+    ally_query: Query<(Entity, &Transform), (Or<(With<Player>, With<Friend>)>, Without<Projectile>)>,
+    enemy_query: Query<(Entity, &Transform), (With<Enemy>, Without<Projectile>)>,
+    time: Res<Time>,
+) {
+    for (mut transform, mut projectile, homing, is_friendly) in projectile_query.iter_mut() {
+        let turn_rate = homing.turn_rate;
+        let pos = transform.translation.truncate();
+
+        let targets: Vec<(Entity, &Transform)> = if is_friendly {
+            enemy_query.iter().collect()
+        } else {
+            ally_query.iter().collect()
+        };
+
+        //тех, в кого уже попал, не преследует
+        let Some(target) = targets
+            .iter()
+            .filter(|(e, _)| !projectile.hit.contains(e))
+            .map(|(_, t)| t.translation.truncate())
+            .min_by(|a, b| a.distance_squared(pos).total_cmp(&b.distance_squared(pos)))
+        else {
+            continue;
+        };
+
+        let wanted = (target - pos).normalize_or_zero();
+        if wanted == Vec2::ZERO {
+            continue;
+        }
+
+        let diff = projectile.direction.angle_between(wanted);
+        let max_turn = turn_rate * time.delta_seconds();
+
+        projectile.direction = Vec2::from_angle(diff.clamp(-max_turn, max_turn)).rotate(projectile.direction);
+        projectile.angle = projectile.direction.to_angle();
+        transform.rotation = Quat::from_rotation_z(projectile.angle);
+    }
+}
+
 fn hit_shield<Side: Component, ProjSide: Component>(
     mut commands: Commands,
     projectile_query: Query<(Entity, &CollidingEntities, &Projectile, &Transform), With<ProjSide>>,
@@ -230,51 +618,227 @@ fn hit_shield<Side: Component, ProjSide: Component>(
     mut ev_spawn_particles: EventWriter<SpawnParticlesEvent>,
 ) {
     for (proj_e, colliding_e, projectile, transform) in projectile_query.iter() {
-        for shield_e in shield_query.iter() {
-            if colliding_e.contains(&shield_e) {
-                commands.entity(proj_e).despawn();
-
-                ev_spawn_particles.send(SpawnParticlesEvent {
-                    pattern: crate::particles::ParticlePattern::Burst {
-                        direction: -projectile.direction,
-                        distance: rand::thread_rng().gen_range(8.0..12.0),
-                        spread: PI / 3.,
-                    },
-                    position: transform.translation,
-                    amount: 3,
-                    color: projectile.element.color(),
-                    speed: 10.,
-                    rotate: false,
-                });
-            }
+        //проверяем только то, с чем снаряд реально соприкасается
+        if colliding_e.iter().any(|e| shield_query.contains(*e)) {
+            commands.entity(proj_e).add(release_projectile);
+
+            ev_spawn_particles.send(SpawnParticlesEvent {
+                pattern: crate::particles::ParticlePattern::Burst {
+                    direction: -projectile.direction,
+                    distance: rand::thread_rng().gen_range(8.0..12.0),
+                    spread: PI / 3.,
+                },
+                position: transform.translation,
+                amount: 3,
+                color: projectile.element.color(),
+                speed: 10.,
+                rotate: false,
+            });
         }
     }
 }
 
 fn hit_walls(
-    mut commands: Commands,
-    projectile_query: Query<(Entity, &CollidingEntities, &Projectile, &Transform)>,
-    wall_query: Query<Entity, With<Wall>>,
+    mut projectile_query: Query<(
+        Entity,
+        &CollidingEntities,
+        &mut Projectile,
+        &mut Transform,
+        Option<&mut Bouncing>,
+    )>,
+    wall_query: Query<&Transform, (With<Wall>, Without<Projectile>)>,
+    mut ev_impact: EventWriter<ProjectileImpactEvent>,
     mut ev_spawn_particles: EventWriter<SpawnParticlesEvent>,
 ) {
-    for (proj_e, colliding_e, projectile, transform) in projectile_query.iter() {
-        for wall_e in wall_query.iter() {
-            if colliding_e.contains(&wall_e) {
-                commands.entity(proj_e).despawn();
-
-                ev_spawn_particles.send(SpawnParticlesEvent {
-                    pattern: crate::particles::ParticlePattern::Burst {
-                        direction: -projectile.direction,
-                        distance: rand::thread_rng().gen_range(8.0..12.0),
-                        spread: PI / 3.,
-                    },
-                    position: transform.translation,
-                    amount: 3,
-                    color: projectile.element.color(),
-                    speed: 10.,
-                    rotate: false,
+    for (proj_e, colliding_e, mut projectile, mut transform, mut bouncing) in
+        projectile_query.iter_mut()
+    {
+        for wall_transform in colliding_e.iter().filter_map(|e| wall_query.get(*e).ok()) {
+            if projectile.spent {
+                continue;
+            }
+
+            ev_spawn_particles.send(SpawnParticlesEvent {
+                pattern: crate::particles::ParticlePattern::Burst {
+                    direction: -projectile.direction,
+                    distance: rand::thread_rng().gen_range(8.0..12.0),
+                    spread: PI / 3.,
+                },
+                position: transform.translation,
+                amount: 3,
+                color: projectile.element.color(),
+                speed: 10.,
+                rotate: false,
+            });
+
+            if let Some(bouncing) = bouncing.as_mut().filter(|b| b.remaining > 0) {
+                //стены - тайлы, нормаль берем по оси, с которой снаряд ближе к краю
+                let diff = (transform.translation - wall_transform.translation).truncate();
+                let normal = if diff.x.abs() > diff.y.abs() {
+                    Vec2::new(diff.x.signum(), 0.)
+                } else {
+                    Vec2::new(0., diff.y.signum())
+                };
+
+                //уже летит от стены - пока еще касается ее после прошлого отскока
+                if projectile.direction.dot(normal) >= 0. {
+                    continue;
+                }
+
+                bouncing.remaining -= 1;
+                let direction = projectile.direction;
+                projectile.direction = direction - 2. * direction.dot(normal) * normal;
+                projectile.angle = projectile.direction.to_angle();
+                transform.rotation = Quat::from_rotation_z(projectile.angle);
+                continue;
+            }
+
+            projectile.spent = true;
+            ev_impact.send(ProjectileImpactEvent {
+                projectile: proj_e,
+                target: None,
+            });
+        }
+    }
+}
+
+//что делать со снарядом после попадания: пробить, перескочить на следующую цель или пропасть
+fn resolve_impacts(
+    mut commands: Commands,
+    mut ev_impact: EventReader<ProjectileImpactEvent>,
+    mut projectile_query: Query<(
+        &mut Projectile,
+        &mut Transform,
+        Has<Friendly>,
+        Option<&mut Piercing>,
+        Option<&mut Chaining>,
+        Option<&Splitting>,
+        Option<&Freezing>,
+        Option<&Igniting>,
+    )>,
+    //замораживаются только те, кто умеет двигаться
+    freezable_query: Query<(), (With<LinearVelocity>, With<Sprite>, Without<Projectile>)>,
+    ally_query: Query<(Entity, &Transform), (Or<(With<Player>, With<Friend>)>, Without<Projectile>)>,
+    enemy_query: Query<(Entity, &Transform), (With<Enemy>, Without<Projectile>)>,
+    mut burning_query: Query<&mut Burning>,
+    mut ev_spawn_projectile: EventWriter<SpawnProjectileEvent>,
+) {
+    let mut despawned: HashSet<Entity> = HashSet::new();
+
+    for ev in ev_impact.read() {
+        if despawned.contains(&ev.projectile) {
+            continue;
+        }
+
+        let Ok((
+            mut projectile,
+            mut transform,
+            is_friendly,
+            piercing,
+            chaining,
+            splitting,
+            freezing,
+            igniting,
+        )) = projectile_query.get_mut(ev.projectile)
+        else {
+            continue;
+        };
+
+        if let (Some(target), Some(freezing)) = (ev.target, freezing) {
+            if freezable_query.contains(target) {
+                commands.entity(target).insert(Stun::new(freezing.duration));
+            }
+        }
+
+        //уже горящую цель не поджигаем второй раз, а продлеваем горение
+        if let (Some(target), Some(igniting)) = (ev.target, igniting) {
+            match burning_query.get_mut(target) {
+                Ok(mut burning) => burning.refresh(igniting.damage, igniting.duration),
+                Err(_) => {
+                    commands
+                        .entity(target)
+                        .insert(Burning::new(igniting.damage, igniting.duration));
+                }
+            }
+        }
+
+        if ev.target.is_some() {
+            if let Some(mut piercing) = piercing.filter(|p| p.remaining > 0) {
+                piercing.remaining -= 1;
+                projectile.spent = false;
+                continue;
+            }
+
+            if let Some(mut chaining) = chaining.filter(|c| c.jumps > 0) {
+                let pos = transform.translation.truncate();
+                let targets: Vec<(Entity, &Transform)> = if is_friendly {
+                    enemy_query.iter().collect()
+                } else {
+                    ally_query.iter().collect()
+                };
+
+                let next = targets
+                    .iter()
+                    .filter(|(e, _)| !projectile.hit.contains(e))
+                    .map(|(_, t)| t.translation.truncate())
+                    .filter(|t| t.distance(pos) <= chaining.radius)
+                    .min_by(|a, b| a.distance_squared(pos).total_cmp(&b.distance_squared(pos)));
+
+                if let Some(next) = next {
+                    chaining.jumps -= 1;
+                    projectile.damage = (projectile.damage as f32 * chaining.falloff).max(1.) as u32;
+                    projectile.direction = (next - pos).normalize_or_zero();
+                    projectile.angle = projectile.direction.to_angle();
+                    transform.rotation = Quat::from_rotation_z(projectile.angle);
+                    projectile.spent = false;
+                    continue;
+                }
+            }
+        }
+
+        if let Some(splitting) = splitting {
+            //от стены осколки летят обратно
+            let base = if ev.target.is_some() {
+                projectile.direction
+            } else {
+                -projectile.direction
+            };
+
+            for i in 0..splitting.count {
+                let offset = if splitting.count > 1 {
+                    -splitting.spread / 2. + splitting.spread * i as f32 / (splitting.count - 1) as f32
+                } else {
+                    0.
+                };
+                let dir = Vec2::from_angle(offset).rotate(base);
+
+                ev_spawn_projectile.send(SpawnProjectileEvent {
+                    texture_path: splitting.texture_path.clone(),
+                    color: splitting.color,
+                    //чуть вперед, чтобы осколок не попал в ту же цель
+                    translation: transform.translation + (dir * 16.).extend(0.),
+                    angle: dir.to_angle(),
+                    collider_radius: splitting.collider_radius,
+                    speed: projectile.speed,
+                    damage: (projectile.damage / 2).max(1),
+                    element: projectile.element,
+                    is_friendly,
+                    trajectory: Trajectory::Straight,
+                    can_go_through_walls: splitting.can_go_through_walls,
+                    modifiers: vec![],
                 });
             }
         }
+
+        despawned.insert(ev.projectile);
+        commands.entity(ev.projectile).add(release_projectile);
+    }
+}
+
+fn expire_projectiles(mut commands: Commands, projectile_query: Query<(Entity, &Projectile)>) {
+    for (proj_e, projectile) in projectile_query.iter() {
+        if projectile.elapsed >= PROJECTILE_LIFETIME {
+            commands.entity(proj_e).add(release_projectile);
+        }
     }
 }
@@ -0,0 +1,162 @@
+//нагрузочный прогон снарядов без окна и рендера:
+//cargo run --release -- --bench-projectiles
+//держит на арене CONCURRENT снарядов одновременно и пишет в лог время кадра и состояние пула
+use std::time::{Duration, Instant};
+
+use avian2d::prelude::*;
+use bevy::{log::LogPlugin, prelude::*, state::app::StatesPlugin, time::TimeUpdateStrategy};
+use rand::Rng;
+
+use crate::{
+    elements::ElementType,
+    gamemap::{Wall, TILE_SIZE},
+    particles::SpawnParticlesEvent,
+    projectile::{
+        PoolKey, Projectile, ProjectileModifier, ProjectilePlugin, ProjectilePool,
+        SpawnProjectileEvent, Trajectory,
+    },
+    GameLayer, GameState,
+};
+
+const CONCURRENT: usize = 2000;
+const FRAMES: usize = 600;
+//сторона арены в тайлах, стены по периметру и колонны внутри
+const ARENA: i32 = 32;
+
+const TEXTURES: [&str; 3] = [
+    "textures/small_fire.png",
+    "textures/fireball.png",
+    "textures/earthquake.png",
+];
+
+pub fn run() {
+    let mut app = App::new();
+
+    app.add_plugins((
+        MinimalPlugins,
+        LogPlugin::default(),
+        AssetPlugin::default(),
+        TransformPlugin,
+        HierarchyPlugin,
+        StatesPlugin,
+    ))
+    .init_asset::<Image>()
+    .init_asset::<Mesh>()
+    .add_plugins(PhysicsPlugins::default())
+    .insert_state(GameState::InGame)
+    .add_event::<SpawnParticlesEvent>()
+    .add_plugins(ProjectilePlugin)
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+        1. / 60.,
+    )));
+
+    app.finish();
+    app.cleanup();
+
+    spawn_arena(app.world_mut());
+
+    let mut frame_times: Vec<Duration> = Vec::with_capacity(FRAMES);
+
+    for _ in 0..FRAMES {
+        top_up_projectiles(app.world_mut());
+
+        let start = Instant::now();
+        app.update();
+        frame_times.push(start.elapsed());
+    }
+
+    report(app.world_mut(), frame_times);
+}
+
+fn spawn_arena(world: &mut World) {
+    for x in 0..ARENA {
+        for y in 0..ARENA {
+            let border = x == 0 || y == 0 || x == ARENA - 1 || y == ARENA - 1;
+            let pillar = x % 6 == 3 && y % 6 == 3;
+
+            if !border && !pillar {
+                continue;
+            }
+
+            world.spawn((
+                TransformBundle::from_transform(Transform::from_xyz(
+                    x as f32 * TILE_SIZE,
+                    y as f32 * TILE_SIZE,
+                    0.,
+                )),
+                RigidBody::Static,
+                Collider::rectangle(TILE_SIZE - 0.01, TILE_SIZE - 0.01),
+                CollisionLayers::new(
+                    GameLayer::Wall,
+                    [GameLayer::Enemy, GameLayer::Player, GameLayer::Projectile],
+                ),
+                Wall,
+            ));
+        }
+    }
+}
+
+//досыпаем снаряды до CONCURRENT, истекшие возвращаются в пул и берутся снова
+fn top_up_projectiles(world: &mut World) {
+    let alive = world.query::<&Projectile>().iter(world).count();
+    let mut rng = rand::thread_rng();
+
+    let center = Vec2::splat(ARENA as f32 * TILE_SIZE / 2.);
+
+    for i in alive..CONCURRENT {
+        let trajectory = match i % 4 {
+            0 => Trajectory::Straight,
+            1 => Trajectory::Wave {
+                amplitude: 12.,
+                frequency: 1.5,
+            },
+            2 => Trajectory::Accelerating {
+                accel: 120.,
+                max_speed: 260.,
+            },
+            _ => Trajectory::Spiral { turn_rate: 0.8 },
+        };
+
+        let offset = Vec2::new(rng.gen_range(-256.0..256.0), rng.gen_range(-256.0..256.0));
+
+        world.send_event(SpawnProjectileEvent {
+            texture_path: TEXTURES[i % TEXTURES.len()].to_string(),
+            color: Color::WHITE,
+            translation: (center + offset).extend(0.),
+            angle: rng.gen_range(0.0..std::f32::consts::TAU),
+            collider_radius: 6.,
+            speed: rng.gen_range(80.0..160.0),
+            damage: 10,
+            element: ElementType::Fire,
+            is_friendly: i % 2 == 0,
+            trajectory,
+            can_go_through_walls: false,
+            modifiers: vec![ProjectileModifier::Bouncing { bounces: 3 }],
+        });
+    }
+}
+
+fn report(world: &mut World, mut frame_times: Vec<Duration>) {
+    let total: Duration = frame_times.iter().sum();
+    let average = total / frame_times.len() as u32;
+
+    frame_times.sort();
+    let p99 = frame_times[frame_times.len() * 99 / 100];
+    let worst = frame_times[frame_times.len() - 1];
+
+    let alive = world.query::<&Projectile>().iter(world).count();
+    let created = world.query::<&PoolKey>().iter(world).count();
+    let free = world.resource::<ProjectilePool>().free_count();
+
+    info!(
+        "projectiles: {} alive, {} entities created, {} free in pool",
+        alive, created, free
+    );
+    info!(
+        "frame time over {} frames: avg {:.3} ms, p99 {:.3} ms, worst {:.3} ms",
+        FRAMES,
+        average.as_secs_f64() * 1000.,
+        p99.as_secs_f64() * 1000.,
+        worst.as_secs_f64() * 1000.,
+    );
+}
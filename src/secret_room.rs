@@ -0,0 +1,199 @@
+//тайные комнаты за треснувшими стенами, открываются заклинаниями земли или бланком
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::{
+    audio::PlayAudioEvent,
+    blank_spell::Blank,
+    camera::CameraShakeEvent,
+    chapter::ChapterManager,
+    elements::ElementType,
+    exp_tank::SpawnExpTankEvent,
+    gamemap::{Floor, LevelGenerator, TILE_SIZE},
+    item::{ItemDatabase, ItemDatabaseHandle, ItemType, SpawnItemEvent},
+    particles::{ParticlePattern, SpawnParticlesEvent},
+    pathfinding::{GraphChange, UpdateGraphEvent},
+    projectile::{Friendly, Projectile},
+    utils::get_random_index_with_weight,
+    GameState,
+};
+
+pub struct SecretRoomPlugin;
+
+impl Plugin for SecretRoomPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SecretRoomOpenedEvent>()
+            .add_systems(
+                Update,
+                (break_cracked_wall, open_secret_room, spawn_secret_loot)
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+#[derive(Component)]
+pub struct CrackedWall;
+
+#[derive(Component)]
+pub struct SecretRoomFloor;
+
+#[derive(Event)]
+pub struct SecretRoomOpenedEvent {
+    pub wall_pos: Vec3,
+}
+
+fn break_cracked_wall(
+    mut commands: Commands,
+    wall_query: Query<(Entity, &Transform), With<CrackedWall>>,
+    projectile_query: Query<(&CollidingEntities, &Projectile), With<Friendly>>,
+    blank_query: Query<&Transform, With<Blank>>,
+    mut ev_opened: EventWriter<SecretRoomOpenedEvent>,
+) {
+    //стены, в которые прямо сейчас попал снаряд земли
+    let hit_by_earth: Vec<Entity> = projectile_query
+        .iter()
+        .filter(|(_, projectile)| projectile.element == ElementType::Earth)
+        .flat_map(|(colliding_e, _)| colliding_e.iter().copied())
+        .filter(|e| wall_query.contains(*e))
+        .collect();
+
+    for (wall_e, wall_transform) in wall_query.iter() {
+        let hit_by_earth = hit_by_earth.contains(&wall_e);
+
+        //у бланка коллайдер радиусом 16, растет вместе со scale
+        let hit_by_blank = blank_query.iter().any(|blank_transform| {
+            blank_transform
+                .translation
+                .truncate()
+                .distance(wall_transform.translation.truncate())
+                <= 16. * blank_transform.scale.x + TILE_SIZE / 2.
+        });
+
+        if hit_by_earth || hit_by_blank {
+            commands.entity(wall_e).despawn_recursive();
+            ev_opened.send(SecretRoomOpenedEvent {
+                wall_pos: wall_transform.translation,
+            });
+        }
+    }
+}
+
+//превращаем стену в пол в мире, Map и граф для поиска пути обновляются событием
+fn open_secret_room(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    chapter_manager: Res<ChapterManager>,
+    room: Res<LevelGenerator>,
+    mut floor_query: Query<&mut Visibility, With<SecretRoomFloor>>,
+    mut ev_opened: EventReader<SecretRoomOpenedEvent>,
+    mut ev_update_graph: EventWriter<UpdateGraphEvent>,
+    mut ev_play_audio: EventWriter<PlayAudioEvent>,
+    mut ev_shake_camera: EventWriter<CameraShakeEvent>,
+    mut ev_spawn_particles: EventWriter<SpawnParticlesEvent>,
+) {
+    for ev in ev_opened.read() {
+        let Some(secret_room) = &room.secret_room else {
+            continue;
+        };
+
+        let texture = asset_server.load(format!("textures/t_floor_{}.png", chapter_manager.get_current_chapter()));
+        let layout = TextureAtlasLayout::from_grid(UVec2::splat(32), 4, 1, None, None);
+        let texture_atlas_layout = texture_atlas_layouts.add(layout);
+
+        commands.spawn((
+            SpriteBundle {
+                texture,
+                transform: Transform::from_xyz(ev.wall_pos.x, ev.wall_pos.y, -100.0),
+                ..default()
+            },
+            TextureAtlas {
+                layout: texture_atlas_layout,
+                index: get_random_index_with_weight(vec![10, 3, 2, 1]),
+            },
+        ))
+        .insert(Floor);
+
+        for tile in std::iter::once(&secret_room.cracked_wall).chain(secret_room.tiles.iter()) {
+            ev_update_graph.send(UpdateGraphEvent {
+                tile: *tile,
+                change: GraphChange::Unblock,
+            });
+        }
+
+        for mut visibility in floor_query.iter_mut() {
+            *visibility = Visibility::Inherited;
+        }
+
+        ev_play_audio.send(PlayAudioEvent::from_file("box_break.ogg"));
+        ev_shake_camera.send(CameraShakeEvent);
+        ev_spawn_particles.send(SpawnParticlesEvent {
+            pattern: ParticlePattern::Circle { radius: 16. },
+            position: ev.wall_pos,
+            amount: 8,
+            color: ElementType::Earth.color(),
+            speed: 10.,
+            rotate: false,
+        });
+    }
+}
+
+//особая таблица лута: предмет, большая колба опыта или предмет вместе с колбой
+fn spawn_secret_loot(
+    room: Res<LevelGenerator>,
+    mut ev_opened: EventReader<SecretRoomOpenedEvent>,
+    mut ev_exp_tank: EventWriter<SpawnExpTankEvent>,
+    mut ev_item: EventWriter<SpawnItemEvent>,
+
+    item_database: Res<Assets<ItemDatabase>>,
+    handle: Res<ItemDatabaseHandle>,
+) {
+    for _ev in ev_opened.read() {
+        let Some(secret_room) = &room.secret_room else {
+            continue;
+        };
+
+        let center = secret_room
+            .tiles
+            .iter()
+            .fold(Vec2::ZERO, |acc, tile| acc + Vec2::new(tile.0 as f32, tile.1 as f32))
+            / secret_room.tiles.len() as f32
+            * TILE_SIZE;
+
+        let pos = center.extend(1.);
+
+        let (spawn_item, orbs) = match get_random_index_with_weight(vec![5, 3, 2]) {
+            0 => (true, 0),
+            1 => (false, 20),
+            _ => (true, 8),
+        };
+
+        if orbs > 0 {
+            let offset = if spawn_item { Vec3::new(TILE_SIZE, 0., 0.) } else { Vec3::ZERO };
+
+            ev_exp_tank.send(SpawnExpTankEvent {
+                pos: pos + offset,
+                orbs,
+            });
+        }
+
+        if spawn_item {
+            let item: ItemType = rand::random();
+
+            let item_name: String = item_database.get(handle.0.id()).unwrap().items[item as usize]["name"].as_str().unwrap().to_string();
+            let texture_name: String = item_database.get(handle.0.id()).unwrap().items[item as usize]["texture_name"].as_str().unwrap().to_string();
+            let item_description: String = item_database.get(handle.0.id()).unwrap().items[item as usize]["description"].as_str().unwrap().to_string();
+
+            let texture_path = format!("textures/items/{}", texture_name);
+
+            ev_item.send(SpawnItemEvent {
+                pos,
+                item_type: item,
+                texture_path,
+                item_name,
+                item_description,
+            });
+        }
+    }
+}
@@ -1,16 +1,26 @@
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 use avian2d::prelude::*;
 use bevy::prelude::*;
 
-use crate::{friend::Friend, mobs::Enemy, GameLayer};
+use crate::{
+    friend::Friend,
+    gamemap::tile_of,
+    mobs::Enemy,
+    pathfinding::{GraphChange, UpdateGraphEvent},
+    GameLayer, GameState,
+};
 
 pub struct ShieldSpellPlugin;
 
 impl Plugin for ShieldSpellPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<SpawnShieldEvent>()
-            .add_systems(Update, (spawn_shield, animate_shield, despawn_shield));
+            .add_systems(Update, (spawn_shield, animate_shield, despawn_shield))
+            .add_systems(
+                Update,
+                update_shields_in_graph.run_if(in_state(GameState::InGame)),
+            );
     }
 }
 
@@ -149,3 +159,41 @@ fn despawn_shield(
         }
     }
 }
+
+//щит занимает тайл в графе, пока существует, и переезжает вместе с владельцем
+fn update_shields_in_graph(
+    shield_query: Query<(Entity, &Transform), With<Shield>>,
+    mut removed_shields: RemovedComponents<Shield>,
+    mut shield_tiles: Local<HashMap<Entity, (u16, u16)>>,
+    mut ev_update_graph: EventWriter<UpdateGraphEvent>,
+) {
+    for (shield_e, transform) in shield_query.iter() {
+        let tile = tile_of(transform.translation);
+        let old_tile = shield_tiles.insert(shield_e, tile);
+
+        if old_tile == Some(tile) {
+            continue;
+        }
+
+        if let Some(old_tile) = old_tile {
+            ev_update_graph.send(UpdateGraphEvent {
+                tile: old_tile,
+                change: GraphChange::RemoveBlocker,
+            });
+        }
+
+        ev_update_graph.send(UpdateGraphEvent {
+            tile,
+            change: GraphChange::AddBlocker,
+        });
+    }
+
+    for shield_e in removed_shields.read() {
+        if let Some(tile) = shield_tiles.remove(&shield_e) {
+            ev_update_graph.send(UpdateGraphEvent {
+                tile,
+                change: GraphChange::RemoveBlocker,
+            });
+        }
+    }
+}
@@ -0,0 +1,381 @@
+//призванные, пережившие уровень, идут за игроком через хаб на следующий уровень.
+//Сколько можно взять с собой - по количеству фонарей душ. Призванные качаются от убийств рядом с ними
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use crate::{
+    camera::YSort,
+    friend::Friend,
+    health::Health,
+    item::ItemType,
+    mobs::{
+        AttackComponent, AttackPattern, AttackType, EliteRoll, Mob, MobDeathEvent, MobSpawnEvent,
+        MobType, Orbital, SummonQueue,
+    },
+    particles::{ParticlePattern, SpawnParticlesEvent},
+    player::Player,
+    ui::ItemInventory,
+    utils::despawn_all_with,
+    GameState,
+};
+
+pub struct SummonPersistencePlugin;
+
+impl Plugin for SummonPersistencePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PersistentSummons>()
+            .init_resource::<SummonRunStats>()
+            .add_systems(OnExit(GameState::MainMenu), reset_persistence)
+            .add_systems(OnExit(GameState::InGame), store_surviving_summons)
+            .add_systems(OnEnter(GameState::GameOver), clear_persistent_summons)
+            .add_systems(OnEnter(GameState::Hub), spawn_hub_followers)
+            .add_systems(OnExit(GameState::Hub), despawn_all_with::<HubFollower>)
+            .add_systems(OnEnter(GameState::InGame), resummon_persistent)
+            .add_systems(
+                Update,
+                follow_player_in_hub.run_if(in_state(GameState::Hub)),
+            )
+            .add_systems(
+                Update,
+                (restore_summon_level, summon_kill_exp, level_up_summons)
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+//с какого расстояния призванный получает опыт за убитого врага
+const KILL_CREDIT_RADIUS: f32 = 128.;
+//на этом уровне призванный получает новую атаку
+const NEW_ATTACK_LEVEL: u8 = 3;
+const HP_PER_LEVEL: i32 = 20;
+const DAMAGE_PER_LEVEL: i32 = 5;
+
+#[derive(Component, Clone)]
+pub struct SummonLevel {
+    pub level: u8,
+    pub exp: u32,
+}
+
+impl Default for SummonLevel {
+    fn default() -> Self {
+        Self { level: 1, exp: 0 }
+    }
+}
+
+impl SummonLevel {
+    pub fn exp_to_next(&self) -> u32 {
+        10 * self.level as u32
+    }
+}
+
+//призванный между уровнями, спрайт нужен для хаба
+#[derive(Clone)]
+pub struct PersistentSummon {
+    pub mob_type: MobType,
+    pub level: SummonLevel,
+    pub texture: Handle<Image>,
+    pub atlas: Option<TextureAtlas>,
+}
+
+#[derive(Resource, Default)]
+pub struct PersistentSummons {
+    pub carried: Vec<PersistentSummon>,
+    //уже заспавнены на уровне, ждут, когда им вернут уровень
+    pending: Vec<PersistentSummon>,
+}
+
+//статистика призванных за забег
+#[derive(Resource, Default)]
+pub struct SummonRunStats {
+    pub kills: u32,
+    pub levels_gained: u32,
+    pub best_level: u8,
+    pub carried_over: u32,
+}
+
+#[derive(Component)]
+struct HubFollower(usize);
+
+fn reset_persistence(mut commands: Commands) {
+    commands.insert_resource(PersistentSummons::default());
+    commands.insert_resource(SummonRunStats::default());
+}
+
+fn clear_persistent_summons(mut persistent: ResMut<PersistentSummons>) {
+    persistent.carried.clear();
+    persistent.pending.clear();
+}
+
+//уровень закончился - запоминаем живых, самых прокачанных в первую очередь
+fn store_surviving_summons(
+    mut persistent: ResMut<PersistentSummons>,
+    mut stats: ResMut<SummonRunStats>,
+    inventory: Res<ItemInventory>,
+    player_query: Query<&SummonQueue, With<Player>>,
+    summon_query: Query<
+        (
+            &MobType,
+            &Handle<Image>,
+            Option<&TextureAtlas>,
+            Option<&SummonLevel>,
+            &Health,
+        ),
+        (With<Friend>, Without<Orbital>),
+    >,
+) {
+    persistent.carried.clear();
+    persistent.pending.clear();
+
+    let capacity = *inventory.amount_of_item(ItemType::SoulLantern);
+    if capacity <= 0 {
+        return;
+    }
+
+    let Ok(summon_queue) = player_query.get_single() else {
+        return;
+    };
+
+    let mut survivors: Vec<PersistentSummon> = summon_queue
+        .queue
+        .iter()
+        .filter_map(|unit| unit.entity)
+        .filter_map(|summon_e| summon_query.get(summon_e).ok())
+        .filter(|(_, _, _, _, health)| health.current > 0)
+        .map(|(mob_type, texture, atlas, level, _)| PersistentSummon {
+            mob_type: mob_type.clone(),
+            level: level.cloned().unwrap_or_default(),
+            texture: texture.clone(),
+            atlas: atlas.cloned(),
+        })
+        .collect();
+
+    survivors.sort_by(|a, b| b.level.level.cmp(&a.level.level));
+    survivors.truncate(capacity as usize);
+
+    stats.carried_over += survivors.len() as u32;
+    persistent.carried = survivors;
+}
+
+fn spawn_hub_followers(
+    mut commands: Commands,
+    persistent: Res<PersistentSummons>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    for (i, summon) in persistent.carried.iter().enumerate() {
+        let follower = commands
+            .spawn(SpriteBundle {
+                texture: summon.texture.clone(),
+                transform: Transform::from_translation(
+                    player_transform.translation - Vec3::new(0., 16. * (i + 1) as f32, 0.),
+                ),
+                ..default()
+            })
+            .insert(YSort(8.))
+            .insert(HubFollower(i))
+            .id();
+
+        if let Some(atlas) = &summon.atlas {
+            commands.entity(follower).insert(atlas.clone());
+        }
+    }
+}
+
+//в хабе призванные просто идут вереницей за игроком
+fn follow_player_in_hub(
+    mut follower_query: Query<(&mut Transform, &HubFollower), Without<Player>>,
+    player_query: Query<&Transform, With<Player>>,
+    time: Res<Time>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    for (mut transform, follower) in follower_query.iter_mut() {
+        let goal = player_transform.translation.truncate()
+            + Vec2::from_angle(-PI / 2. + follower.0 as f32 * 0.6) * 20.;
+        let pos = transform.translation.truncate();
+
+        transform.translation = pos
+            .lerp(goal, (time.delta_seconds() * 4.).min(1.))
+            .extend(transform.translation.z);
+    }
+}
+
+fn resummon_persistent(
+    mut persistent: ResMut<PersistentSummons>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+    mut ev_spawn: EventWriter<MobSpawnEvent>,
+) {
+    let Ok((player_e, player_transform)) = player_query.get_single() else {
+        return;
+    };
+
+    let carried = std::mem::take(&mut persistent.carried);
+    let count = carried.len();
+
+    for (i, summon) in carried.iter().enumerate() {
+        ev_spawn.send(MobSpawnEvent {
+            mob_type: summon.mob_type.clone(),
+            pos: player_transform.translation.truncate()
+                + Vec2::from_angle(i as f32 * 2. * PI / count as f32) * 24.,
+            is_friendly: true,
+            owner: Some(player_e),
+            loot: None,
+            exp_amount: -1,
+            elite: EliteRoll::Never,
+        });
+    }
+
+    persistent.pending = carried;
+}
+
+//новым призванным - первый уровень, вернувшимся - их уровень со всеми бонусами
+fn restore_summon_level(
+    mut commands: Commands,
+    mut persistent: ResMut<PersistentSummons>,
+    mut summon_query: Query<
+        (Entity, &MobType, &mut Health, &mut Mob, Option<&mut AttackComponent>),
+        (Added<Friend>, Without<SummonLevel>),
+    >,
+) {
+    for (summon_e, mob_type, mut health, mut mob, mut attack) in summon_query.iter_mut() {
+        let Some(index) = persistent
+            .pending
+            .iter()
+            .position(|summon| summon.mob_type == *mob_type)
+        else {
+            commands.entity(summon_e).insert(SummonLevel::default());
+            continue;
+        };
+
+        let summon = persistent.pending.remove(index);
+
+        for level in 2..=summon.level.level {
+            apply_level_bonus(level, &mut health, &mut mob, attack.as_deref_mut());
+        }
+        health.current = health.max;
+
+        commands.entity(summon_e).insert(summon.level);
+    }
+}
+
+//опыт получают все призванные, которые были рядом с убитым
+fn summon_kill_exp(
+    mut ev_death: EventReader<MobDeathEvent>,
+    mut summon_query: Query<(&Transform, &mut SummonLevel), With<Friend>>,
+    mut stats: ResMut<SummonRunStats>,
+) {
+    for ev in ev_death.read() {
+        let mut credited = false;
+
+        for (transform, mut level) in summon_query.iter_mut() {
+            if transform.translation.truncate().distance(ev.pos.truncate()) > KILL_CREDIT_RADIUS {
+                continue;
+            }
+
+            level.exp += ev.orbs.max(1);
+            credited = true;
+        }
+
+        if credited {
+            stats.kills += 1;
+        }
+    }
+}
+
+fn level_up_summons(
+    mut summon_query: Query<
+        (
+            &Transform,
+            &mut SummonLevel,
+            &mut Health,
+            &mut Mob,
+            Option<&mut AttackComponent>,
+        ),
+        (With<Friend>, Changed<SummonLevel>),
+    >,
+    mut stats: ResMut<SummonRunStats>,
+    mut ev_spawn_particles: EventWriter<SpawnParticlesEvent>,
+) {
+    for (transform, mut level, mut health, mut mob, mut attack) in summon_query.iter_mut() {
+        while level.exp >= level.exp_to_next() {
+            level.exp -= level.exp_to_next();
+            level.level += 1;
+
+            apply_level_bonus(level.level, &mut health, &mut mob, attack.as_deref_mut());
+
+            stats.levels_gained += 1;
+            stats.best_level = stats.best_level.max(level.level);
+
+            ev_spawn_particles.send(SpawnParticlesEvent {
+                pattern: ParticlePattern::Circle { radius: 12. },
+                position: transform.translation,
+                amount: 8,
+                color: Color::srgb(1., 0.9, 0.4),
+                speed: 10.,
+                rotate: false,
+            });
+        }
+    }
+}
+
+fn apply_level_bonus(
+    level: u8,
+    health: &mut Health,
+    mob: &mut Mob,
+    attack: Option<&mut AttackComponent>,
+) {
+    health.max += HP_PER_LEVEL;
+    health.current += HP_PER_LEVEL;
+    mob.damage += DAMAGE_PER_LEVEL;
+
+    let Some(attack) = attack else {
+        return;
+    };
+
+    attack.damage += DAMAGE_PER_LEVEL;
+
+    if level == NEW_ATTACK_LEVEL {
+        upgrade_attack(attack);
+    }
+}
+
+//новая атака: мили начинают бить вокруг себя, стрелки - стрелять шире
+fn upgrade_attack(attack: &mut AttackComponent) {
+    match attack.attack_type {
+        AttackType::Slash | AttackType::Rush => {
+            attack.attack_type = AttackType::Circle;
+            attack.pattern = Some(AttackPattern::ring(8));
+        }
+        AttackType::Spear => {
+            let mut pattern = attack.pattern();
+            pattern.count += 1;
+            attack.pattern = Some(pattern);
+        }
+        AttackType::Circle => {
+            //без паттерна круговой удар бьет 16 раз
+            let mut pattern = attack.pattern();
+            pattern.count = pattern.count.max(16) + 8;
+            attack.pattern = Some(pattern);
+        }
+        AttackType::Range => {
+            let pattern = attack.pattern();
+            let mut upgraded = if pattern.is_ring() {
+                AttackPattern::ring(pattern.count + 4)
+            } else {
+                AttackPattern::fan(pattern.count + 2, pattern.spread + 0.3)
+            };
+            upgraded.speed = pattern.speed;
+            upgraded.interval = pattern.interval;
+            upgraded.trajectory = pattern.trajectory;
+            upgraded.modifiers = pattern.modifiers;
+            attack.pattern = Some(upgraded);
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use bevy::prelude::*;
+
+use crate::{
+    health::Health,
+    mobs::{BossAttackFlag, BossScript, BossTelegraphEvent, PhaseManager},
+    utils::despawn_all_with,
+    GameState,
+};
+
+pub struct BossUIPlugin;
+
+impl Plugin for BossUIPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(GameState::InGame), despawn_all_with::<BossBarUI>)
+            .add_systems(OnEnter(GameState::GameOver), despawn_all_with::<BossBarUI>)
+            .add_systems(
+                Update,
+                (
+                    spawn_boss_bar,
+                    despawn_boss_bar,
+                    update_boss_bar,
+                    flash_boss_bar,
+                    show_telegraph,
+                    fade_telegraph,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+const BOSS_BAR_WIDTH: f32 = 320.;
+const BOSS_BAR_COLOR: Color = Color::hsl(0.0, 1.0, 0.4);
+
+#[derive(Component)]
+pub struct BossBarUI;
+
+#[derive(Component)]
+struct BossHPBar;
+
+#[derive(Component)]
+struct BossHPText;
+
+//отметка порога фазы на полоске, номер фазы, которая начнется после нее
+#[derive(Component)]
+struct PhasePip(u8);
+
+#[derive(Component)]
+struct BossBarFlash {
+    timer: Timer,
+}
+
+#[derive(Component)]
+struct TelegraphBanner {
+    timer: Timer,
+}
+
+//полоска появляется, когда босс подтянул свое описание из базы
+fn spawn_boss_bar(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    boss_query: Query<&BossScript>,
+    bar_query: Query<Entity, With<BossBarUI>>,
+) {
+    if !bar_query.is_empty() {
+        return;
+    }
+
+    let Some(definition) = boss_query
+        .iter()
+        .find_map(|script| script.definition.as_ref())
+    else {
+        return;
+    };
+
+    let font = asset_server.load("fonts/ebbe_bold.ttf");
+
+    commands.spawn(NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.),
+            top: Val::Px(8.),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ..default()
+    })
+    .insert(BossBarUI)
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            definition.title.clone(),
+            TextStyle {
+                font: font.clone(),
+                font_size: 16.0,
+                color: Color::WHITE,
+            },
+        ));
+
+        parent.spawn(ImageBundle { // фон полоски ХП босса
+            image: UiImage::solid_color(Color::hsl(0.0, 1.0, 0.1)),
+            style: Style {
+                width: Val::Px(BOSS_BAR_WIDTH),
+                height: Val::Px(16.0),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(ImageBundle { // сама полоска
+                image: UiImage::solid_color(BOSS_BAR_COLOR),
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(BossHPBar);
+
+            // пороги фаз, первая фаза начинается с полного хп и отметки не имеет
+            for (i, phase) in definition.phases.iter().enumerate().skip(1) {
+                parent.spawn(ImageBundle {
+                    image: UiImage::solid_color(Color::WHITE),
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Percent(phase.hp_threshold * 100.),
+                        width: Val::Px(2.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(PhasePip(i as u8 + 1));
+            }
+        });
+
+        parent.spawn(TextBundle::from_section(
+            "",
+            TextStyle {
+                font: font.clone(),
+                font_size: 12.0,
+                color: Color::WHITE,
+            },
+        ))
+        .insert(BossHPText);
+
+        parent.spawn(TextBundle::from_section(
+            "",
+            TextStyle {
+                font: font.clone(),
+                font_size: 16.0,
+                color: Color::NONE,
+            },
+        ))
+        .insert(TelegraphBanner {
+            timer: Timer::from_seconds(1.5, TimerMode::Once),
+        });
+    });
+}
+
+//босс умер или ушел вместе с уровнем - убираем полоску
+fn despawn_boss_bar(
+    mut commands: Commands,
+    boss_query: Query<&BossScript>,
+    bar_query: Query<Entity, With<BossBarUI>>,
+) {
+    if !boss_query.is_empty() {
+        return;
+    }
+
+    for bar in bar_query.iter() {
+        commands.entity(bar).despawn_recursive();
+    }
+}
+
+fn update_boss_bar(
+    mut commands: Commands,
+    boss_query: Query<(&Health, &PhaseManager), With<BossScript>>,
+    mut bar_query: Query<(Entity, &mut Style), With<BossHPBar>>,
+    mut text_query: Query<&mut Text, With<BossHPText>>,
+    mut pip_query: Query<(&PhasePip, &mut UiImage)>,
+    mut last_phase: Local<u8>,
+) {
+    let Ok((health, phase_manager)) = boss_query.get_single() else {
+        *last_phase = 0;
+        return;
+    };
+
+    let Ok((bar_e, mut style)) = bar_query.get_single_mut() else {
+        return;
+    };
+
+    let percent = (health.current.max(0) as f32 / health.max as f32) * 100.0;
+    style.width = Val::Percent(percent);
+
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.sections[0].value = format!("{}/{}", health.current.max(0), health.max);
+    }
+
+    if *last_phase == phase_manager.current_phase {
+        return;
+    }
+
+    // вспышка только при смене фазы, а не при появлении полоски
+    if *last_phase != 0 {
+        commands.entity(bar_e).insert(BossBarFlash {
+            timer: Timer::from_seconds(0.4, TimerMode::Once),
+        });
+    }
+    *last_phase = phase_manager.current_phase;
+
+    // пройденные пороги гаснут
+    for (pip, mut image) in pip_query.iter_mut() {
+        image.color = if pip.0 <= phase_manager.current_phase {
+            Color::srgba(1., 1., 1., 0.25)
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+fn flash_boss_bar(
+    mut commands: Commands,
+    mut bar_query: Query<(Entity, &mut UiImage, &mut BossBarFlash), With<BossHPBar>>,
+    time: Res<Time>,
+) {
+    for (bar_e, mut image, mut flash) in bar_query.iter_mut() {
+        flash.timer.tick(time.delta());
+
+        image.color = Color::WHITE.mix(&BOSS_BAR_COLOR, flash.timer.fraction());
+
+        if flash.timer.finished() {
+            image.color = BOSS_BAR_COLOR;
+            commands.entity(bar_e).remove::<BossBarFlash>();
+        }
+    }
+}
+
+//баннер с названием атаки, цвет зависит от типа атаки
+fn show_telegraph(
+    mut ev_telegraph: EventReader<BossTelegraphEvent>,
+    mut banner_query: Query<(&mut Text, &mut TelegraphBanner)>,
+) {
+    for ev in ev_telegraph.read() {
+        let Ok((mut text, mut banner)) = banner_query.get_single_mut() else {
+            continue;
+        };
+
+        text.sections[0].value = ev.attack.telegraph().to_string();
+        text.sections[0].style.color = match ev.attack.flag() {
+            BossAttackFlag::ProjectileSpells => Color::srgb(1.0, 0.45, 0.3),
+            BossAttackFlag::DefensiveSpells => Color::srgb(0.4, 0.7, 1.0),
+            BossAttackFlag::SpawnSpells(_) => Color::srgb(0.5, 1.0, 0.4),
+            BossAttackFlag::RaiseSpells => Color::srgb(0.85, 0.85, 0.7),
+        };
+        banner.timer.reset();
+    }
+}
+
+fn fade_telegraph(
+    mut banner_query: Query<(&mut Text, &mut TelegraphBanner)>,
+    time: Res<Time>,
+) {
+    for (mut text, mut banner) in banner_query.iter_mut() {
+        if banner.timer.finished() {
+            continue;
+        }
+
+        banner.timer.tick(time.delta());
+
+        let alpha = 1. - banner.timer.fraction();
+        text.sections[0].style.color.set_alpha(alpha);
+    }
+}
@@ -6,7 +6,7 @@ use bevy_simple_text_input::{
     TextInputSystem, TextInputValue
 };
 
-use crate::{chapter::ChapterManager, exp_tank::SpawnExpTankEvent, health_tank::SpawnHealthTankEvent, invincibility::Invincibility, item::{ItemDatabase, ItemDatabaseHandle, ItemType, SpawnItemEvent}, player::Player, GameState};
+use crate::{chapter::ChapterManager, exp_tank::SpawnExpTankEvent, health_tank::SpawnHealthTankEvent, invincibility::Invincibility, item::{ItemDatabase, ItemDatabaseHandle, ItemType, SpawnItemEvent}, pathfinding::BenchmarkPathfindingEvent, player::Player, GameState};
 
 pub struct DebugConsolePlugin;
 
@@ -88,6 +88,7 @@ fn handle_commands(
     mut ev_spawn_exp: EventWriter<SpawnExpTankEvent>,
     mut ev_spawn_hp: EventWriter<SpawnHealthTankEvent>,
     mut ev_spawn_item: EventWriter<SpawnItemEvent>,
+    mut ev_benchmark: EventWriter<BenchmarkPathfindingEvent>,
 
     item_database: Res<Assets<ItemDatabase>>,
     item_database_handle: Res<ItemDatabaseHandle>,
@@ -148,6 +149,11 @@ fn handle_commands(
                 let Ok(duration) = command[1].parse::<f32>() else { return; };
                 commands.entity(player_entity).insert(Invincibility::new(duration));
             }
+            "bench" => { // compares per-mob A* with shared flow field, e.g. "bench path 200"
+                if command.get(1) != Some(&"path") { return; }
+                let mobs = command.get(2).and_then(|value| value.parse::<usize>().ok()).unwrap_or(100);
+                ev_benchmark.send(BenchmarkPathfindingEvent { mobs });
+            }
             "goto" => {
                 let Ok(chapter) = command[1].parse::<u8>() else { return; };
                 let Ok(level) = command[2].parse::<u8>() else { return; };
@@ -20,7 +20,7 @@ impl Plugin for ElementsUIPlugin {
     fn build(&self, app: &mut App) {
         app
             .add_systems(OnExit(GameState::MainMenu), spawn_ui)
-            .add_systems(Update, (update_ui, add_slots_from_lv));
+            .add_systems(Update, (update_ui, add_slots_from_lv, sync_slots).chain());
     }
 }
 
@@ -86,32 +86,55 @@ fn update_ui(
 }
 
 fn add_slots_from_lv(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
     mut ev_exp_gained: EventReader<ExpGained>,
     player_experience: Res<PlayerExperience>,
-    mut element_bar_query: Query<Entity, With<ElementBarUI>>,
     mut element_bar: ResMut<ElementBar>,
 ) {
-    if let Ok(bar_e) = element_bar_query.get_single_mut() {
-        for _ev in ev_exp_gained.read() {
-            if element_bar.max < player_experience.lv {
-                commands.entity(bar_e).with_children(|parent| {
-                    for i in element_bar.max..player_experience.lv {
-                        parent.spawn(ImageBundle {
-                            style: Style {
-                                width: Val::Px(48.0),
-                                height: Val::Px(48.0),
-                                ..default()
-                            },
-                            image: UiImage::new(asset_server.load("textures/empty_slot.png")),
-                            ..default()
-                        }).insert(ElementSlot(i as usize));
-                    }
-                });
+    for _ev in ev_exp_gained.read() {
+        if element_bar.max < player_experience.lv {
+            element_bar.max = player_experience.lv;
+        }
+    }
+}
+
+// ячеек столько, сколько влезает в полоску с учетом посоха
+fn sync_slots(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    element_bar_query: Query<Entity, With<ElementBarUI>>,
+    slot_query: Query<(Entity, &ElementSlot)>,
+    element_bar: Res<ElementBar>,
+) {
+    if !element_bar.is_changed() {
+        return;
+    }
+
+    let Ok(bar_e) = element_bar_query.get_single() else {
+        return;
+    };
+
+    let capacity = element_bar.capacity() as usize;
+    let slots = slot_query.iter().count();
 
-                element_bar.max = player_experience.lv;
+    if slots < capacity {
+        commands.entity(bar_e).with_children(|parent| {
+            for i in slots..capacity {
+                parent.spawn(ImageBundle {
+                    style: Style {
+                        width: Val::Px(48.0),
+                        height: Val::Px(48.0),
+                        ..default()
+                    },
+                    image: UiImage::new(asset_server.load("textures/empty_slot.png")),
+                    ..default()
+                }).insert(ElementSlot(i));
             }
-        }   
+        });
     }
-}
\ No newline at end of file
+
+    for (slot_e, slot) in slot_query.iter() {
+        if slot.0 >= capacity {
+            commands.entity(slot_e).despawn_recursive();
+        }
+    }
+}
@@ -3,7 +3,7 @@ use serde::Deserialize;
 use serde_json::{Map, Value};
 
 use crate::{
-    audio::PlayAudioEvent, item::{ItemDatabase, ItemDatabaseHandle}, mobs::{MobDatabase, MobDatabaseHandle}, save::{DeleteSaveEvent, Save, SaveHandle}, GameState, MainMenuState
+    audio::PlayAudioEvent, item::{ItemDatabase, ItemDatabaseHandle}, mobs::{MobDatabase, MobDatabaseHandle}, save::{DeleteSaveEvent, Save, SaveHandle}, ui::recipe_icon, GameState, MainMenuState
 };
 
 use bevy_common_assets::json::JsonAssetPlugin;
@@ -459,17 +459,7 @@ fn spawn_view_spells(
         ))
         .with_children(|parent| {
             for element in recipe.iter() {
-                let texture_path = match element.as_str().unwrap() {
-                    "fire" => "fire_slot.png",
-                    "water" => "water_slot.png",
-                    "earth" => "earth_slot.png",
-                    "air" => "air_slot.png",
-                    "etc" => "ui/etc.png",
-                    "lower" => "ui/lower.png",
-                    "greater" => "ui/greater.png",
-                    "equals" => "ui/equals.png",
-                    _ => ""
-                };
+                let texture_path = recipe_icon(element.as_str().unwrap());
 
                 parent.spawn(ImageBundle {
                     style: Style {
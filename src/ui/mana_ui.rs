@@ -0,0 +1,184 @@
+use bevy::prelude::*;
+
+use crate::{
+    mana::{CastDenied, CastDeniedEvent, Mana},
+    utils::despawn_all_with,
+    GameState,
+};
+
+pub struct ManaUIPlugin;
+
+impl Plugin for ManaUIPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(GameState::MainMenu), spawn_ui)
+            .add_systems(OnEnter(GameState::GameOver), despawn_all_with::<ManaBarUI>)
+            .add_systems(Update, (update_ui, update_cooldowns, flash_on_denied));
+    }
+}
+
+#[derive(Component)]
+pub struct ManaBarUI;
+
+#[derive(Component)]
+struct ManaBar;
+
+#[derive(Component)]
+struct ManaBarBackground;
+
+#[derive(Component)]
+struct ManaText;
+
+#[derive(Component)]
+struct CooldownText;
+
+const MANA_COLOR: Color = Color::hsl(220.0, 0.9, 0.55);
+const MANA_BACKGROUND: Color = Color::hsl(220.0, 0.9, 0.1);
+
+//справа от полоски здоровья
+fn spawn_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(192. + 40.),
+                top: Val::Px(20.),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(2.),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(ManaBarUI)
+        .with_children(|parent| {
+            parent
+                .spawn(ImageBundle {
+                    // фон полоски маны
+                    image: UiImage::solid_color(MANA_BACKGROUND),
+                    style: Style {
+                        width: Val::Px(128.),
+                        height: Val::Px(24.),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(ManaBarBackground)
+                .with_children(|parent| {
+                    parent
+                        .spawn(ImageBundle {
+                            image: UiImage::solid_color(MANA_COLOR),
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                left: Val::Px(0.),
+                                width: Val::Percent(100.),
+                                height: Val::Percent(100.),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .insert(ManaBar);
+
+                    parent
+                        .spawn(TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font: asset_server.load("fonts/ebbe_bold.ttf"),
+                                font_size: 16.0,
+                                color: Color::WHITE,
+                            },
+                        ))
+                        .insert(ManaText);
+                });
+
+            //что сейчас перезаряжается
+            parent
+                .spawn(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("fonts/ebbe_bold.ttf"),
+                        font_size: 12.0,
+                        color: Color::srgb(0.7, 0.8, 1.0),
+                    },
+                ))
+                .insert(CooldownText);
+        });
+}
+
+fn update_ui(
+    mut bar_query: Query<&mut Style, With<ManaBar>>,
+    mut text_query: Query<&mut Text, With<ManaText>>,
+    mana: Res<Mana>,
+) {
+    if !mana.is_changed() {
+        return;
+    }
+
+    if let Ok(mut style) = bar_query.get_single_mut() {
+        style.width = Val::Percent(mana.current / mana.max * 100.);
+    }
+
+    if let Ok(mut text) = text_query.get_single_mut() {
+        let value = format!("{}/{}", mana.current.floor() as i32, mana.max as i32);
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+fn update_cooldowns(mut text_query: Query<&mut Text, With<CooldownText>>, mana: Res<Mana>) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    let mut cooldowns: Vec<String> = mana
+        .cooldowns()
+        .map(|(spell, _)| format!("{} {:.1}", spell.name(), mana.cooldown_left(spell)))
+        .collect();
+    cooldowns.sort();
+
+    let value = cooldowns.join("\n");
+    if text.sections[0].value != value {
+        text.sections[0].value = value;
+    }
+}
+
+//не хватило маны - полоска мигает, заклинание не готово - мигает список перезарядки
+fn flash_on_denied(
+    mut ev_denied: EventReader<CastDeniedEvent>,
+    mut background_query: Query<&mut UiImage, With<ManaBarBackground>>,
+    mut cooldown_query: Query<&mut Text, With<CooldownText>>,
+    mut flash: Local<Option<(CastDenied, Timer)>>,
+    time: Res<Time>,
+) {
+    for ev in ev_denied.read() {
+        *flash = Some((ev.reason, Timer::from_seconds(0.25, TimerMode::Once)));
+    }
+
+    let Some((reason, timer)) = flash.as_mut() else {
+        return;
+    };
+
+    timer.tick(time.delta());
+    let active = !timer.finished();
+
+    if let Ok(mut image) = background_query.get_single_mut() {
+        image.color = if active && *reason == CastDenied::NoMana {
+            Color::hsl(0.0, 0.9, 0.35)
+        } else {
+            MANA_BACKGROUND
+        };
+    }
+
+    if let Ok(mut text) = cooldown_query.get_single_mut() {
+        text.sections[0].style.color = if active && *reason == CastDenied::Cooldown {
+            Color::srgb(1.0, 0.4, 0.4)
+        } else {
+            Color::srgb(0.7, 0.8, 1.0)
+        };
+    }
+
+    if !active {
+        *flash = None;
+    }
+}
@@ -4,6 +4,9 @@ pub use elements_ui::*;
 mod health_ui;
 pub use health_ui::*;
 
+mod mana_ui;
+pub use mana_ui::*;
+
 mod experience_ui;
 pub use experience_ui::*;
 
@@ -16,8 +19,17 @@ pub use item_ui::*;
 mod pause_ui;
 pub use pause_ui::*;
 
+mod spellbook_ui;
+pub use spellbook_ui::*;
+
 mod loading_screen_ui;
 pub use loading_screen_ui::*;
 
+mod boss_ui;
+pub use boss_ui::*;
+
+mod summon_ui;
+pub use summon_ui::*;
+
 mod debug_console;
 pub use debug_console::DebugConsolePlugin;
\ No newline at end of file
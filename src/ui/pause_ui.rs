@@ -13,6 +13,9 @@ impl Plugin for PauseUIPlguin {
 #[derive(Component)]
 pub struct PauseMenu;
 
+#[derive(Component)]
+pub struct SpellbookButton;
+
 fn init_pause_menu(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -23,6 +26,9 @@ fn init_pause_menu(
             height: Val::Percent(100.), 
             justify_content: JustifyContent::Center,
             align_content: AlignContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(16.),
             ..default()
         },
         background_color: BackgroundColor(Color::hsla(0., 0., 0., 0.5)),
@@ -43,6 +49,34 @@ fn init_pause_menu(
             }),
             ..default()
         });
+
+        parent.spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(224.),
+                    height: Val::Px(40.),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                image: UiImage::new(asset_server.load("textures/ui/button.png")),
+                ..default()
+            },
+            ImageScaleMode::Sliced(TextureSlicer {
+                border: BorderRect::square(16.0),
+                center_scale_mode: SliceScaleMode::Stretch,
+                sides_scale_mode: SliceScaleMode::Stretch,
+                ..default()
+            }),
+            SpellbookButton,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("Книга заклинаний", TextStyle {
+                font: asset_server.load("fonts/ebbe_bold.ttf"),
+                font_size: 20.,
+                color: Color::BLACK,
+            }));
+        });
     })
     .insert(PauseMenu)
     .insert(Visibility::Hidden);
@@ -0,0 +1,399 @@
+//книга заклинаний во время забега: открывается из паузы, показывает открытые заклинания,
+//их рецепты и бонусы урона. Под полоской элементов - что скастуется прямо сейчас
+use bevy::prelude::*;
+
+use crate::{
+    audio::PlayAudioEvent,
+    elements::{match_recipe, ElementBar, ElementType, Spell, SpellPool},
+    mana::Mana,
+    player::PlayerStats,
+    ui::SpellbookButton,
+    utils::despawn_all_with,
+    wand::{EquippedWand, WandType},
+    GameState,
+};
+
+pub struct SpellbookUIPlugin;
+
+impl Plugin for SpellbookUIPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(GameState::MainMenu), spawn_preview)
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                (
+                    despawn_all_with::<SpellPreviewUI>,
+                    despawn_all_with::<SpellbookUI>,
+                ),
+            )
+            .add_systems(
+                Update,
+                (update_preview, open_spellbook, close_spellbook)
+                    .run_if(in_state(GameState::InGame).or_else(in_state(GameState::Hub))),
+            );
+    }
+}
+
+#[derive(Component)]
+pub struct SpellbookUI;
+
+#[derive(Component)]
+struct SpellbookCloseButton;
+
+#[derive(Component)]
+pub struct SpellPreviewUI;
+
+//значок для элемента рецепта, общий с альманахом
+pub fn recipe_icon(token: &str) -> &str {
+    match token {
+        "fire" => "fire_slot.png",
+        "water" => "water_slot.png",
+        "earth" => "earth_slot.png",
+        "air" => "air_slot.png",
+        "etc" => "ui/etc.png",
+        "lower" => "ui/lower.png",
+        "greater" => "ui/greater.png",
+        "equals" => "ui/equals.png",
+        _ => "",
+    }
+}
+
+fn element_name(element: ElementType) -> &'static str {
+    match element {
+        ElementType::Fire => "огонь",
+        ElementType::Water => "вода",
+        ElementType::Earth => "земля",
+        ElementType::Air => "воздух",
+        ElementType::Steam => "пар",
+    }
+}
+
+//от какой стихии считается урон заклинания, None - заклинание не бьет напрямую
+fn damage_element(spell: Spell) -> Option<ElementType> {
+    match spell {
+        Spell::Fire | Spell::Steam | Spell::Flamethrower | Spell::Magma | Spell::Meteor => {
+            Some(ElementType::Fire)
+        }
+        Spell::Water | Spell::WaterBeam | Spell::Ice => Some(ElementType::Water),
+        Spell::Earth | Spell::Sandstorm => Some(ElementType::Earth),
+        Spell::Air | Spell::Blank | Spell::BlackHole | Spell::Lightning => Some(ElementType::Air),
+        _ => None,
+    }
+}
+
+//под полоской элементов
+fn spawn_preview(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn(
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/ebbe_bold.ttf"),
+                    font_size: 16.0,
+                    color: Color::WHITE,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(4.),
+                top: Val::Px(100.),
+                ..default()
+            }),
+        )
+        .insert(SpellPreviewUI);
+}
+
+fn update_preview(
+    mut preview_query: Query<&mut Text, With<SpellPreviewUI>>,
+    element_bar: Res<ElementBar>,
+    spell_pool: Res<SpellPool>,
+    mana: Res<Mana>,
+    player_stats: Res<PlayerStats>,
+) {
+    let Ok(mut text) = preview_query.get_single_mut() else {
+        return;
+    };
+
+    let (value, color) = match match_recipe(&element_bar, &spell_pool) {
+        None => (String::new(), Color::WHITE),
+        Some(spell) => {
+            let cost = mana.cost_of(spell, &player_stats);
+            let cooldown = mana.cooldown_left(spell);
+
+            if cooldown > 0. {
+                (
+                    format!("> {} (перезарядка {:.1})", spell.name(), cooldown),
+                    Color::srgb(0.6, 0.6, 0.6),
+                )
+            } else if mana.current < cost {
+                (
+                    format!(
+                        "> {} (не хватает маны: {})",
+                        spell.name(),
+                        cost.ceil() as i32
+                    ),
+                    Color::srgb(0.5, 0.6, 1.0),
+                )
+            } else {
+                (
+                    format!("> {} ({} маны)", spell.name(), cost.ceil() as i32),
+                    Color::WHITE,
+                )
+            }
+        }
+    };
+
+    if text.sections[0].value != value {
+        text.sections[0].value = value;
+    }
+    text.sections[0].style.color = color;
+}
+
+fn open_spellbook(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    button_query: Query<&Interaction, (Changed<Interaction>, With<SpellbookButton>)>,
+    spellbook_query: Query<Entity, With<SpellbookUI>>,
+    mut ev_play_audio: EventWriter<PlayAudioEvent>,
+    spell_pool: Res<SpellPool>,
+    player_stats: Res<PlayerStats>,
+    mana: Res<Mana>,
+    equipped_wand: Res<EquippedWand>,
+) {
+    if !button_query.iter().any(|i| *i == Interaction::Pressed) || !spellbook_query.is_empty() {
+        return;
+    }
+
+    ev_play_audio.send(PlayAudioEvent::from_file("tick.ogg"));
+
+    let font = asset_server.load("fonts/ebbe_bold.ttf");
+    let text_style = |font_size: f32, color: Color| TextStyle {
+        font: font.clone(),
+        font_size,
+        color,
+    };
+
+    let slicer = TextureSlicer {
+        border: BorderRect::square(16.0),
+        center_scale_mode: SliceScaleMode::Stretch,
+        sides_scale_mode: SliceScaleMode::Stretch,
+        ..default()
+    };
+
+    //бонусы урона и маны от предметов
+    let mut bonuses: Vec<String> = [
+        ElementType::Fire,
+        ElementType::Water,
+        ElementType::Earth,
+        ElementType::Air,
+        ElementType::Steam,
+    ]
+    .iter()
+    .filter(|element| player_stats.element_damage_percent[**element as usize] != 0.)
+    .map(|element| {
+        format!(
+            "{} {:+}%",
+            element_name(*element),
+            (player_stats.element_damage_percent[*element as usize] * 100.).round() as i32
+        )
+    })
+    .collect();
+
+    if player_stats.mana_cost_mult != 1. {
+        bonuses.push(format!("мана x{:.2}", player_stats.mana_cost_mult));
+    }
+    if player_stats.cooldown_mult != 1. {
+        bonuses.push(format!("перезарядка x{:.2}", player_stats.cooldown_mult));
+    }
+    if equipped_wand.0 != WandType::Twig {
+        bonuses.push(format!("посох: {}", equipped_wand.0.name().to_lowercase()));
+    }
+
+    let bonuses = if bonuses.is_empty() {
+        "Бонусов пока нет".to_string()
+    } else {
+        format!("Бонусы: {}", bonuses.join(", "))
+    };
+
+    let mut spells = spell_pool.unlocked.clone();
+    spells.sort();
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(4.),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::hsla(0., 0., 0., 0.85)),
+            z_index: ZIndex::Global(10),
+            ..default()
+        })
+        .insert(SpellbookUI)
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Книга заклинаний",
+                text_style(28., Color::WHITE),
+            ));
+
+            parent.spawn(TextBundle::from_section(
+                bonuses,
+                text_style(14., Color::srgb(1.0, 0.85, 0.5)),
+            ));
+
+            //заклинаний много, поэтому в несколько столбцов
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(90.),
+                        flex_direction: FlexDirection::Row,
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::Center,
+                        row_gap: Val::Px(4.),
+                        column_gap: Val::Px(4.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for spell in spells.iter() {
+                        let cost = mana.cost_of(*spell, &player_stats).ceil() as i32;
+                        let cooldown = spell.cooldown() * player_stats.cooldown_mult;
+
+                        let mut info = if spell.is_channelled() {
+                            format!(
+                                "мана {} + {} в секунду",
+                                cost,
+                                (spell.channel_drain() * player_stats.mana_cost_mult).ceil() as i32
+                            )
+                        } else {
+                            format!("мана {}", cost)
+                        };
+                        if cooldown > 0. {
+                            info += &format!(", перезарядка {:.1} с", cooldown);
+                        }
+                        if let Some(element) = damage_element(*spell) {
+                            info += &format!(
+                                ", урон {} за элемент",
+                                (player_stats.get_bonused_damage(element) as f32
+                                    * equipped_wand.damage_mult(element))
+                                    as u32
+                            );
+                        }
+
+                        parent
+                            .spawn((
+                                ImageBundle {
+                                    style: Style {
+                                        width: Val::Px(400.),
+                                        padding: UiRect::all(Val::Px(6.)),
+                                        column_gap: Val::Px(8.),
+                                        flex_direction: FlexDirection::Row,
+                                        align_items: AlignItems::Center,
+                                        ..default()
+                                    },
+                                    image: UiImage::new(
+                                        asset_server.load("textures/ui/button.png"),
+                                    ),
+                                    ..default()
+                                },
+                                ImageScaleMode::Sliced(slicer.clone()),
+                            ))
+                            .with_children(|parent| {
+                                parent
+                                    .spawn(NodeBundle {
+                                        style: Style {
+                                            width: Val::Px(7. * 16.),
+                                            flex_direction: FlexDirection::Row,
+                                            ..default()
+                                        },
+                                        ..default()
+                                    })
+                                    .with_children(|parent| {
+                                        for token in spell.recipe().iter() {
+                                            parent.spawn(ImageBundle {
+                                                style: Style {
+                                                    width: Val::Px(16.),
+                                                    height: Val::Px(16.),
+                                                    ..default()
+                                                },
+                                                image: UiImage::new(asset_server.load(format!(
+                                                    "textures/{}",
+                                                    recipe_icon(token)
+                                                ))),
+                                                ..default()
+                                            });
+                                        }
+                                    });
+
+                                parent
+                                    .spawn(NodeBundle {
+                                        style: Style {
+                                            flex_direction: FlexDirection::Column,
+                                            ..default()
+                                        },
+                                        ..default()
+                                    })
+                                    .with_children(|parent| {
+                                        parent.spawn(TextBundle::from_section(
+                                            spell.name(),
+                                            text_style(18., Color::BLACK),
+                                        ));
+                                        parent.spawn(TextBundle::from_section(
+                                            spell.scaling(),
+                                            text_style(12., Color::srgb(0.2, 0.2, 0.2)),
+                                        ));
+                                        parent.spawn(TextBundle::from_section(
+                                            info,
+                                            text_style(12., Color::srgb(0.2, 0.2, 0.2)),
+                                        ));
+                                    });
+                            });
+                    }
+                });
+
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(96.),
+                            height: Val::Px(32.),
+                            margin: UiRect::top(Val::Px(8.)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        image: UiImage::new(asset_server.load("textures/ui/button.png")),
+                        ..default()
+                    },
+                    ImageScaleMode::Sliced(slicer.clone()),
+                    SpellbookCloseButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Назад",
+                        text_style(18., Color::BLACK),
+                    ));
+                });
+        });
+}
+
+//закрывается кнопкой "Назад" или вместе с паузой
+fn close_spellbook(
+    mut commands: Commands,
+    button_query: Query<&Interaction, (Changed<Interaction>, With<SpellbookCloseButton>)>,
+    spellbook_query: Query<Entity, With<SpellbookUI>>,
+    time: Res<Time<Virtual>>,
+) {
+    let pressed = button_query.iter().any(|i| *i == Interaction::Pressed);
+
+    if pressed || !time.is_paused() {
+        for entity in spellbook_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use bevy::prelude::*;
+
+use crate::{
+    friend::SummonCommand,
+    health::Health,
+    mobs::{mob_type_to_tag_convert, MobType, Orbital, SummonQueue},
+    player::Player,
+    utils::despawn_all_with,
+    GameState,
+};
+
+pub struct SummonUIPlugin;
+
+impl Plugin for SummonUIPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(GameState::MainMenu), spawn_ui)
+            .add_systems(OnEnter(GameState::GameOver), despawn_all_with::<SummonUI>)
+            .add_systems(
+                Update,
+                (rebuild_summon_rows, update_summon_rows, update_header)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+#[derive(Component)]
+pub struct SummonUI;
+
+#[derive(Component)]
+struct SummonHeader;
+
+#[derive(Component)]
+struct SummonRowList;
+
+//полоски одного призванного, хранят его entity
+#[derive(Component)]
+struct SummonHPBar(Entity);
+
+#[derive(Component)]
+struct SummonLifetimeBar(Entity);
+
+const ROW_BAR_WIDTH: f32 = 64.;
+
+fn spawn_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(8.),
+                bottom: Val::Px(8.),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(SummonUI)
+        .with_children(|parent| {
+            parent
+                .spawn(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("fonts/ebbe_bold.ttf"),
+                        font_size: 12.0,
+                        color: Color::WHITE,
+                    },
+                ))
+                .insert(SummonHeader);
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(2.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(SummonRowList);
+        });
+}
+
+//строки пересобираются, когда меняется состав призванных игрока
+fn rebuild_summon_rows(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    queue_query: Query<&SummonQueue, With<Player>>,
+    list_query: Query<Entity, With<SummonRowList>>,
+    mob_type_query: Query<(&MobType, Has<Orbital>)>,
+    mut shown: Local<Vec<Entity>>,
+) {
+    let (Ok(summon_queue), Ok(list_e)) = (queue_query.get_single(), list_query.get_single()) else {
+        return;
+    };
+
+    //очередь может ссылаться на еще не заспавненных или уже мертвых
+    let summons: Vec<Entity> = summon_queue
+        .queue
+        .iter()
+        .filter_map(|unit| unit.entity)
+        .filter(|summon_e| mob_type_query.contains(*summon_e))
+        .collect();
+
+    if *shown == summons {
+        return;
+    }
+
+    commands.entity(list_e).despawn_descendants();
+
+    for summon_e in summons.iter().copied() {
+        let Ok((mob_type, has_lifetime)) = mob_type_query.get(summon_e) else {
+            continue;
+        };
+
+        let portrait = format!(
+            "textures/ui/mob_portraits/{}",
+            mob_type_to_tag_convert(mob_type.clone())
+        );
+
+        commands.entity(list_e).with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(4.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(ImageBundle {
+                        image: UiImage::new(asset_server.load(portrait)),
+                        style: Style {
+                            width: Val::Px(16.),
+                            height: Val::Px(16.),
+                            ..default()
+                        },
+                        ..default()
+                    });
+
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                row_gap: Val::Px(1.),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            spawn_row_bar(parent, Color::hsl(0.0, 1.0, 0.4), SummonHPBar(summon_e));
+
+                            //время жизни есть только у орбитальных
+                            if has_lifetime {
+                                spawn_row_bar(
+                                    parent,
+                                    Color::hsl(200.0, 0.8, 0.6),
+                                    SummonLifetimeBar(summon_e),
+                                );
+                            }
+                        });
+                });
+        });
+    }
+
+    *shown = summons;
+}
+
+fn spawn_row_bar(parent: &mut ChildBuilder, color: Color, marker: impl Component) {
+    parent
+        .spawn(ImageBundle { // фон полоски
+            image: UiImage::solid_color(Color::hsl(0.0, 0.0, 0.1)),
+            style: Style {
+                width: Val::Px(ROW_BAR_WIDTH),
+                height: Val::Px(4.),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn(ImageBundle {
+                    image: UiImage::solid_color(color),
+                    style: Style {
+                        width: Val::Percent(100.),
+                        height: Val::Percent(100.),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(marker);
+        });
+}
+
+fn update_summon_rows(
+    mut hp_bar_query: Query<(&mut Style, &SummonHPBar), Without<SummonLifetimeBar>>,
+    mut lifetime_bar_query: Query<(&mut Style, &SummonLifetimeBar), Without<SummonHPBar>>,
+    health_query: Query<&Health>,
+    orbital_query: Query<&Orbital>,
+) {
+    for (mut style, bar) in hp_bar_query.iter_mut() {
+        if let Ok(health) = health_query.get(bar.0) {
+            style.width = Val::Percent(health.current.max(0) as f32 / health.max as f32 * 100.);
+        }
+    }
+
+    for (mut style, bar) in lifetime_bar_query.iter_mut() {
+        if let Ok(orbital) = orbital_query.get(bar.0) {
+            style.width = Val::Percent(if orbital.is_eternal {
+                100.
+            } else {
+                orbital.time_to_live.fraction_remaining() * 100.
+            });
+        }
+    }
+}
+
+fn update_header(
+    mut header_query: Query<&mut Text, With<SummonHeader>>,
+    queue_query: Query<&SummonQueue, With<Player>>,
+    command: Res<SummonCommand>,
+) {
+    let (Ok(mut text), Ok(summon_queue)) = (header_query.get_single_mut(), queue_query.get_single())
+    else {
+        return;
+    };
+
+    let value = if summon_queue.amount_of_mobs == 0 {
+        "".to_string()
+    } else {
+        format!(
+            "Призванные {}/{} - {} [F/G/H/R]",
+            summon_queue.amount_of_mobs,
+            summon_queue.max_amount,
+            command.label()
+        )
+    };
+
+    if text.sections[0].value != value {
+        text.sections[0].value = value;
+    }
+}
@@ -1,8 +1,19 @@
+use std::f32::consts::PI;
+
+use avian2d::prelude::*;
 use bevy::prelude::*;
+use rand::{distributions::Standard, prelude::Distribution, Rng};
 
+use crate::audio::PlayAudioEvent;
+use crate::camera::YSort;
+use crate::channel::Channeling;
+use crate::elements::{ElementBar, ElementBarClear, ElementType, SpellCharge};
+use crate::experience::{ExpGained, PlayerExperience};
 use crate::item::ItemPickupAnimation;
 use crate::mouse_position::MouseCoords;
 use crate::player::Player;
+use crate::projectile::{ProjectileModifier, SpawnProjectileEvent, Trajectory};
+use crate::utils::despawn_all_with;
 use crate::GameState;
 
 pub struct WandPlugin;
@@ -10,20 +21,265 @@ pub struct WandPlugin;
 impl Plugin for WandPlugin {
     fn build(&self, app: &mut App) {
         app
+            .add_event::<SpawnWandEvent>()
+            .init_resource::<EquippedWand>()
+            .add_systems(OnExit(GameState::MainMenu), init_wand)
             .add_systems(OnEnter(GameState::InGame), spawn_wand)
-            .add_systems(FixedUpdate, move_rotate_wand);
+            .add_systems(OnExit(GameState::InGame), despawn_all_with::<WandPickup>)
+            .add_systems(OnExit(GameState::Hub), despawn_all_with::<WandPickup>)
+            .add_systems(FixedUpdate, move_rotate_wand)
+            .add_systems(Update, (glow_wand, apply_wand, spawn_wand_pickup))
+            .add_systems(
+                Update,
+                (swap_wand, update_wand_hints)
+                    .run_if(in_state(GameState::InGame).or_else(in_state(GameState::Hub))),
+            );
     }
 }
 
 #[derive(Component)]
 pub struct Wand;
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum WandType {
+    #[default]
+    Twig,
+    EmberStaff,
+    StoneRod,
+    FeatherWand,
+    CrystalWand,
+    BoneWand,
+}
+
+impl Distribution<WandType> for Standard {
+    //палка есть у всех с начала забега, поэтому не выпадает
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> WandType {
+        match rng.gen_range(0..5) {
+            0 => WandType::EmberStaff,
+            1 => WandType::StoneRod,
+            2 => WandType::FeatherWand,
+            3 => WandType::CrystalWand,
+            _ => WandType::BoneWand,
+        }
+    }
+}
+
+//особое свойство посоха, срабатывает на снарядах своей стихии
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WandTrait {
+    None,
+    Ignite, //огненные снаряды поджигают цель
+    Pierce, //земляные пробивают на одну цель больше
+    Homing, //воздушные доворачивают к врагам
+}
+
+pub struct WandStats {
+    pub damage_mult: f32,
+    pub speed_mult: f32,
+    pub spread_mult: f32, //меньше 1 - кучнее
+    pub bar_bonus: i8,    //ячейки полоски элементов сверх уровня
+    pub affinity: Option<ElementType>,
+    pub special: WandTrait,
+}
+
+//урон заклинаний родной стихии посоха
+const AFFINITY_BONUS: f32 = 1.25;
+
+impl WandType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WandType::Twig => "Палка",
+            WandType::EmberStaff => "Тлеющий посох",
+            WandType::StoneRod => "Каменный жезл",
+            WandType::FeatherWand => "Перьевая палочка",
+            WandType::CrystalWand => "Хрустальный жезл",
+            WandType::BoneWand => "Костяной посох",
+        }
+    }
+
+    pub fn texture(&self) -> &'static str {
+        match self {
+            WandType::Twig => "textures/wand.png",
+            WandType::EmberStaff => "textures/wands/ember_staff.png",
+            WandType::StoneRod => "textures/wands/stone_rod.png",
+            WandType::FeatherWand => "textures/wands/feather_wand.png",
+            WandType::CrystalWand => "textures/wands/crystal_wand.png",
+            WandType::BoneWand => "textures/wands/bone_wand.png",
+        }
+    }
+
+    pub fn stats(&self) -> WandStats {
+        match self {
+            WandType::Twig => WandStats {
+                damage_mult: 1.,
+                speed_mult: 1.,
+                spread_mult: 1.,
+                bar_bonus: 0,
+                affinity: None,
+                special: WandTrait::None,
+            },
+            WandType::EmberStaff => WandStats {
+                damage_mult: 1.,
+                speed_mult: 1.1,
+                spread_mult: 1.,
+                bar_bonus: 0,
+                affinity: Some(ElementType::Fire),
+                special: WandTrait::Ignite,
+            },
+            WandType::StoneRod => WandStats {
+                damage_mult: 1.3,
+                speed_mult: 0.8,
+                spread_mult: 0.7,
+                bar_bonus: 0,
+                affinity: Some(ElementType::Earth),
+                special: WandTrait::Pierce,
+            },
+            WandType::FeatherWand => WandStats {
+                damage_mult: 0.9,
+                speed_mult: 1.4,
+                spread_mult: 1.3,
+                bar_bonus: 1,
+                affinity: Some(ElementType::Air),
+                special: WandTrait::Homing,
+            },
+            WandType::CrystalWand => WandStats {
+                damage_mult: 0.9,
+                speed_mult: 1.,
+                spread_mult: 0.5,
+                bar_bonus: 2,
+                affinity: Some(ElementType::Water),
+                special: WandTrait::None,
+            },
+            WandType::BoneWand => WandStats {
+                damage_mult: 1.5,
+                speed_mult: 1.,
+                spread_mult: 1.2,
+                bar_bonus: -1,
+                affinity: None,
+                special: WandTrait::None,
+            },
+        }
+    }
+
+    //цена в хабе, в опыте
+    pub fn price(&self) -> u32 {
+        match self {
+            WandType::Twig => 0,
+            WandType::EmberStaff | WandType::StoneRod => 60,
+            WandType::FeatherWand | WandType::BoneWand => 80,
+            WandType::CrystalWand => 100,
+        }
+    }
+
+    //для подсказки над лежащим посохом
+    pub fn description(&self) -> String {
+        let stats = self.stats();
+        let mut lines = vec![];
+
+        if stats.damage_mult != 1. {
+            lines.push(format!("урон x{:.1}", stats.damage_mult));
+        }
+        if stats.speed_mult != 1. {
+            lines.push(format!("скорость снарядов x{:.1}", stats.speed_mult));
+        }
+        if stats.spread_mult != 1. {
+            lines.push(format!("разброс x{:.1}", stats.spread_mult));
+        }
+        if stats.bar_bonus != 0 {
+            lines.push(format!("ячейки элементов {:+}", stats.bar_bonus));
+        }
+        if let Some(element) = stats.affinity {
+            let element = match element {
+                ElementType::Fire => "огня",
+                ElementType::Water => "воды",
+                ElementType::Earth => "земли",
+                ElementType::Air => "воздуха",
+                ElementType::Steam => "пара",
+            };
+            lines.push(format!("заклинания {} сильнее", element));
+        }
+        match stats.special {
+            WandTrait::Ignite => lines.push("огонь поджигает врагов".to_string()),
+            WandTrait::Pierce => lines.push("земля пробивает на одну цель больше".to_string()),
+            WandTrait::Homing => lines.push("воздух летит за врагами".to_string()),
+            WandTrait::None => {}
+        }
+
+        lines.join("\n")
+    }
+}
+
+//посох в руках, один на забег
+#[derive(Resource, Default)]
+pub struct EquippedWand(pub WandType);
+
+impl EquippedWand {
+    pub fn damage_mult(&self, element: ElementType) -> f32 {
+        let stats = self.0.stats();
+
+        if stats.affinity == Some(element) {
+            stats.damage_mult * AFFINITY_BONUS
+        } else {
+            stats.damage_mult
+        }
+    }
+
+    //урон уже учтен при выборе заклинания, здесь скорость, разброс и свойство посоха.
+    //aim - направление прицела, если снаряды летят конусом
+    pub fn apply(&self, ev: &mut SpawnProjectileEvent, aim: Option<f32>) {
+        let stats = self.0.stats();
+
+        ev.speed *= stats.speed_mult;
+
+        if let Some(aim) = aim {
+            let offset = (ev.angle - aim + PI).rem_euclid(2. * PI) - PI;
+            ev.angle = aim + offset * stats.spread_mult;
+        }
+
+        match stats.special {
+            WandTrait::Ignite if ev.element == ElementType::Fire => {
+                ev.modifiers.push(ProjectileModifier::Igniting {
+                    damage: (ev.damage / 4).max(1),
+                    duration: 2.,
+                });
+            }
+            WandTrait::Pierce if ev.element == ElementType::Earth => {
+                let piercing = ev.modifiers.iter_mut().find_map(|modifier| match modifier {
+                    ProjectileModifier::Piercing { targets } => Some(targets),
+                    _ => None,
+                });
+
+                match piercing {
+                    Some(targets) => *targets += 1,
+                    None => ev
+                        .modifiers
+                        .push(ProjectileModifier::Piercing { targets: 1 }),
+                }
+            }
+            //воздушный снаряд и молния летят прямо, кривые траектории не трогаем
+            WandTrait::Homing
+                if ev.element == ElementType::Air
+                    && matches!(ev.trajectory, Trajectory::Straight) =>
+            {
+                ev.modifiers
+                    .push(ProjectileModifier::Homing { turn_rate: 3. });
+            }
+            _ => {}
+        }
+    }
+}
+
+fn init_wand(mut commands: Commands) {
+    commands.insert_resource(EquippedWand::default());
+}
+
 fn spawn_wand( // спавним палку
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    equipped: Res<EquippedWand>,
 ) {
     commands.spawn(SpriteBundle {
-        texture: asset_server.load("textures/wand.png"),
+        texture: asset_server.load(equipped.0.texture()),
         ..default()
     }).insert(Wand);
 }
@@ -58,3 +314,240 @@ fn move_rotate_wand(
     }
 }
 
+// палка светится цветом стихии, пока копится заряд или идет поток
+fn glow_wand(
+    mut wand_query: Query<(&mut Sprite, &mut Transform), With<Wand>>,
+    channel_query: Query<&Channeling, With<Player>>,
+    charge: Res<SpellCharge>,
+    time: Res<Time>,
+) {
+    let Ok((mut sprite, mut transform)) = wand_query.get_single_mut() else {
+        return;
+    };
+
+    let glow = if let Ok(channel) = channel_query.get_single() {
+        Some((channel.element, 1.))
+    } else {
+        charge.charging.map(|element| (element, charge.fraction()))
+    };
+
+    let Some((element, fraction)) = glow else {
+        sprite.color = Color::WHITE;
+        transform.scale = Vec3::ONE;
+        return;
+    };
+
+    // на полном заряде палка пульсирует
+    let pulse = if fraction >= 1. {
+        (time.elapsed_seconds() * 20.).sin() * 0.05
+    } else {
+        0.
+    };
+
+    sprite.color = Color::WHITE.mix(&element.color(), fraction);
+    transform.scale = Vec3::splat(1. + 0.25 * fraction + pulse);
+}
+
+// посох сменился - меняем спрайт и размер полоски элементов
+fn apply_wand(
+    mut wand_query: Query<&mut Handle<Image>, With<Wand>>,
+    mut element_bar: ResMut<ElementBar>,
+    mut ev_bar_clear: EventWriter<ElementBarClear>,
+    equipped: Res<EquippedWand>,
+    asset_server: Res<AssetServer>,
+) {
+    if !equipped.is_changed() {
+        return;
+    }
+
+    for mut texture in wand_query.iter_mut() {
+        *texture = asset_server.load(equipped.0.texture());
+    }
+
+    let bonus = equipped.0.stats().bar_bonus;
+    if element_bar.bonus != bonus {
+        // набранное могло не влезть в новую полоску
+        element_bar.bonus = bonus;
+        element_bar.clear();
+        ev_bar_clear.send(ElementBarClear);
+    }
+}
+
+//посох на полу: выпал с врага (бесплатно) или продается в хабе
+#[derive(Component)]
+pub struct WandPickup {
+    pub wand_type: WandType,
+    pub price: u32,
+}
+
+#[derive(Component)]
+struct WandHint;
+
+#[derive(Event)]
+pub struct SpawnWandEvent {
+    pub pos: Vec3,
+    pub wand_type: WandType,
+    pub price: u32,
+}
+
+const WAND_PICKUP_KEY: KeyCode = KeyCode::KeyE;
+const WAND_PICKUP_RADIUS: f32 = 24.;
+
+fn spawn_wand_pickup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut ev_spawn_wand: EventReader<SpawnWandEvent>,
+) {
+    for ev in ev_spawn_wand.read() {
+        let action = if ev.price > 0 {
+            format!("[E] купить за {} опыта", ev.price)
+        } else {
+            "[E] взять".to_string()
+        };
+
+        commands
+            .spawn(SpriteBundle {
+                texture: asset_server.load(ev.wand_type.texture()),
+                transform: Transform::from_translation(ev.pos),
+                ..default()
+            })
+            .insert(Collider::circle(8.))
+            .insert(Sensor)
+            .insert(YSort(8.))
+            .insert(WandPickup {
+                wand_type: ev.wand_type,
+                price: ev.price,
+            })
+            .with_children(|parent| {
+                parent
+                    .spawn(Text2dBundle {
+                        text: Text::from_sections([
+                            TextSection::new(
+                                format!("{}\n", ev.wand_type.name()),
+                                TextStyle {
+                                    font: asset_server.load("fonts/ebbe_bold.ttf"),
+                                    font_size: 24.,
+                                    color: Color::hsl(40., 1., 0.5),
+                                },
+                            ),
+                            TextSection::new(
+                                format!("{}\n", ev.wand_type.description()),
+                                TextStyle {
+                                    font: asset_server.load("fonts/ebbe_bold.ttf"),
+                                    font_size: 16.,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                            TextSection::new(
+                                action,
+                                TextStyle {
+                                    font: asset_server.load("fonts/ebbe_bold.ttf"),
+                                    font_size: 16.,
+                                    color: Color::srgb(0.6, 1., 0.6),
+                                },
+                            ),
+                        ])
+                        .with_justify(JustifyText::Center),
+                        text_anchor: bevy::sprite::Anchor::BottomCenter,
+                        transform: Transform {
+                            translation: Vec3::new(0., 12., 9.),
+                            scale: Vec3::splat(0.5),
+                            ..default()
+                        },
+                        visibility: Visibility::Hidden,
+                        ..default()
+                    })
+                    .insert(WandHint);
+            });
+    }
+}
+
+fn nearest_pickup<'a>(
+    pickups: impl Iterator<Item = (Entity, &'a Transform, &'a WandPickup)>,
+    player_pos: Vec2,
+) -> Option<(Entity, &'a Transform, &'a WandPickup)> {
+    pickups
+        .filter(|(_, t, _)| t.translation.truncate().distance(player_pos) <= WAND_PICKUP_RADIUS)
+        .min_by(|(_, a, _), (_, b, _)| {
+            a.translation
+                .truncate()
+                .distance_squared(player_pos)
+                .total_cmp(&b.translation.truncate().distance_squared(player_pos))
+        })
+}
+
+//подсказка только у ближайшего посоха в радиусе
+fn update_wand_hints(
+    pickup_query: Query<(Entity, &Transform, &WandPickup, &Children)>,
+    mut hint_query: Query<&mut Visibility, With<WandHint>>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let nearest = player_query.get_single().ok().and_then(|player_transform| {
+        nearest_pickup(
+            pickup_query.iter().map(|(e, t, p, _)| (e, t, p)),
+            player_transform.translation.truncate(),
+        )
+        .map(|(e, _, _)| e)
+    });
+
+    for (pickup_e, _, _, children) in pickup_query.iter() {
+        for child in children.iter() {
+            if let Ok(mut visibility) = hint_query.get_mut(*child) {
+                *visibility = if Some(pickup_e) == nearest {
+                    Visibility::Visible
+                } else {
+                    Visibility::Hidden
+                };
+            }
+        }
+    }
+}
+
+//берем посох, старый остается лежать на его месте уже бесплатно
+fn swap_wand(
+    mut commands: Commands,
+    pickup_query: Query<(Entity, &Transform, &WandPickup)>,
+    player_query: Query<&Transform, (With<Player>, Without<ItemPickupAnimation>)>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut equipped: ResMut<EquippedWand>,
+    mut player_experience: ResMut<PlayerExperience>,
+    mut ev_exp_gained: EventWriter<ExpGained>,
+    mut ev_spawn_wand: EventWriter<SpawnWandEvent>,
+    mut ev_play_audio: EventWriter<PlayAudioEvent>,
+    time: Res<Time<Virtual>>,
+) {
+    if !keyboard.just_pressed(WAND_PICKUP_KEY) || time.is_paused() {
+        return;
+    }
+
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    let Some((pickup_e, pickup_transform, pickup)) =
+        nearest_pickup(pickup_query.iter(), player_transform.translation.truncate())
+    else {
+        return;
+    };
+
+    if player_experience.current < pickup.price {
+        ev_play_audio.send(PlayAudioEvent::from_file("tick.ogg"));
+        return;
+    }
+
+    if pickup.price > 0 {
+        player_experience.current -= pickup.price;
+        ev_exp_gained.send(ExpGained);
+    }
+
+    ev_spawn_wand.send(SpawnWandEvent {
+        pos: pickup_transform.translation,
+        wand_type: equipped.0,
+        price: 0,
+    });
+
+    equipped.0 = pickup.wand_type;
+    commands.entity(pickup_e).despawn_recursive();
+
+    ev_play_audio.send(PlayAudioEvent::from_file("item.ogg"));
+}